@@ -0,0 +1,285 @@
+use super::install::InstallLink;
+use super::transaction::Transaction;
+use super::{ManagerScope, Systemd};
+use crate::Result;
+use crate::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+
+/// How the unit file changed when a configuration was applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileChange {
+    Created,
+    Updated,
+    Unchanged,
+}
+
+impl fmt::Display for FileChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FileChange::Created => write!(f, "created"),
+            FileChange::Updated => write!(f, "updated"),
+            FileChange::Unchanged => write!(f, "unchanged"),
+        }
+    }
+}
+
+/// Options of [`Systemd::apply`].
+#[derive(Debug, Clone)]
+pub struct ApplyOptions {
+    /// Create the missing `[Install]` symlinks of the unit. Enabled by default.
+    pub enable: bool,
+    /// Restart the unit when its unit file was created or updated, so the new configuration
+    /// takes effect. A unit that is not running is started.
+    pub restart: bool,
+}
+
+impl Default for ApplyOptions {
+    fn default() -> Self {
+        Self {
+            enable: true,
+            restart: false,
+        }
+    }
+}
+
+impl ApplyOptions {
+    pub fn enable(mut self, value: bool) -> Self {
+        self.enable = value;
+        self
+    }
+
+    pub fn restart(mut self, value: bool) -> Self {
+        self.restart = value;
+        self
+    }
+}
+
+/// Changes made by [`Systemd::apply`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApplyReport {
+    pub unit_file: FileChange,
+    /// Symlinks created to enable the unit.
+    pub enabled: Vec<InstallLink>,
+    /// Whether the service manager reloaded its unit files.
+    pub reloaded: bool,
+    /// Whether the unit was restarted.
+    pub restarted: bool,
+}
+
+impl ApplyReport {
+    /// Returns true if anything was changed.
+    pub fn is_changed(&self) -> bool {
+        self.unit_file != FileChange::Unchanged || !self.enabled.is_empty() || self.restarted
+    }
+}
+
+impl fmt::Display for ApplyReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unit file {}", self.unit_file)?;
+        if !self.enabled.is_empty() {
+            write!(f, ", {} symlink(s) created", self.enabled.len())?;
+        }
+        if self.reloaded {
+            write!(f, ", reloaded")?;
+        }
+        if self.restarted {
+            write!(f, ", restarted")?;
+        }
+        Ok(())
+    }
+}
+
+impl Systemd {
+    /// Brings the installed unit in line with the configuration, and can be called on every deploy.
+    ///
+    /// The unit file is only rewritten if the rendered configuration differs from it, and the
+    /// service manager is only reloaded if the unit file or its symlinks changed.
+    /// Unlike [`Systemd::install`], an existing unit file is not an error.
+    ///
+    /// The unit file is replaced atomically, its previous contents are kept as `<unit>.bak`
+    /// until the apply succeeded. If enabling, reloading or restarting fails, the previous
+    /// unit file is restored, created symlinks are removed and the service manager is reloaded
+    /// again. If restarting with an updated unit file fails, the unit is restarted once more
+    /// with the restored one, so it keeps running the previous configuration.
+    pub fn apply(&self, options: &ApplyOptions) -> Result<ApplyReport> {
+        self.validate()?;
+
+        let mut transaction = Transaction::default();
+        let report = match self.apply_steps(options, &mut transaction) {
+            Ok(report) => report,
+            Err(e) => return Err(self.roll_back(transaction, e)),
+        };
+        if report.restarted
+            && let Err(e) = self.restart()
+        {
+            let error = self.roll_back(transaction, e);
+            if report.unit_file != FileChange::Updated {
+                return Err(error);
+            }
+            return Err(match self.restart() {
+                Ok(()) => error,
+                Err(e) => Error::CommandError(format!(
+                    "{}; restarting with the previous unit file failed: {}",
+                    error, e
+                )),
+            });
+        }
+        transaction.commit()?;
+        Ok(report)
+    }
+
+    fn apply_steps(
+        &self,
+        options: &ApplyOptions,
+        transaction: &mut Transaction,
+    ) -> Result<ApplyReport> {
+        let dst = self.config_path()?;
+        let unit_file = match fs::read_to_string(&dst) {
+            Ok(installed) if installed == self.config.to_string() => FileChange::Unchanged,
+            Ok(_) => FileChange::Updated,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => FileChange::Created,
+            Err(e) => return Err(e.into()),
+        };
+        if unit_file != FileChange::Unchanged {
+            self.write_unit_file(transaction, &dst)?;
+        }
+
+        let enabled = if options.enable {
+            self.enable()?
+        } else {
+            Vec::new()
+        };
+        transaction.linked(enabled.clone());
+
+        // Without a running manager there is nothing to reload or restart
+        let live = self.root.is_none() && self.scope != ManagerScope::Global;
+        if live && self.linger && self.scope == ManagerScope::User {
+            self.enable_linger()?;
+        }
+        let reloaded = live && (unit_file != FileChange::Unchanged || !enabled.is_empty());
+        if reloaded {
+            self.daemon_reload()?;
+        }
+        // The restart itself is done by `apply`, which also undoes it if it fails
+        let restarted = live && options.restart && unit_file != FileChange::Unchanged;
+
+        Ok(ApplyReport {
+            unit_file,
+            enabled,
+            reloaded,
+            restarted,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systemd::testing::TempDir;
+    use crate::systemd::{CommandOutput, Config, Install, MockRunner, Service};
+    use std::sync::Arc;
+
+    fn ok() -> CommandOutput {
+        CommandOutput::success("")
+    }
+
+    fn config(command: &str) -> Config {
+        Config::new("demo")
+            .service(Service {
+                exec_start: Some(vec![command.to_string()]),
+                ..Default::default()
+            })
+            .install(Install::default().wanted_by(vec!["multi-user.target"]))
+    }
+
+    fn systemd(dir: &TempDir, runner: &Arc<MockRunner>, config: Config) -> Systemd {
+        Systemd::new(config)
+            .runner(Arc::clone(runner))
+            .with_unit_dir(dir)
+    }
+
+    #[test]
+    fn created_then_unchanged() {
+        let dir = TempDir::new();
+        let runner = Arc::new(MockRunner::new().expect("systemctl", &["daemon-reload"], ok()));
+        let systemd = systemd(&dir, &runner, config("/usr/bin/demo"));
+
+        let report = systemd.apply(&ApplyOptions::default()).unwrap();
+        assert_eq!(report.unit_file, FileChange::Created);
+        assert_eq!(
+            report.enabled,
+            [InstallLink {
+                path: dir.join("multi-user.target.wants/demo.service"),
+                target: dir.join("demo.service"),
+            }]
+        );
+        assert!(report.reloaded);
+        runner.assert_done();
+
+        // Nothing changed, so the manager is neither reloaded nor restarted
+        let options = ApplyOptions::default().restart(true);
+        let report = systemd.apply(&options).unwrap();
+        assert_eq!(report.unit_file, FileChange::Unchanged);
+        assert!(!report.is_changed());
+        assert!(!report.reloaded);
+        assert_eq!(runner.calls().len(), 1);
+    }
+
+    #[test]
+    fn updated_restarts_and_drops_backup() {
+        let dir = TempDir::new();
+        fs::write(dir.join("demo.service"), config("/usr/bin/old").to_string()).unwrap();
+        let runner = Arc::new(
+            MockRunner::new()
+                .expect("systemctl", &["daemon-reload"], ok())
+                .expect("systemctl", &["restart", "demo.service"], ok()),
+        );
+        let config = config("/usr/bin/new");
+
+        let report = systemd(&dir, &runner, config.clone())
+            .apply(&ApplyOptions::default().restart(true))
+            .unwrap();
+
+        runner.assert_done();
+        assert_eq!(report.unit_file, FileChange::Updated);
+        assert!(report.reloaded);
+        assert!(report.restarted);
+        assert_eq!(
+            fs::read_to_string(dir.join("demo.service")).unwrap(),
+            config.to_string()
+        );
+        assert!(!dir.join("demo.service.bak").exists());
+    }
+
+    #[test]
+    fn failed_restart_restarts_previous_configuration() {
+        let dir = TempDir::new();
+        let previous = config("/usr/bin/old").to_string();
+        fs::write(dir.join("demo.service"), &previous).unwrap();
+        let runner = Arc::new(
+            MockRunner::new()
+                .expect("systemctl", &["daemon-reload"], ok())
+                .expect(
+                    "systemctl",
+                    &["restart", "demo.service"],
+                    CommandOutput::failure(1, "Job for demo.service failed."),
+                )
+                .expect("systemctl", &["daemon-reload"], ok())
+                .expect("systemctl", &["restart", "demo.service"], ok()),
+        );
+
+        let result = systemd(&dir, &runner, config("/usr/bin/new"))
+            .apply(&ApplyOptions::default().restart(true));
+
+        runner.assert_done();
+        assert!(matches!(result, Err(Error::CommandError(_))));
+        assert_eq!(
+            fs::read_to_string(dir.join("demo.service")).unwrap(),
+            previous
+        );
+        assert!(!dir.join("multi-user.target.wants/demo.service").exists());
+        assert!(!dir.join("demo.service.bak").exists());
+    }
+}
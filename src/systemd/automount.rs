@@ -0,0 +1,163 @@
+use super::install::Install;
+use super::unit::Unit;
+use super::unit_file::escape_path;
+use crate::error::Error;
+use std::fmt;
+use std::path::PathBuf;
+
+/// Automount section options
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.automount.html#Options
+#[derive(Debug, Clone, Default)]
+pub struct Automount {
+    /// Absolute path of the automount point. The unit name is derived from it.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.automount.html#Where=
+    pub where_: PathBuf,
+
+    /// Extra mount options for the autofs mount point.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.automount.html#ExtraOptions=
+    pub extra_options: Option<Vec<String>>,
+
+    /// Access mode of automatically created automount point directories.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.automount.html#DirectoryMode=
+    pub directory_mode: Option<u32>,
+
+    /// Idle time after which the mount point is unmounted.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.automount.html#TimeoutIdleSec=
+    pub timeout_idle_sec: Option<String>,
+}
+
+impl fmt::Display for Automount {
+    fn fmt(&self, buf: &mut fmt::Formatter<'_>) -> fmt::Result {
+        buf.write_str("[Automount]\n")?;
+        writeln!(buf, "Where={}", self.where_.display())?;
+        if let Some(ref options) = self.extra_options {
+            writeln!(buf, "ExtraOptions={}", options.join(","))?;
+        }
+        write_mode!(buf, self.directory_mode, "DirectoryMode");
+        write_option!(buf, self.timeout_idle_sec, "TimeoutIdleSec");
+        Ok(())
+    }
+}
+
+impl Automount {
+    /// Creates an `Automount` section for the mount point `where_`.
+    pub fn new(where_: impl Into<PathBuf>) -> Self {
+        Self {
+            where_: where_.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Validate the automount configuration according to systemd specifications
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.where_.is_absolute() {
+            return Err(format!(
+                "Automount Where= '{}' must be an absolute path",
+                self.where_.display()
+            ));
+        }
+        if let Some(mode) = self.directory_mode
+            && mode > 0o7777
+        {
+            return Err(format!("DirectoryMode {:o} is not a valid file mode", mode));
+        }
+        Ok(())
+    }
+
+    // Builder pattern setters
+    pub fn where_(mut self, value: impl Into<PathBuf>) -> Self {
+        self.where_ = value.into();
+        self
+    }
+
+    pub fn extra_options(mut self, value: Vec<impl Into<String>>) -> Self {
+        self.extra_options = Some(value.into_iter().map(|s| s.into()).collect());
+        self
+    }
+
+    pub fn directory_mode(mut self, value: u32) -> Self {
+        self.directory_mode = Some(value);
+        self
+    }
+
+    pub fn timeout_idle_sec(mut self, value: impl Into<String>) -> Self {
+        self.timeout_idle_sec = Some(value.into());
+        self
+    }
+}
+
+/// Configuration for a systemd automount unit.
+/// The unit name is derived from the mount point and must match the mount unit it activates.
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.automount.html
+#[derive(Debug, Clone)]
+pub struct AutomountConfig {
+    pub unit: Unit,
+    pub automount: Automount,
+    pub install: Install,
+}
+
+impl AutomountConfig {
+    pub fn new(automount: Automount) -> Self {
+        Self {
+            unit: Unit::default(),
+            automount,
+            install: Install::default(),
+        }
+    }
+
+    pub fn unit(mut self, unit: Unit) -> Self {
+        self.unit = unit;
+        self
+    }
+
+    pub fn automount(mut self, automount: Automount) -> Self {
+        self.automount = automount;
+        self
+    }
+
+    pub fn install(mut self, install: Install) -> Self {
+        self.install = install;
+        self
+    }
+
+    pub fn validate(&self) -> crate::Result<()> {
+        self.unit.validate().map_err(Error::ValidationError)?;
+        self.automount.validate().map_err(Error::ValidationError)?;
+        self.install.validate().map_err(Error::ValidationError)?;
+        Ok(())
+    }
+
+    pub(crate) fn unit_name(&self) -> String {
+        format!("{}.automount", escape_path(&self.automount.where_))
+    }
+}
+
+impl fmt::Display for AutomountConfig {
+    fn fmt(&self, buf: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.unit.fmt(buf)?;
+        self.automount.fmt(buf)?;
+        self.install.fmt(buf)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_automount_unit() {
+        let config = AutomountConfig::new(
+            Automount::new("/mnt/backup")
+                .extra_options(vec!["uid=1000"])
+                .timeout_idle_sec("10min"),
+        );
+
+        assert!(config.validate().is_ok());
+        assert_eq!(config.unit_name(), "mnt-backup.automount");
+        assert_eq!(
+            config.to_string(),
+            "[Unit]\n[Automount]\nWhere=/mnt/backup\nExtraOptions=uid=1000\nTimeoutIdleSec=10min\n[Install]\n"
+        );
+    }
+}
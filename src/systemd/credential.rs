@@ -0,0 +1,294 @@
+use crate::Result;
+use crate::error::Error;
+use std::fmt;
+use std::fs;
+use std::io::{self, Read};
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+
+/// Environment variable the service manager sets to the directory holding the unit's credentials.
+const CREDENTIALS_DIRECTORY: &str = "CREDENTIALS_DIRECTORY";
+
+/// Maximum length of a credential name, the file name limit of the credentials directory.
+const MAX_CREDENTIAL_NAME: usize = 255;
+
+/// A credential read from a file, a credential store or an AF_UNIX socket.
+/// Without a path, the credential of the same name passed to the service manager is used.
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#LoadCredential=ID:PATH
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadCredential {
+    pub id: String,
+    pub path: Option<PathBuf>,
+}
+
+impl fmt::Display for LoadCredential {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.path {
+            Some(ref path) => write!(f, "{}:{}", self.id, path.display()),
+            None => write!(f, "{}", self.id),
+        }
+    }
+}
+
+impl LoadCredential {
+    pub fn new(id: impl Into<String>, path: impl Into<PathBuf>) -> Self {
+        Self {
+            id: id.into(),
+            path: Some(path.into()),
+        }
+    }
+
+    /// Passes on the credential `id` the service manager itself received.
+    pub fn inherit(id: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            path: None,
+        }
+    }
+
+    pub fn validate(&self, key: &str) -> std::result::Result<(), String> {
+        validate_credential_name(&self.id, key)?;
+        if let Some(ref path) = self.path
+            && path.as_os_str().is_empty()
+        {
+            return Err(format!(
+                "{} path of credential '{}' cannot be empty",
+                key, self.id
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// A credential with literal data embedded in the unit file.
+/// For SetCredentialEncrypted= the data is the base64 output of `systemd-creds encrypt`.
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#SetCredential=ID:VALUE
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SetCredential {
+    pub id: String,
+    pub data: String,
+}
+
+impl fmt::Display for SetCredential {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:", self.id)?;
+        // The value is unescaped C-style by systemd
+        for c in self.data.chars() {
+            match c {
+                '\\' => f.write_str("\\\\")?,
+                '\n' => f.write_str("\\n")?,
+                '\t' => f.write_str("\\t")?,
+                c => write!(f, "{}", c)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl SetCredential {
+    pub fn new(id: impl Into<String>, data: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            data: data.into(),
+        }
+    }
+
+    pub fn validate(&self, key: &str) -> std::result::Result<(), String> {
+        validate_credential_name(&self.id, key)
+    }
+
+    pub(crate) fn validate_encrypted(&self, key: &str) -> std::result::Result<(), String> {
+        self.validate(key)?;
+        if self.data.is_empty()
+            || !self
+                .data
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "+/=".contains(c) || c.is_ascii_whitespace())
+        {
+            return Err(format!(
+                "{} data of credential '{}' must be base64 encoded",
+                key, self.id
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Checks a credential name: a valid file name without the `:` separator.
+pub(crate) fn validate_credential_name(id: &str, key: &str) -> std::result::Result<(), String> {
+    if id.is_empty()
+        || id == "."
+        || id == ".."
+        || id.len() > MAX_CREDENTIAL_NAME
+        || id.contains(['/', ':', '\0'])
+    {
+        return Err(format!("Invalid credential name '{}' in {}", id, key));
+    }
+    Ok(())
+}
+
+/// Checks an ImportCredential= glob, which follows the rules of credential names.
+pub(crate) fn validate_credential_glob(glob: &str) -> std::result::Result<(), String> {
+    if glob.is_empty() || glob.len() > MAX_CREDENTIAL_NAME || glob.contains(['/', '\0']) {
+        return Err(format!(
+            "Invalid credential glob '{}' in ImportCredential",
+            glob
+        ));
+    }
+    Ok(())
+}
+
+/// Credentials passed to the running service, read from `$CREDENTIALS_DIRECTORY`.
+/// Meant to be used by services built with this crate to read secrets without putting them
+/// into the environment.
+/// https://systemd.io/CREDENTIALS/
+#[derive(Debug, Clone)]
+pub struct Credentials {
+    dir: PathBuf,
+}
+
+impl Credentials {
+    /// Opens the credentials directory the service manager passed to this process.
+    pub fn from_env() -> Result<Self> {
+        match std::env::var_os(CREDENTIALS_DIRECTORY) {
+            Some(dir) if !dir.is_empty() => Self::open(dir),
+            _ => Err(Error::IoError(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{} is not set", CREDENTIALS_DIRECTORY),
+            ))),
+        }
+    }
+
+    /// Opens a credentials directory at `dir`.
+    pub fn open(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        if !dir.is_absolute() {
+            return Err(Error::ValidationError(format!(
+                "Credentials directory '{}' must be an absolute path",
+                dir.display()
+            )));
+        }
+        if !fs::metadata(&dir)?.is_dir() {
+            return Err(Error::IoError(io::Error::new(
+                io::ErrorKind::NotADirectory,
+                format!(
+                    "Credentials directory '{}' is not a directory",
+                    dir.display()
+                ),
+            )));
+        }
+        Ok(Self { dir })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the names of all credentials, sorted.
+    pub fn list(&self) -> Result<Vec<String>> {
+        let mut names = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let entry = entry?;
+            if entry.file_type()?.is_file()
+                && let Ok(name) = entry.file_name().into_string()
+            {
+                names.push(name);
+            }
+        }
+        names.sort();
+        Ok(names)
+    }
+
+    /// Reads the credential `id`.
+    ///
+    /// The name is checked so it cannot escape the credentials directory,
+    /// and symbolic links or other non-regular files are refused. The file is opened
+    /// without following symbolic links and checked through the opened handle,
+    /// so it cannot be swapped between the check and the read.
+    pub fn read(&self, id: &str) -> Result<Vec<u8>> {
+        validate_credential_name(id, CREDENTIALS_DIRECTORY).map_err(Error::ValidationError)?;
+        let not_regular = || {
+            Error::IoError(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Credential '{}' is not a regular file", id),
+            ))
+        };
+        // O_NONBLOCK keeps a FIFO from blocking the open, it is refused right after
+        let mut file = match fs::OpenOptions::new()
+            .read(true)
+            .custom_flags(libc::O_NOFOLLOW | libc::O_NONBLOCK)
+            .open(self.dir.join(id))
+        {
+            Ok(file) => file,
+            Err(e) if e.raw_os_error() == Some(libc::ELOOP) => return Err(not_regular()),
+            Err(e) => return Err(e.into()),
+        };
+        if !file.metadata()?.file_type().is_file() {
+            return Err(not_regular());
+        }
+        let mut data = Vec::new();
+        file.read_to_end(&mut data)?;
+        Ok(data)
+    }
+
+    /// Reads the credential `id` as UTF-8 text.
+    pub fn read_string(&self, id: &str) -> Result<String> {
+        String::from_utf8(self.read(id)?).map_err(|_| {
+            Error::IoError(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Credential '{}' is not valid UTF-8", id),
+            ))
+        })
+    }
+
+    /// Reads the credential `id`, returning `None` if it was not passed.
+    pub fn get(&self, id: &str) -> Result<Option<Vec<u8>>> {
+        match self.read(id) {
+            Ok(data) => Ok(Some(data)),
+            Err(Error::IoError(e)) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systemd::testing::TempDir;
+    use std::os::unix::fs::symlink;
+    use std::process::Command;
+
+    fn is_not_regular(result: Result<Vec<u8>>) -> bool {
+        matches!(result, Err(Error::IoError(e)) if e.kind() == io::ErrorKind::InvalidInput)
+    }
+
+    #[test]
+    fn reads_regular_files() {
+        let dir = TempDir::new();
+        fs::write(dir.join("token"), "secret").unwrap();
+        let credentials = Credentials::open(dir.path()).unwrap();
+
+        assert_eq!(credentials.read_string("token").unwrap(), "secret");
+        assert_eq!(credentials.get("missing").unwrap(), None);
+        assert!(matches!(
+            credentials.read("../token"),
+            Err(Error::ValidationError(_))
+        ));
+    }
+
+    #[test]
+    fn refuses_symlinks_and_special_files() {
+        let dir = TempDir::new();
+        fs::write(dir.join("target"), "secret").unwrap();
+        symlink(dir.join("target"), dir.join("link")).unwrap();
+        fs::create_dir(dir.join("directory")).unwrap();
+        let fifo = Command::new("mkfifo").arg(dir.join("fifo")).status();
+        let credentials = Credentials::open(dir.path()).unwrap();
+
+        assert!(is_not_regular(credentials.read("link")));
+        assert!(is_not_regular(credentials.read("directory")));
+        if fifo.is_ok_and(|status| status.success()) {
+            assert!(is_not_regular(credentials.read("fifo")));
+        }
+    }
+}
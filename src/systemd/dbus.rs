@@ -0,0 +1,705 @@
+use crate::Result;
+use crate::error::Error;
+use futures_lite::{StreamExt, future};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use zbus::blocking::Connection;
+use zbus::blocking::fdo::PropertiesProxy;
+use zbus::proxy::CacheProperties;
+use zbus::zvariant::{OwnedObjectPath, OwnedValue};
+use zbus::{names::InterfaceName, proxy};
+
+const DESTINATION: &str = "org.freedesktop.systemd1";
+const UNIT_INTERFACE: &str = "org.freedesktop.systemd1.Unit";
+
+/// Changes of `EnableUnitFiles` and `DisableUnitFiles` as (kind, path, source) triples.
+type Changes = Vec<(String, String, String)>;
+
+/// Job mode replacing conflicting queued jobs, the mode `systemctl` uses by default.
+const JOB_MODE: &str = "replace";
+
+/// Default of [`DbusManager::job_timeout`].
+const JOB_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// The subset of the `org.freedesktop.systemd1.Manager` interface used by [`DbusManager`].
+/// https://www.freedesktop.org/software/systemd/man/latest/org.freedesktop.systemd1.html
+#[proxy(
+    interface = "org.freedesktop.systemd1.Manager",
+    default_service = "org.freedesktop.systemd1",
+    default_path = "/org/freedesktop/systemd1",
+    async_name = "AsyncManagerProxy",
+    blocking_name = "ManagerProxy"
+)]
+trait Manager {
+    fn subscribe(&self) -> zbus::Result<()>;
+
+    fn unsubscribe(&self) -> zbus::Result<()>;
+
+    fn start_unit(&self, name: &str, mode: &str) -> zbus::Result<OwnedObjectPath>;
+
+    fn stop_unit(&self, name: &str, mode: &str) -> zbus::Result<OwnedObjectPath>;
+
+    fn restart_unit(&self, name: &str, mode: &str) -> zbus::Result<OwnedObjectPath>;
+
+    fn load_unit(&self, name: &str) -> zbus::Result<OwnedObjectPath>;
+
+    fn reset_failed_unit(&self, name: &str) -> zbus::Result<()>;
+
+    fn enable_unit_files(
+        &self,
+        files: &[&str],
+        runtime: bool,
+        force: bool,
+    ) -> zbus::Result<(bool, Changes)>;
+
+    fn disable_unit_files(&self, files: &[&str], runtime: bool) -> zbus::Result<Changes>;
+
+    fn reload(&self) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    fn job_removed(
+        &self,
+        id: u32,
+        job: zbus::zvariant::ObjectPath<'_>,
+        unit: &str,
+        result: &str,
+    ) -> zbus::Result<()>;
+}
+
+/// Result of a finished job, as reported by the `JobRemoved` signal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JobResult {
+    Done,
+    Canceled,
+    Timeout,
+    Failed,
+    Dependency,
+    Skipped,
+    Other(String),
+}
+
+impl fmt::Display for JobResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JobResult::Done => write!(f, "done"),
+            JobResult::Canceled => write!(f, "canceled"),
+            JobResult::Timeout => write!(f, "timeout"),
+            JobResult::Failed => write!(f, "failed"),
+            JobResult::Dependency => write!(f, "dependency"),
+            JobResult::Skipped => write!(f, "skipped"),
+            JobResult::Other(result) => write!(f, "{}", result),
+        }
+    }
+}
+
+impl From<&str> for JobResult {
+    fn from(result: &str) -> Self {
+        match result {
+            "done" => JobResult::Done,
+            "canceled" => JobResult::Canceled,
+            "timeout" => JobResult::Timeout,
+            "failed" => JobResult::Failed,
+            "dependency" => JobResult::Dependency,
+            "skipped" => JobResult::Skipped,
+            other => JobResult::Other(other.to_string()),
+        }
+    }
+}
+
+impl JobResult {
+    pub fn is_success(&self) -> bool {
+        *self == JobResult::Done
+    }
+}
+
+/// A change made to the unit file configuration, as returned by `EnableUnitFiles`
+/// and `DisableUnitFiles`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnitFileChange {
+    /// Kind of change, e.g. `symlink` or `unlink`.
+    pub kind: String,
+    /// Path of the symlink that was created or removed.
+    pub path: String,
+    /// Target of the symlink, empty for removals.
+    pub source: String,
+}
+
+impl From<(String, String, String)> for UnitFileChange {
+    fn from((kind, path, source): (String, String, String)) -> Self {
+        Self { kind, path, source }
+    }
+}
+
+/// A client of the systemd manager talking to it over D-Bus instead of running `systemctl`.
+///
+/// Any connection serving the used subset of `org.freedesktop.systemd1.Manager` and
+/// `org.freedesktop.systemd1.Unit` works, so it can be pointed at a mock service with
+/// [`DbusManager::address`] or [`DbusManager::from_connection`].
+#[derive(Debug, Clone)]
+pub struct DbusManager {
+    connection: Connection,
+    job_timeout: Duration,
+    /// Number of jobs being waited for, the manager is subscribed to while it is not zero.
+    /// Shared by clones, which share the connection.
+    subscribers: Arc<Mutex<usize>>,
+}
+
+impl DbusManager {
+    /// Connects to the system service manager over the system bus.
+    pub fn system() -> Result<Self> {
+        Ok(Self::from_connection(
+            Connection::system().map_err(dbus_error)?,
+        ))
+    }
+
+    /// Connects to the service manager of the calling user over the session bus.
+    pub fn session() -> Result<Self> {
+        Ok(Self::from_connection(
+            Connection::session().map_err(dbus_error)?,
+        ))
+    }
+
+    /// Connects to the bus at `address`, e.g. `unix:path=/run/dbus/system_bus_socket`.
+    pub fn address(address: &str) -> Result<Self> {
+        let connection = zbus::blocking::connection::Builder::address(address)
+            .and_then(|builder| builder.build())
+            .map_err(dbus_error)?;
+        Ok(Self::from_connection(connection))
+    }
+
+    pub fn from_connection(connection: Connection) -> Self {
+        Self {
+            connection,
+            job_timeout: JOB_TIMEOUT,
+            subscribers: Arc::new(Mutex::new(0)),
+        }
+    }
+
+    /// Sets how long to wait for a start, stop or restart job to finish, 5 minutes by default.
+    /// A job still running after the timeout is left queued and an error is returned.
+    pub fn job_timeout(mut self, timeout: Duration) -> Self {
+        self.job_timeout = timeout;
+        self
+    }
+
+    pub fn connection(&self) -> &Connection {
+        &self.connection
+    }
+
+    /// Starts `unit` and waits for the job to finish.
+    pub fn start_unit(&self, unit: &str) -> Result<JobResult> {
+        self.run_job(unit, |manager| manager.start_unit(unit, JOB_MODE))
+    }
+
+    /// Stops `unit` and waits for the job to finish.
+    pub fn stop_unit(&self, unit: &str) -> Result<JobResult> {
+        self.run_job(unit, |manager| manager.stop_unit(unit, JOB_MODE))
+    }
+
+    /// Restarts `unit` and waits for the job to finish.
+    pub fn restart_unit(&self, unit: &str) -> Result<JobResult> {
+        self.run_job(unit, |manager| manager.restart_unit(unit, JOB_MODE))
+    }
+
+    /// Resets the failed state of `unit`.
+    pub fn reset_failed_unit(&self, unit: &str) -> Result<()> {
+        self.manager()?.reset_failed_unit(unit).map_err(dbus_error)
+    }
+
+    /// Enables unit files by creating the symlinks of their `[Install]` sections.
+    pub fn enable_unit_files(&self, files: &[&str]) -> Result<Vec<UnitFileChange>> {
+        let (_, changes) = self
+            .manager()?
+            .enable_unit_files(files, false, false)
+            .map_err(dbus_error)?;
+        Ok(changes.into_iter().map(UnitFileChange::from).collect())
+    }
+
+    /// Disables unit files by removing the symlinks of their `[Install]` sections.
+    pub fn disable_unit_files(&self, files: &[&str]) -> Result<Vec<UnitFileChange>> {
+        let changes = self
+            .manager()?
+            .disable_unit_files(files, false)
+            .map_err(dbus_error)?;
+        Ok(changes.into_iter().map(UnitFileChange::from).collect())
+    }
+
+    /// Reloads the unit files of the manager, the equivalent of `systemctl daemon-reload`.
+    pub fn reload(&self) -> Result<()> {
+        self.manager()?.reload().map_err(dbus_error)
+    }
+
+    /// Reads the property `name` of `interface`, e.g. `org.freedesktop.systemd1.Service`,
+    /// of `unit`. The unit is loaded if it is not already.
+    pub fn unit_property(&self, unit: &str, interface: &str, name: &str) -> Result<OwnedValue> {
+        let interface = InterfaceName::try_from(interface).map_err(|e| dbus_error(e.into()))?;
+        self.properties(unit)?
+            .get(interface, name)
+            .map_err(|e| dbus_error(e.into()))
+    }
+
+    /// Reads all properties of `interface` of `unit`. The unit is loaded if it is not already.
+    pub fn unit_properties(
+        &self,
+        unit: &str,
+        interface: &str,
+    ) -> Result<HashMap<String, OwnedValue>> {
+        let interface = InterfaceName::try_from(interface).map_err(|e| dbus_error(e.into()))?;
+        self.properties(unit)?
+            .get_all(interface)
+            .map_err(|e| dbus_error(e.into()))
+    }
+
+    /// Reads a string property of the `org.freedesktop.systemd1.Unit` interface of `unit`,
+    /// e.g. `ActiveState` or `LoadState`.
+    pub fn unit_state(&self, unit: &str, name: &str) -> Result<String> {
+        let value = self.unit_property(unit, UNIT_INTERFACE, name)?;
+        String::try_from(value).map_err(|e| dbus_error(e.into()))
+    }
+
+    /// Starts listening for property changes of `unit`, so a wait for a state does not have to poll.
+    /// Changes made before this call are not reported.
+    pub(crate) fn unit_changes(&self, unit: &str) -> Result<UnitChanges> {
+        let subscription = Subscription::new(self)?;
+        let path = self.manager()?.load_unit(unit).map_err(dbus_error)?;
+        let changes = async_io::block_on(async {
+            zbus::fdo::PropertiesProxy::builder(self.connection.inner())
+                .destination(DESTINATION)?
+                .path(path)?
+                .cache_properties(CacheProperties::No)
+                .build()
+                .await?
+                .receive_properties_changed()
+                .await
+        })
+        .map_err(dbus_error)?;
+        Ok(UnitChanges {
+            changes,
+            _subscription: subscription,
+        })
+    }
+
+    fn properties(&self, unit: &str) -> Result<PropertiesProxy<'_>> {
+        let path = self.manager()?.load_unit(unit).map_err(dbus_error)?;
+        PropertiesProxy::builder(&self.connection)
+            .destination(DESTINATION)
+            .and_then(|builder| builder.path(path))
+            .and_then(|builder| builder.cache_properties(CacheProperties::No).build())
+            .map_err(dbus_error)
+    }
+
+    fn manager(&self) -> Result<ManagerProxy<'_>> {
+        ManagerProxy::builder(&self.connection)
+            .cache_properties(CacheProperties::No)
+            .build()
+            .map_err(dbus_error)
+    }
+
+    /// Queues a job with `enqueue` and waits for its `JobRemoved` signal, at most for the job timeout.
+    fn run_job(
+        &self,
+        unit: &str,
+        enqueue: impl FnOnce(&ManagerProxy<'_>) -> zbus::Result<OwnedObjectPath>,
+    ) -> Result<JobResult> {
+        let manager = self.manager()?;
+        let _subscription = Subscription::new(self)?;
+        // Listen before queueing the job, so a job finishing right away is not missed
+        let mut signals = async_io::block_on(async {
+            AsyncManagerProxy::builder(self.connection.inner())
+                .cache_properties(CacheProperties::No)
+                .build()
+                .await?
+                .receive_job_removed()
+                .await
+        })
+        .map_err(dbus_error)?;
+        let job = enqueue(&manager).map_err(dbus_error)?;
+
+        let removed = async {
+            while let Some(signal) = signals.next().await {
+                let args = signal.args().map_err(dbus_error)?;
+                if args.job.as_str() == job.as_str() {
+                    return Ok(JobResult::from(args.result));
+                }
+            }
+            Err(Error::CommandError(format!(
+                "Connection closed while waiting for the job of '{}'",
+                unit
+            )))
+        };
+        let timeout = async {
+            async_io::Timer::after(self.job_timeout).await;
+            Err(Error::CommandError(format!(
+                "Job {} of '{}' did not finish within {:?}",
+                job.as_str(),
+                unit,
+                self.job_timeout
+            )))
+        };
+        async_io::block_on(future::or(removed, timeout))
+    }
+}
+
+/// Keeps the manager subscribed to its signals, e.g. of jobs or unit changes, while they are waited for.
+/// The last subscription dropped unsubscribes, so other waiters on the connection are not affected.
+struct Subscription {
+    dbus: DbusManager,
+}
+
+impl Subscription {
+    fn new(dbus: &DbusManager) -> Result<Self> {
+        let mut subscribers = dbus.subscribers.lock().unwrap_or_else(|e| e.into_inner());
+        if *subscribers == 0 {
+            dbus.manager()?.subscribe().map_err(dbus_error)?;
+        }
+        *subscribers += 1;
+        Ok(Self { dbus: dbus.clone() })
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        let mut subscribers = self
+            .dbus
+            .subscribers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        *subscribers -= 1;
+        if *subscribers == 0
+            && let Ok(manager) = self.dbus.manager()
+        {
+            let _ = manager.unsubscribe();
+        }
+    }
+}
+
+/// Property changes of a unit object, see [`DbusManager::unit_changes`].
+pub(crate) struct UnitChanges {
+    changes: zbus::fdo::PropertiesChangedStream,
+    _subscription: Subscription,
+}
+
+impl UnitChanges {
+    /// Waits until a property of the unit changes, or at most `timeout`.
+    pub(crate) fn wait(&mut self, timeout: Duration) -> Result<()> {
+        let changed = async {
+            match self.changes.next().await {
+                Some(_) => Ok(()),
+                None => Err(Error::CommandError(
+                    "Connection closed while waiting for the unit to change".to_string(),
+                )),
+            }
+        };
+        let timeout = async {
+            async_io::Timer::after(timeout).await;
+            Ok(())
+        };
+        async_io::block_on(future::or(changed, timeout))
+    }
+}
+
+fn dbus_error(e: zbus::Error) -> Error {
+    Error::CommandError(format!("D-Bus call failed: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systemd::{ActiveState, Config, MockRunner, Systemd};
+    use std::os::unix::net::UnixStream;
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::Instant;
+    use zbus::blocking::connection::Builder;
+    use zbus::object_server::SignalEmitter;
+    use zbus::zvariant::ObjectPath;
+
+    const MANAGER_PATH: &str = "/org/freedesktop/systemd1";
+    const UNIT_PATH: &str = "/org/freedesktop/systemd1/unit/demo_2eservice";
+
+    /// A service manager finishing jobs of `hang.service` never, of `broken.service` with
+    /// `failed` and of every other unit with `done`.
+    #[derive(Default)]
+    struct MockManager {
+        calls: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl MockManager {
+        fn record(&self, call: String) {
+            self.calls.lock().unwrap().push(call);
+        }
+    }
+
+    #[zbus::interface(name = "org.freedesktop.systemd1.Manager")]
+    impl MockManager {
+        fn subscribe(&self) {
+            self.record("Subscribe".to_string());
+        }
+
+        fn unsubscribe(&self) {
+            self.record("Unsubscribe".to_string());
+        }
+
+        async fn start_unit(
+            &self,
+            name: &str,
+            mode: &str,
+            #[zbus(signal_emitter)] emitter: SignalEmitter<'_>,
+        ) -> zbus::fdo::Result<OwnedObjectPath> {
+            self.record(format!("StartUnit {} {}", name, mode));
+            let job = ObjectPath::try_from("/org/freedesktop/systemd1/job/7").unwrap();
+            let result = match name {
+                "hang.service" => None,
+                "broken.service" => Some("failed"),
+                _ => Some("done"),
+            };
+            if let Some(result) = result {
+                Self::job_removed(&emitter, 7, job.clone(), name, result).await?;
+            }
+            Ok(job.into())
+        }
+
+        fn load_unit(&self, name: &str) -> OwnedObjectPath {
+            self.record(format!("LoadUnit {}", name));
+            ObjectPath::try_from(UNIT_PATH).unwrap().into()
+        }
+
+        fn enable_unit_files(
+            &self,
+            files: Vec<String>,
+            runtime: bool,
+            force: bool,
+        ) -> (bool, Changes) {
+            self.record(format!("EnableUnitFiles {:?} {} {}", files, runtime, force));
+            let changes = files
+                .iter()
+                .map(|file| {
+                    (
+                        "symlink".to_string(),
+                        format!("/etc/systemd/system/multi-user.target.wants/{}", file),
+                        format!("/etc/systemd/system/{}", file),
+                    )
+                })
+                .collect();
+            (false, changes)
+        }
+
+        fn reload(&self) {
+            self.record("Reload".to_string());
+        }
+
+        #[zbus(signal)]
+        async fn job_removed(
+            emitter: &SignalEmitter<'_>,
+            id: u32,
+            job: ObjectPath<'_>,
+            unit: &str,
+            result: &str,
+        ) -> zbus::Result<()>;
+    }
+
+    /// A unit in `active_state`, which a test may change while a client waits.
+    struct MockUnit {
+        active_state: String,
+    }
+
+    #[zbus::interface(name = "org.freedesktop.systemd1.Unit")]
+    impl MockUnit {
+        #[zbus(property)]
+        fn load_state(&self) -> &str {
+            "loaded"
+        }
+
+        #[zbus(property)]
+        fn active_state(&self) -> &str {
+            &self.active_state
+        }
+
+        #[zbus(property)]
+        fn sub_state(&self) -> &str {
+            "running"
+        }
+
+        #[zbus(property)]
+        fn unit_file_state(&self) -> &str {
+            "enabled"
+        }
+    }
+
+    struct MockService;
+
+    #[zbus::interface(name = "org.freedesktop.systemd1.Service")]
+    impl MockService {
+        #[zbus(property, name = "MainPID")]
+        fn main_pid(&self) -> u32 {
+            4242
+        }
+
+        #[zbus(property)]
+        fn result(&self) -> &str {
+            "success"
+        }
+
+        #[zbus(property, name = "NRestarts")]
+        fn n_restarts(&self) -> u32 {
+            2
+        }
+    }
+
+    /// Serves a mock manager on a private peer-to-peer connection, returning the client and
+    /// the calls made to the manager. The server connection has to be kept alive.
+    fn connect() -> (DbusManager, Arc<Mutex<Vec<String>>>, Connection) {
+        connect_with_state("active")
+    }
+
+    /// Like [`connect`], with the demo unit in `active_state`.
+    fn connect_with_state(
+        active_state: &str,
+    ) -> (DbusManager, Arc<Mutex<Vec<String>>>, Connection) {
+        let unit = MockUnit {
+            active_state: active_state.to_string(),
+        };
+        let manager = MockManager::default();
+        let calls = Arc::clone(&manager.calls);
+        let (client, server) = UnixStream::pair().unwrap();
+        // Both ends authenticate at the same time
+        let server = thread::spawn(move || {
+            Builder::async_io_unix_stream(server)
+                .server(zbus::Guid::generate())
+                .unwrap()
+                .p2p()
+                .serve_at(MANAGER_PATH, manager)
+                .unwrap()
+                .serve_at(UNIT_PATH, unit)
+                .unwrap()
+                .serve_at(UNIT_PATH, MockService)
+                .unwrap()
+                .build()
+                .unwrap()
+        });
+        let client = Builder::async_io_unix_stream(client).p2p().build().unwrap();
+        let server = server.join().unwrap();
+        (DbusManager::from_connection(client), calls, server)
+    }
+
+    #[test]
+    fn start_unit_waits_for_job_removed() {
+        let (manager, calls, _server) = connect();
+
+        assert_eq!(manager.start_unit("demo.service").unwrap(), JobResult::Done);
+        assert_eq!(
+            manager.start_unit("broken.service").unwrap(),
+            JobResult::Failed
+        );
+
+        assert_eq!(
+            *calls.lock().unwrap(),
+            [
+                "Subscribe",
+                "StartUnit demo.service replace",
+                "Unsubscribe",
+                "Subscribe",
+                "StartUnit broken.service replace",
+                "Unsubscribe",
+            ]
+        );
+    }
+
+    #[test]
+    fn failed_job_is_an_error() {
+        let (manager, _calls, _server) = connect();
+        let systemd = Systemd::new(Config::new("broken")).dbus(manager);
+
+        let error = systemd.start().unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "Command Error: Failed to start service: job for broken.service finished with result 'failed'"
+        );
+    }
+
+    #[test]
+    fn job_wait_times_out() {
+        let (manager, calls, _server) = connect();
+        let manager = manager.job_timeout(Duration::from_millis(100));
+
+        let error = manager.start_unit("hang.service").unwrap_err();
+
+        assert!(error.to_string().contains("did not finish within 100ms"));
+        assert_eq!(calls.lock().unwrap().last().unwrap(), "Unsubscribe");
+    }
+
+    #[test]
+    fn enable_unit_files_and_reload() {
+        let (manager, calls, _server) = connect();
+
+        let changes = manager.enable_unit_files(&["demo.service"]).unwrap();
+        manager.reload().unwrap();
+
+        assert_eq!(
+            changes,
+            [UnitFileChange {
+                kind: "symlink".to_string(),
+                path: "/etc/systemd/system/multi-user.target.wants/demo.service".to_string(),
+                source: "/etc/systemd/system/demo.service".to_string(),
+            }]
+        );
+        assert_eq!(
+            *calls.lock().unwrap(),
+            ["EnableUnitFiles [\"demo.service\"] false false", "Reload"]
+        );
+    }
+
+    #[test]
+    fn reads_unit_properties() {
+        let (manager, _calls, _server) = connect();
+
+        assert_eq!(
+            manager.unit_state("demo.service", "ActiveState").unwrap(),
+            "active"
+        );
+        let state = Systemd::new(Config::new("demo"))
+            .dbus(manager)
+            .state()
+            .unwrap();
+
+        assert_eq!(state.active_state, ActiveState::Active);
+        assert_eq!(state.sub_state, "running");
+        assert_eq!(state.main_pid, Some(4242));
+        assert_eq!(state.restarts, 2);
+    }
+
+    #[test]
+    fn wait_for_reads_the_state_on_property_changes() {
+        let (manager, calls, server) = connect_with_state("activating");
+        let activate = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(200));
+            let unit = server
+                .object_server()
+                .interface::<_, MockUnit>(UNIT_PATH)
+                .unwrap();
+            unit.get_mut().active_state = "active".to_string();
+            async_io::block_on(unit.get().active_state_changed(unit.signal_emitter())).unwrap();
+            server
+        });
+        // Any systemctl or journalctl run panics, the state is only read over D-Bus
+        let runner = Arc::new(MockRunner::new());
+        let started = Instant::now();
+
+        let state = Systemd::new(Config::new("demo"))
+            .runner(Arc::clone(&runner))
+            .dbus(manager)
+            .wait_for(ActiveState::Active, Duration::from_secs(10))
+            .unwrap();
+
+        let _server = activate.join().unwrap();
+        assert_eq!(state.active_state, ActiveState::Active);
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert!(runner.calls().is_empty());
+        let calls = calls.lock().unwrap();
+        assert_eq!(calls.first().unwrap(), "Subscribe");
+        assert_eq!(calls.last().unwrap(), "Unsubscribe");
+    }
+}
@@ -0,0 +1,534 @@
+use super::install::{Install, InstallLink};
+use super::{ManagerScope, Systemd};
+use crate::Result;
+use crate::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::os::unix::fs::symlink;
+use std::path::{Path, PathBuf};
+
+/// Suffixes of the directories holding the dependency symlinks of a unit.
+const DEPENDENCY_DIRS: &[&str] = &[".wants", ".requires", ".upholds"];
+
+/// Target of the symlink masking a unit.
+const MASK_TARGET: &str = "/dev/null";
+
+/// Enablement state of a unit file, as reported by `systemctl is-enabled`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnitFileState {
+    /// The `[Install]` symlinks of the unit exist.
+    Enabled,
+    /// The unit can be enabled, but none of its symlinks exist.
+    Disabled,
+    /// The unit has no `[Install]` section and cannot be enabled.
+    Static,
+    /// The unit name is linked to `/dev/null`.
+    Masked,
+    /// The unit name is a symlink to another unit file.
+    Alias,
+    /// The unit is only enabled through `Also=`, or is a template without `DefaultInstance=`.
+    Indirect,
+    /// There is no unit file.
+    NotFound,
+    /// Enabled below `/run`, until the next reboot. Only reported by the service manager.
+    EnabledRuntime,
+    /// The unit file is linked into the unit search path from elsewhere.
+    /// Only reported by the service manager.
+    Linked,
+    /// Linked below `/run`, until the next reboot. Only reported by the service manager.
+    LinkedRuntime,
+    /// Masked below `/run`, until the next reboot. Only reported by the service manager.
+    MaskedRuntime,
+    /// The unit file was created by a generator. Only reported by the service manager.
+    Generated,
+    /// The unit was created at runtime, e.g. by `systemd-run`. Only reported by the service manager.
+    Transient,
+    /// The unit file is invalid. Only reported by the service manager.
+    Bad,
+}
+
+impl fmt::Display for UnitFileState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnitFileState::Enabled => write!(f, "enabled"),
+            UnitFileState::Disabled => write!(f, "disabled"),
+            UnitFileState::Static => write!(f, "static"),
+            UnitFileState::Masked => write!(f, "masked"),
+            UnitFileState::Alias => write!(f, "alias"),
+            UnitFileState::Indirect => write!(f, "indirect"),
+            UnitFileState::NotFound => write!(f, "not-found"),
+            UnitFileState::EnabledRuntime => write!(f, "enabled-runtime"),
+            UnitFileState::Linked => write!(f, "linked"),
+            UnitFileState::LinkedRuntime => write!(f, "linked-runtime"),
+            UnitFileState::MaskedRuntime => write!(f, "masked-runtime"),
+            UnitFileState::Generated => write!(f, "generated"),
+            UnitFileState::Transient => write!(f, "transient"),
+            UnitFileState::Bad => write!(f, "bad"),
+        }
+    }
+}
+
+impl UnitFileState {
+    /// Parses a state as printed by `systemctl is-enabled`, `None` if it is unknown.
+    pub fn from_name(state: &str) -> Option<Self> {
+        match state {
+            "enabled" => Some(UnitFileState::Enabled),
+            "disabled" => Some(UnitFileState::Disabled),
+            "static" => Some(UnitFileState::Static),
+            "masked" => Some(UnitFileState::Masked),
+            "alias" => Some(UnitFileState::Alias),
+            "indirect" => Some(UnitFileState::Indirect),
+            "not-found" => Some(UnitFileState::NotFound),
+            "enabled-runtime" => Some(UnitFileState::EnabledRuntime),
+            "linked" => Some(UnitFileState::Linked),
+            "linked-runtime" => Some(UnitFileState::LinkedRuntime),
+            "masked-runtime" => Some(UnitFileState::MaskedRuntime),
+            "generated" => Some(UnitFileState::Generated),
+            "transient" => Some(UnitFileState::Transient),
+            "bad" => Some(UnitFileState::Bad),
+            _ => None,
+        }
+    }
+}
+
+/// A problem with the `[Install]` symlinks of a unit, found by [`Systemd::verify_links`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinkIssue {
+    /// A symlink implied by `[Install]` does not exist.
+    Missing(InstallLink),
+    /// A symlink implied by `[Install]` points to another file.
+    Conflict { link: InstallLink, actual: PathBuf },
+    /// A symlink implied by `[Install]` exists, but the unit file it points to does not.
+    Broken(InstallLink),
+    /// A symlink to the unit no longer implied by `[Install]`, e.g. left behind by an earlier `WantedBy=`.
+    Stale(InstallLink),
+}
+
+impl fmt::Display for LinkIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LinkIssue::Missing(link) => write!(
+                f,
+                "Missing symlink '{}' to '{}'",
+                link.path.display(),
+                link.target.display()
+            ),
+            LinkIssue::Conflict { link, actual } => write!(
+                f,
+                "Symlink '{}' points to '{}' instead of '{}'",
+                link.path.display(),
+                actual.display(),
+                link.target.display()
+            ),
+            LinkIssue::Broken(link) => write!(
+                f,
+                "Symlink '{}' points to missing unit file '{}'",
+                link.path.display(),
+                link.target.display()
+            ),
+            LinkIssue::Stale(link) => write!(
+                f,
+                "Stale symlink '{}' to '{}'",
+                link.path.display(),
+                link.target.display()
+            ),
+        }
+    }
+}
+
+impl Systemd {
+    /// Returns every symlink enabling the unit, including those of the units in `Also=`,
+    /// whose unit files are looked up in the unit search path.
+    /// Paths are below the alternate root if one is set, targets are as seen by the service manager.
+    pub fn install_links(&self) -> Result<Vec<InstallLink>> {
+        let unit_dir = self.unit_dir()?;
+        let mut links = self.own_links()?;
+        for (unit_file, install) in self.also_units()? {
+            links.extend(install.links(&unit_dir, &unit_file));
+        }
+        Ok(links
+            .into_iter()
+            .map(|link| InstallLink {
+                path: self.rooted(&link.path),
+                target: link.target,
+            })
+            .collect())
+    }
+
+    /// Returns the symlinks [`Systemd::enable`] would create, without changing anything.
+    pub fn enable_dry_run(&self) -> Result<Vec<InstallLink>> {
+        let mut missing = Vec::new();
+        for link in self.install_links()? {
+            if !link_exists(&link)? {
+                missing.push(link);
+            }
+        }
+        Ok(missing)
+    }
+
+    /// Enables the unit by creating its `[Install]` symlinks directly, without `systemctl`.
+    /// Returns the symlinks that were created, existing ones are kept.
+    /// If a symlink cannot be created, those created before it are removed again.
+    /// The service manager is not reloaded.
+    pub fn enable(&self) -> Result<Vec<InstallLink>> {
+        let missing = self.enable_dry_run()?;
+        for (i, link) in missing.iter().enumerate() {
+            let created = match link.path.parent() {
+                Some(dir) => fs::create_dir_all(dir),
+                None => Ok(()),
+            }
+            .and_then(|_| symlink(&link.target, &link.path));
+            if let Err(e) = created {
+                for link in &missing[..i] {
+                    let _ = fs::remove_file(&link.path);
+                }
+                return Err(e.into());
+            }
+        }
+        Ok(missing)
+    }
+
+    /// Disables the unit by removing its `[Install]` symlinks and any stale symlinks to it.
+    /// Returns the symlinks that were removed, symlinks pointing elsewhere are left alone.
+    pub fn disable(&self) -> Result<Vec<InstallLink>> {
+        let mut removed = Vec::new();
+        for link in self.install_links()? {
+            if read_link(&link.path)?.as_ref() == Some(&link.target) {
+                fs::remove_file(&link.path)?;
+                removed.push(link);
+            }
+        }
+        for link in self.stale_links()? {
+            fs::remove_file(&link.path)?;
+            removed.push(link);
+        }
+        Ok(removed)
+    }
+
+    /// Compares the symlinks on disk with those implied by `[Install]`.
+    pub fn verify_links(&self) -> Result<Vec<LinkIssue>> {
+        let mut issues = Vec::new();
+        for link in self.install_links()? {
+            match read_link(&link.path)? {
+                None => issues.push(LinkIssue::Missing(link)),
+                Some(actual) if actual != link.target => {
+                    issues.push(LinkIssue::Conflict { link, actual })
+                }
+                Some(_) if !self.rooted(&link.target).exists() => {
+                    issues.push(LinkIssue::Broken(link))
+                }
+                Some(_) => {}
+            }
+        }
+        issues.extend(self.stale_links()?.into_iter().map(LinkIssue::Stale));
+        Ok(issues)
+    }
+
+    /// Returns the enablement state of the unit, determined from the file system.
+    pub fn is_enabled(&self) -> Result<UnitFileState> {
+        let path = self.config_path()?;
+        match read_link(&path)? {
+            Some(target) if target == Path::new(MASK_TARGET) => return Ok(UnitFileState::Masked),
+            Some(_) => return Ok(UnitFileState::Alias),
+            None if !path.exists() => return Ok(UnitFileState::NotFound),
+            None => {}
+        }
+        let install = self.config.install_section();
+        let links = self.own_links()?;
+        for link in &links {
+            if read_link(&self.rooted(&link.path))?.as_ref() == Some(&link.target) {
+                return Ok(UnitFileState::Enabled);
+            }
+        }
+        if !links.is_empty() {
+            return Ok(UnitFileState::Disabled);
+        }
+        // A template without DefaultInstance= is only enabled through its instances
+        if !install.is_empty() || install.also.as_ref().is_some_and(|also| !also.is_empty()) {
+            return Ok(UnitFileState::Indirect);
+        }
+        Ok(UnitFileState::Static)
+    }
+
+    /// Returns the symlinks implied by the unit's own `[Install]` section, without the root.
+    fn own_links(&self) -> Result<Vec<InstallLink>> {
+        let unit_dir = self.unit_dir()?;
+        let unit_file = unit_dir.join(self.config.unit_name());
+        Ok(self.config.install_section().links(&unit_dir, &unit_file))
+    }
+
+    /// Resolves the units of `Also=`, transitively, to their unit files and `[Install]` sections.
+    fn also_units(&self) -> Result<Vec<(PathBuf, Install)>> {
+        let mut seen = vec![self.config.unit_name()];
+        let mut queue = self
+            .config
+            .install_section()
+            .also
+            .clone()
+            .unwrap_or_default();
+        let mut units = Vec::new();
+        while let Some(name) = queue.pop() {
+            if seen.contains(&name) {
+                continue;
+            }
+            let Some(unit_file) = self.find_unit_file(&name)? else {
+                return Err(Error::ValidationError(format!(
+                    "Unit '{}' listed in Also= has no unit file",
+                    name
+                )));
+            };
+            let install = Install::parse(&fs::read_to_string(self.rooted(&unit_file))?);
+            queue.extend(install.also.iter().flatten().cloned());
+            seen.push(name);
+            units.push((unit_file, install));
+        }
+        Ok(units)
+    }
+
+    /// Looks up the unit file of `name` in the unit search path of the scope.
+    fn find_unit_file(&self, name: &str) -> Result<Option<PathBuf>> {
+        let vendor_dirs = match self.scope {
+            ManagerScope::System => ["/usr/lib/systemd/system", "/lib/systemd/system"],
+            ManagerScope::User | ManagerScope::Global => {
+                ["/etc/systemd/user", "/usr/lib/systemd/user"]
+            }
+        };
+        let mut dirs = vec![self.unit_dir()?];
+        dirs.extend(vendor_dirs.iter().map(PathBuf::from));
+        Ok(dirs
+            .into_iter()
+            .map(|dir| dir.join(name))
+            .find(|unit_file| self.rooted(unit_file).exists()))
+    }
+
+    /// Finds symlinks in the unit directory and its dependency directories that point to
+    /// the unit, or one of its `Also=` units, but are not implied by `[Install]`.
+    fn stale_links(&self) -> Result<Vec<InstallLink>> {
+        let expected = self.install_links()?;
+        let unit_name = self.config.unit_name();
+        let mut targets = vec![self.unit_dir()?.join(&unit_name)];
+        targets.extend(
+            self.also_units()?
+                .into_iter()
+                .map(|(unit_file, _)| unit_file),
+        );
+
+        let unit_dir = self.config_path()?.with_file_name("");
+        let mut candidates = Vec::new();
+        for entry in read_dir(&unit_dir)? {
+            let name = entry.to_string_lossy();
+            if DEPENDENCY_DIRS.iter().any(|suffix| name.ends_with(suffix)) {
+                candidates.extend(
+                    read_dir(&unit_dir.join(&entry))?
+                        .into_iter()
+                        .map(|link| unit_dir.join(&entry).join(link)),
+                );
+            } else {
+                candidates.push(unit_dir.join(entry));
+            }
+        }
+
+        let mut stale = Vec::new();
+        for path in candidates {
+            if expected.iter().any(|link| link.path == path) {
+                continue;
+            }
+            if let Some(target) = read_link(&path)?
+                && targets.contains(&target)
+            {
+                stale.push(InstallLink { path, target });
+            }
+        }
+        Ok(stale)
+    }
+}
+
+/// Returns true if the symlink exists and points to its target. A file or a symlink pointing
+/// elsewhere at its path is an error, as it cannot be replaced without losing data.
+fn link_exists(link: &InstallLink) -> Result<bool> {
+    match read_link(&link.path)? {
+        Some(target) if target == link.target => Ok(true),
+        Some(target) => Err(Error::ValidationError(format!(
+            "Symlink '{}' already exists and points to '{}'",
+            link.path.display(),
+            target.display()
+        ))),
+        None if fs::symlink_metadata(&link.path).is_ok() => Err(Error::ValidationError(format!(
+            "'{}' already exists and is not a symlink",
+            link.path.display()
+        ))),
+        None => Ok(false),
+    }
+}
+
+/// Reads the target of a symlink, `None` if the path does not exist or is not a symlink.
+fn read_link(path: &Path) -> Result<Option<PathBuf>> {
+    match fs::read_link(path) {
+        Ok(target) => Ok(Some(target)),
+        Err(e)
+            if matches!(
+                e.kind(),
+                io::ErrorKind::NotFound | io::ErrorKind::InvalidInput
+            ) =>
+        {
+            Ok(None)
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Lists the names of the entries of a directory, which may not exist.
+fn read_dir(dir: &Path) -> Result<Vec<PathBuf>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    let mut names = Vec::new();
+    for entry in entries {
+        names.push(PathBuf::from(entry?.file_name()));
+    }
+    Ok(names)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systemd::testing::TempDir;
+    use crate::systemd::{Config, Install};
+
+    const UNIT_FILE: &str = "/etc/systemd/system/demo.service";
+
+    /// A manager of `demo.service` with the `[Install]` section `install`, below `root`.
+    fn systemd(root: &TempDir, install: Install) -> Systemd {
+        Systemd::new(Config::new("demo").install(install)).root(root.path())
+    }
+
+    fn wanted() -> Install {
+        Install::default().wanted_by(vec!["multi-user.target"])
+    }
+
+    fn link(root: &TempDir, path: &str) -> InstallLink {
+        InstallLink {
+            path: root.join("etc/systemd/system").join(path),
+            target: PathBuf::from(UNIT_FILE),
+        }
+    }
+
+    fn write_unit_file(root: &TempDir) {
+        let unit_dir = root.join("etc/systemd/system");
+        fs::create_dir_all(&unit_dir).unwrap();
+        fs::write(unit_dir.join("demo.service"), "[Service]\n").unwrap();
+    }
+
+    #[test]
+    fn enable_dry_run_lists_missing_links() {
+        let root = TempDir::new();
+        write_unit_file(&root);
+        let install = wanted().alias(vec!["demo-alias.service"]);
+        let systemd = systemd(&root, install);
+        let expected = vec![
+            link(&root, "multi-user.target.wants/demo.service"),
+            link(&root, "demo-alias.service"),
+        ];
+
+        assert_eq!(systemd.enable_dry_run().unwrap(), expected);
+        assert!(!expected[0].path.exists());
+
+        assert_eq!(systemd.enable().unwrap(), expected);
+        assert_eq!(systemd.enable_dry_run().unwrap(), []);
+        assert_eq!(systemd.enable().unwrap(), []);
+        assert_eq!(systemd.verify_links().unwrap(), []);
+
+        assert_eq!(systemd.disable().unwrap(), expected);
+        assert_eq!(systemd.enable_dry_run().unwrap(), expected);
+    }
+
+    #[test]
+    fn verify_links_reports_missing_and_broken() {
+        let root = TempDir::new();
+        let systemd = systemd(&root, wanted());
+        let wants = link(&root, "multi-user.target.wants/demo.service");
+
+        assert_eq!(
+            systemd.verify_links().unwrap(),
+            [LinkIssue::Missing(wants.clone())]
+        );
+        // Enabled, but the unit file is missing
+        systemd.enable().unwrap();
+        assert_eq!(systemd.verify_links().unwrap(), [LinkIssue::Broken(wants)]);
+    }
+
+    #[test]
+    fn verify_links_reports_conflicting_and_stale() {
+        let root = TempDir::new();
+        write_unit_file(&root);
+        let systemd = systemd(&root, wanted());
+        let wants = link(&root, "multi-user.target.wants/demo.service");
+        let stale = link(&root, "graphical.target.wants/demo.service");
+        fs::create_dir_all(wants.path.parent().unwrap()).unwrap();
+        symlink("/etc/systemd/system/other.service", &wants.path).unwrap();
+        fs::create_dir_all(stale.path.parent().unwrap()).unwrap();
+        symlink(UNIT_FILE, &stale.path).unwrap();
+
+        assert_eq!(
+            systemd.verify_links().unwrap(),
+            [
+                LinkIssue::Conflict {
+                    link: wants.clone(),
+                    actual: PathBuf::from("/etc/systemd/system/other.service"),
+                },
+                LinkIssue::Stale(stale.clone()),
+            ]
+        );
+        // The conflicting link is not replaced
+        assert!(matches!(systemd.enable(), Err(Error::ValidationError(_))));
+        // Disabling removes the stale link, but not the one pointing elsewhere
+        assert_eq!(systemd.disable().unwrap(), [stale]);
+        assert!(wants.path.is_symlink());
+    }
+
+    #[test]
+    fn is_enabled_reads_the_file_system() {
+        let root = TempDir::new();
+        let systemd = systemd(&root, wanted());
+
+        assert_eq!(systemd.is_enabled().unwrap(), UnitFileState::NotFound);
+        write_unit_file(&root);
+        assert_eq!(systemd.is_enabled().unwrap(), UnitFileState::Disabled);
+        systemd.enable().unwrap();
+        assert_eq!(systemd.is_enabled().unwrap(), UnitFileState::Enabled);
+    }
+
+    #[test]
+    fn is_enabled_masked_and_alias() {
+        let root = TempDir::new();
+        let unit_dir = root.join("etc/systemd/system");
+        fs::create_dir_all(&unit_dir).unwrap();
+        let systemd = systemd(&root, wanted());
+
+        symlink(MASK_TARGET, unit_dir.join("demo.service")).unwrap();
+        assert_eq!(systemd.is_enabled().unwrap(), UnitFileState::Masked);
+
+        fs::remove_file(unit_dir.join("demo.service")).unwrap();
+        symlink(
+            "/etc/systemd/system/other.service",
+            unit_dir.join("demo.service"),
+        )
+        .unwrap();
+        assert_eq!(systemd.is_enabled().unwrap(), UnitFileState::Alias);
+    }
+
+    #[test]
+    fn is_enabled_static_and_indirect() {
+        let root = TempDir::new();
+        write_unit_file(&root);
+
+        let plain = systemd(&root, Install::default());
+        assert_eq!(plain.is_enabled().unwrap(), UnitFileState::Static);
+
+        let also = systemd(&root, Install::default().also(vec!["demo.socket"]));
+        assert_eq!(also.is_enabled().unwrap(), UnitFileState::Indirect);
+    }
+}
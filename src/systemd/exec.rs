@@ -1,5 +1,478 @@
+use super::credential::{self, LoadCredential, SetCredential};
+use super::limit::Limits;
+use super::namespace::{
+    self, BindPath, ExtensionImage, MountFlags, MountImage, NamespacePath, RootImage,
+    TemporaryFileSystem,
+};
+use super::network::AddressFamilies;
+use super::resource_control::CpuSet;
+use super::syscall::{self, SystemCallFilter};
+use super::{ManagerScope, xdg_dir};
+use crate::error::Error;
 use std::fmt;
-use std::path::PathBuf;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const DIRECTORY_KINDS: [DirectoryKind; 5] = [
+    DirectoryKind::Runtime,
+    DirectoryKind::State,
+    DirectoryKind::Cache,
+    DirectoryKind::Logs,
+    DirectoryKind::Configuration,
+];
+
+/// Architectures accepted by Personality=.
+const PERSONALITIES: &[&str] = &[
+    "x86",
+    "x86-64",
+    "arm",
+    "arm-be",
+    "arm64",
+    "arm64-be",
+    "ppc",
+    "ppc-le",
+    "ppc64",
+    "ppc64-le",
+    "s390",
+    "s390x",
+    "mips",
+    "mips-le",
+    "mips64",
+    "mips64-le",
+    "sparc",
+    "sparc64",
+    "riscv32",
+    "riscv64",
+    "loongarch64",
+    "alpha",
+    "ia64",
+    "parisc",
+    "parisc64",
+    "sh",
+    "sh64",
+    "m68k",
+];
+
+/// Namespace types accepted by RestrictNamespaces=.
+const NAMESPACES: &[&str] = &["cgroup", "ipc", "net", "mnt", "pid", "user", "uts"];
+
+/// File system protection level.
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#ProtectSystem=
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProtectSystem {
+    /// No protection.
+    No,
+    /// /usr and /boot (or /efi) are read-only.
+    Yes,
+    /// Like `Yes`, and /etc is read-only as well.
+    Full,
+    /// The entire file system hierarchy is read-only, except for the API file systems.
+    Strict,
+}
+
+impl fmt::Display for ProtectSystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProtectSystem::No => write!(f, "no"),
+            ProtectSystem::Yes => write!(f, "yes"),
+            ProtectSystem::Full => write!(f, "full"),
+            ProtectSystem::Strict => write!(f, "strict"),
+        }
+    }
+}
+
+/// Home directory protection level.
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#ProtectHome=
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProtectHome {
+    /// No protection.
+    No,
+    /// Home directories are inaccessible and empty.
+    Yes,
+    /// Home directories are read-only.
+    ReadOnly,
+    /// Home directories are replaced by empty read-only tmpfs mounts.
+    Tmpfs,
+}
+
+impl fmt::Display for ProtectHome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProtectHome::No => write!(f, "no"),
+            ProtectHome::Yes => write!(f, "yes"),
+            ProtectHome::ReadOnly => write!(f, "read-only"),
+            ProtectHome::Tmpfs => write!(f, "tmpfs"),
+        }
+    }
+}
+
+/// Visibility of other processes in /proc.
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#ProtectProc=
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProtectProc {
+    Default,
+    /// Processes owned by other users are hidden.
+    Invisible,
+    /// Only processes that can be traced are visible.
+    Ptraceable,
+    /// Processes owned by other users are visible but inaccessible.
+    NoAccess,
+}
+
+impl fmt::Display for ProtectProc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProtectProc::Default => write!(f, "default"),
+            ProtectProc::Invisible => write!(f, "invisible"),
+            ProtectProc::Ptraceable => write!(f, "ptraceable"),
+            ProtectProc::NoAccess => write!(f, "noaccess"),
+        }
+    }
+}
+
+/// Subset of /proc that is mounted.
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#ProcSubset=
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProcSubset {
+    All,
+    /// Only the process specific directories are visible.
+    Pid,
+}
+
+impl fmt::Display for ProcSubset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProcSubset::All => write!(f, "all"),
+            ProcSubset::Pid => write!(f, "pid"),
+        }
+    }
+}
+
+/// Namespace types processes may create or join.
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#RestrictNamespaces=
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RestrictNamespaces {
+    /// Deny all namespace types.
+    Yes,
+    /// Allow all namespace types.
+    No,
+    /// Allow only the listed namespace types, e.g. `net` or `mnt`.
+    Allow(Vec<String>),
+    /// Deny only the listed namespace types.
+    Deny(Vec<String>),
+}
+
+impl fmt::Display for RestrictNamespaces {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RestrictNamespaces::Yes => write!(f, "yes"),
+            RestrictNamespaces::No => write!(f, "no"),
+            RestrictNamespaces::Allow(namespaces) => write!(f, "{}", namespaces.join(" ")),
+            RestrictNamespaces::Deny(namespaces) => write!(f, "~{}", namespaces.join(" ")),
+        }
+    }
+}
+
+/// Where standard input of executed processes is connected to.
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#StandardInput=
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StandardInput {
+    Null,
+    Tty,
+    /// Like `Tty`, but take the terminal over from other processes.
+    TtyForce,
+    /// Like `Tty`, but fail if the terminal is already in use.
+    TtyFail,
+    /// Read from the data configured with StandardInputText= or StandardInputData=.
+    Data,
+    /// Read from a file or device node.
+    File(PathBuf),
+    /// Read from the socket that activated the service.
+    Socket,
+    /// Read from a file descriptor passed by a socket unit with the given FileDescriptorName=.
+    Fd(String),
+}
+
+impl fmt::Display for StandardInput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StandardInput::Null => write!(f, "null"),
+            StandardInput::Tty => write!(f, "tty"),
+            StandardInput::TtyForce => write!(f, "tty-force"),
+            StandardInput::TtyFail => write!(f, "tty-fail"),
+            StandardInput::Data => write!(f, "data"),
+            StandardInput::File(path) => write!(f, "file:{}", path.display()),
+            StandardInput::Socket => write!(f, "socket"),
+            StandardInput::Fd(name) => write!(f, "fd:{}", name),
+        }
+    }
+}
+
+/// Where standard output or standard error of executed processes is connected to.
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#StandardOutput=
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StandardOutput {
+    /// Use the same target as standard input, or for standard error, standard output.
+    Inherit,
+    Null,
+    Tty,
+    Journal,
+    Kmsg,
+    /// Log to the journal and copy to the system console.
+    JournalConsole,
+    /// Log to the kernel log buffer and copy to the system console.
+    KmsgConsole,
+    /// Write to a file, starting at its beginning without truncating it.
+    File(PathBuf),
+    /// Append to a file.
+    Append(PathBuf),
+    /// Truncate the file when the process is started, then write to it.
+    Truncate(PathBuf),
+    /// Write to the socket that activated the service.
+    Socket,
+    /// Write to a file descriptor passed by a socket unit with the given FileDescriptorName=.
+    Fd(String),
+}
+
+impl fmt::Display for StandardOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StandardOutput::Inherit => write!(f, "inherit"),
+            StandardOutput::Null => write!(f, "null"),
+            StandardOutput::Tty => write!(f, "tty"),
+            StandardOutput::Journal => write!(f, "journal"),
+            StandardOutput::Kmsg => write!(f, "kmsg"),
+            StandardOutput::JournalConsole => write!(f, "journal+console"),
+            StandardOutput::KmsgConsole => write!(f, "kmsg+console"),
+            StandardOutput::File(path) => write!(f, "file:{}", path.display()),
+            StandardOutput::Append(path) => write!(f, "append:{}", path.display()),
+            StandardOutput::Truncate(path) => write!(f, "truncate:{}", path.display()),
+            StandardOutput::Socket => write!(f, "socket"),
+            StandardOutput::Fd(name) => write!(f, "fd:{}", name),
+        }
+    }
+}
+
+/// Syslog facility used when logging to syslog.
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#SyslogFacility=
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyslogFacility {
+    Kern,
+    User,
+    Mail,
+    Daemon,
+    Auth,
+    Syslog,
+    Lpr,
+    News,
+    Uucp,
+    Cron,
+    AuthPriv,
+    Ftp,
+    /// One of the local facilities, `local0` to `local7`.
+    Local(u8),
+}
+
+impl fmt::Display for SyslogFacility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SyslogFacility::Kern => write!(f, "kern"),
+            SyslogFacility::User => write!(f, "user"),
+            SyslogFacility::Mail => write!(f, "mail"),
+            SyslogFacility::Daemon => write!(f, "daemon"),
+            SyslogFacility::Auth => write!(f, "auth"),
+            SyslogFacility::Syslog => write!(f, "syslog"),
+            SyslogFacility::Lpr => write!(f, "lpr"),
+            SyslogFacility::News => write!(f, "news"),
+            SyslogFacility::Uucp => write!(f, "uucp"),
+            SyslogFacility::Cron => write!(f, "cron"),
+            SyslogFacility::AuthPriv => write!(f, "authpriv"),
+            SyslogFacility::Ftp => write!(f, "ftp"),
+            SyslogFacility::Local(n) => write!(f, "local{}", n),
+        }
+    }
+}
+
+/// Syslog log level.
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#LogLevelMax=
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Emerg,
+    Alert,
+    Crit,
+    Err,
+    Warning,
+    Notice,
+    Info,
+    Debug,
+}
+
+impl fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LogLevel::Emerg => write!(f, "emerg"),
+            LogLevel::Alert => write!(f, "alert"),
+            LogLevel::Crit => write!(f, "crit"),
+            LogLevel::Err => write!(f, "err"),
+            LogLevel::Warning => write!(f, "warning"),
+            LogLevel::Notice => write!(f, "notice"),
+            LogLevel::Info => write!(f, "info"),
+            LogLevel::Debug => write!(f, "debug"),
+        }
+    }
+}
+
+/// Kind of per-unit directory managed by systemd.
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#RuntimeDirectory=
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DirectoryKind {
+    Runtime,
+    State,
+    Cache,
+    Logs,
+    Configuration,
+}
+
+impl fmt::Display for DirectoryKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DirectoryKind::Runtime => write!(f, "RuntimeDirectory"),
+            DirectoryKind::State => write!(f, "StateDirectory"),
+            DirectoryKind::Cache => write!(f, "CacheDirectory"),
+            DirectoryKind::Logs => write!(f, "LogsDirectory"),
+            DirectoryKind::Configuration => write!(f, "ConfigurationDirectory"),
+        }
+    }
+}
+
+impl DirectoryKind {
+    /// Returns the directory names of this kind are created below, e.g. `/var/lib` for state directories
+    /// of system services or `$XDG_STATE_HOME` for user services.
+    /// Global units run in the manager of every user, their directories are resolved for the calling user.
+    pub fn base(&self, scope: ManagerScope) -> crate::Result<PathBuf> {
+        match scope {
+            ManagerScope::System => Ok(PathBuf::from(match self {
+                DirectoryKind::Runtime => "/run",
+                DirectoryKind::State => "/var/lib",
+                DirectoryKind::Cache => "/var/cache",
+                DirectoryKind::Logs => "/var/log",
+                DirectoryKind::Configuration => "/etc",
+            })),
+            ManagerScope::User | ManagerScope::Global => match self {
+                DirectoryKind::Runtime => std::env::var_os("XDG_RUNTIME_DIR")
+                    .map(PathBuf::from)
+                    .filter(|dir| dir.is_absolute())
+                    .ok_or_else(|| {
+                        Error::IoError(io::Error::new(
+                            io::ErrorKind::NotFound,
+                            "XDG_RUNTIME_DIR is not set",
+                        ))
+                    }),
+                DirectoryKind::State => xdg_dir("XDG_STATE_HOME", ".local/state"),
+                DirectoryKind::Cache => xdg_dir("XDG_CACHE_HOME", ".cache"),
+                DirectoryKind::Logs => Ok(xdg_dir("XDG_STATE_HOME", ".local/state")?.join("log")),
+                DirectoryKind::Configuration => xdg_dir("XDG_CONFIG_HOME", ".config"),
+            },
+        }
+    }
+}
+
+/// Whether the runtime directories are kept when the unit stops.
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#RuntimeDirectoryPreserve=
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuntimeDirectoryPreserve {
+    No,
+    Yes,
+    /// Keep the directories only while the unit is restarted.
+    Restart,
+}
+
+impl fmt::Display for RuntimeDirectoryPreserve {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuntimeDirectoryPreserve::No => write!(f, "no"),
+            RuntimeDirectoryPreserve::Yes => write!(f, "yes"),
+            RuntimeDirectoryPreserve::Restart => write!(f, "restart"),
+        }
+    }
+}
+
+/// CPU scheduling policy of executed processes.
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#CPUSchedulingPolicy=
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CpuSchedulingPolicy {
+    Other,
+    Batch,
+    Idle,
+    /// Realtime first-in, first-out scheduling.
+    Fifo,
+    /// Realtime round-robin scheduling.
+    Rr,
+}
+
+impl fmt::Display for CpuSchedulingPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CpuSchedulingPolicy::Other => write!(f, "other"),
+            CpuSchedulingPolicy::Batch => write!(f, "batch"),
+            CpuSchedulingPolicy::Idle => write!(f, "idle"),
+            CpuSchedulingPolicy::Fifo => write!(f, "fifo"),
+            CpuSchedulingPolicy::Rr => write!(f, "rr"),
+        }
+    }
+}
+
+impl CpuSchedulingPolicy {
+    pub fn is_realtime(&self) -> bool {
+        matches!(self, CpuSchedulingPolicy::Fifo | CpuSchedulingPolicy::Rr)
+    }
+}
+
+/// NUMA memory policy of executed processes.
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#NUMAPolicy=
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumaPolicy {
+    Default,
+    /// Prefer allocations on the nodes of NUMAMask=.
+    Preferred,
+    /// Restrict allocations to the nodes of NUMAMask=.
+    Bind,
+    /// Interleave allocations across the nodes of NUMAMask=.
+    Interleave,
+    /// Allocate on the node of the CPU that triggered the allocation.
+    Local,
+}
+
+impl fmt::Display for NumaPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NumaPolicy::Default => write!(f, "default"),
+            NumaPolicy::Preferred => write!(f, "preferred"),
+            NumaPolicy::Bind => write!(f, "bind"),
+            NumaPolicy::Interleave => write!(f, "interleave"),
+            NumaPolicy::Local => write!(f, "local"),
+        }
+    }
+}
+
+/// I/O scheduling class of executed processes.
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#IOSchedulingClass=
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IoSchedulingClass {
+    Realtime,
+    BestEffort,
+    Idle,
+}
+
+impl fmt::Display for IoSchedulingClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IoSchedulingClass::Realtime => write!(f, "realtime"),
+            IoSchedulingClass::BestEffort => write!(f, "best-effort"),
+            IoSchedulingClass::Idle => write!(f, "idle"),
+        }
+    }
+}
 
 /// Execution environment options
 /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#Options
@@ -14,37 +487,9 @@ pub struct Exec {
     /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#RootDirectory=
     pub root_directory: Option<PathBuf>,
 
-    /// Image to mount as root directory.
+    /// Disk image mounted as root directory, with its options and verity data.
     /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#RootImage=
-    pub root_image: Option<PathBuf>,
-
-    /// Options for mounting the root image.
-    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#RootImageOptions=
-    pub root_image_options: Option<Vec<String>>,
-
-    /// Verity data for the root image.
-    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#RootVerity=
-    pub root_verity: Option<PathBuf>,
-
-    /// Hash for root verity.
-    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#RootHash=
-    pub root_hash: Option<String>,
-
-    /// Signature for root hash.
-    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#RootHashSignature=
-    pub root_hash_signature: Option<PathBuf>,
-
-    /// Path to cryptographic key for root hash signature.
-    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#RootHashSignature=
-    pub root_hash_signature_key: Option<PathBuf>,
-
-    /// Path to the PKCS#7 signature of the root hash.
-    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#RootHashSignature=
-    pub root_hash_signature_pcr_private_key: Option<PathBuf>,
-
-    /// Path to the PKCS#7 signature of the root hash.
-    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#RootHashSignature=
-    pub root_hash_signature_pcr_public_key: Option<PathBuf>,
+    pub root_image: Option<RootImage>,
 
     /// Ephemeral root directory.
     /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#RootEphemeral=
@@ -84,13 +529,79 @@ pub struct Exec {
     /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#UnsetEnvironment=
     pub unset_environment: Option<Vec<String>>,
 
-    // Resource limits - these would be extensive, implementing just a few for now
+    // Credentials
+    /// Credentials read from files, credential stores or sockets.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#LoadCredential=ID:PATH
+    pub load_credential: Option<Vec<LoadCredential>>,
+
+    /// Encrypted credentials read from files, credential stores or sockets.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#LoadCredentialEncrypted=ID:PATH
+    pub load_credential_encrypted: Option<Vec<LoadCredential>>,
+
+    /// Credentials with literal data.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#SetCredential=ID:VALUE
+    pub set_credential: Option<Vec<SetCredential>>,
+
+    /// Credentials with encrypted, base64 encoded data.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#SetCredentialEncrypted=ID:VALUE
+    pub set_credential_encrypted: Option<Vec<SetCredential>>,
+
+    /// Globs of credentials to import from the service manager's credentials.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#ImportCredential=GLOB
+    pub import_credential: Option<Vec<String>>,
+
+    // Logging and standard I/O settings
+    /// Standard input of executed processes.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#StandardInput=
+    pub standard_input: Option<StandardInput>,
+
+    /// Standard output of executed processes.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#StandardOutput=
+    pub standard_output: Option<StandardOutput>,
+
+    /// Standard error of executed processes.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#StandardError=
+    pub standard_error: Option<StandardOutput>,
+
+    /// Identifier prefixed to log lines.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#SyslogIdentifier=
+    pub syslog_identifier: Option<String>,
+
+    /// Syslog facility of log lines.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#SyslogFacility=
+    pub syslog_facility: Option<SyslogFacility>,
+
+    /// Drop log messages with a lower priority than this level.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#LogLevelMax=
+    pub log_level_max: Option<LogLevel>,
+
+    /// Extra journal fields in FIELD=value format added to all log records.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#LogExtraFields=
+    pub log_extra_fields: Option<Vec<String>>,
+
+    /// Interval of the journal rate limit.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#LogRateLimitIntervalSec=
+    pub log_rate_limit_interval_sec: Option<String>,
+
+    /// Number of messages accepted within the rate limit interval.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#LogRateLimitBurst=
+    pub log_rate_limit_burst: Option<u32>,
+
+    /// Journal namespace to log to.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#LogNamespace=
+    pub log_namespace: Option<String>,
+
+    // Resource limits
+    pub limits: Option<Limits>,
+
     /// CPU time limit.
     /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#LimitCPU=
+    #[deprecated(since = "0.2.0", note = "use `limits` with `Limits::cpu` instead")]
     pub limit_cpu: Option<String>,
 
     /// File size limit.
     /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#LimitFSIZE=
+    #[deprecated(since = "0.2.0", note = "use `limits` with `Limits::fsize` instead")]
     pub limit_fsize: Option<String>,
 
     // Security settings
@@ -118,6 +629,70 @@ pub struct Exec {
     /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#ProtectControlGroups=
     pub protect_control_groups: Option<bool>,
 
+    /// Mount /usr, /boot and optionally /etc or the whole file system read-only.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#ProtectSystem=
+    pub protect_system: Option<ProtectSystem>,
+
+    /// Make /home, /root and /run/user inaccessible, read-only or empty.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#ProtectHome=
+    pub protect_home: Option<ProtectHome>,
+
+    /// Restrict access to process information in /proc.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#ProtectProc=
+    pub protect_proc: Option<ProtectProc>,
+
+    /// Hide non-process entries of /proc.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#ProcSubset=
+    pub proc_subset: Option<ProcSubset>,
+
+    /// Protect the hostname from being changed.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#ProtectHostname=
+    pub protect_hostname: Option<bool>,
+
+    /// Deny writes to the system and hardware clock.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#ProtectClock=
+    pub protect_clock: Option<bool>,
+
+    /// Deny access to the kernel log ring buffer.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#ProtectKernelLogs=
+    pub protect_kernel_logs: Option<bool>,
+
+    /// Restrict access to Linux namespace functionality.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#RestrictNamespaces=
+    pub restrict_namespaces: Option<RestrictNamespaces>,
+
+    /// Lock down the personality(2) system call.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#LockPersonality=
+    pub lock_personality: Option<bool>,
+
+    /// Deny creating memory mappings that are writable and executable at the same time.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#MemoryDenyWriteExecute=
+    pub memory_deny_write_execute: Option<bool>,
+
+    /// Deny enabling realtime scheduling.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#RestrictRealtime=
+    pub restrict_realtime: Option<bool>,
+
+    /// Deny setting the set-user-ID and set-group-ID bits on files.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#RestrictSUIDSGID=
+    pub restrict_suid_sgid: Option<bool>,
+
+    /// Remove IPC objects of the user and group when the unit stops.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#RemoveIPC=
+    pub remove_ipc: Option<bool>,
+
+    /// Run processes in a user namespace with a minimal user and group mapping.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#PrivateUsers=
+    pub private_users: Option<bool>,
+
+    /// Run processes in a private IPC namespace.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#PrivateIPC=
+    pub private_ipc: Option<bool>,
+
+    /// Allocate a dynamic user and group for the unit while it is running.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#DynamicUser=
+    pub dynamic_user: Option<bool>,
+
     // Capability settings
     /// Capability bounding set.
     /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#CapabilityBoundingSet=
@@ -127,6 +702,24 @@ pub struct Exec {
     /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#AmbientCapabilities=
     pub ambient_capabilities: Option<Vec<String>>,
 
+    // System call filtering
+    /// System call allow and deny lists, applied in order.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#SystemCallFilter=
+    pub system_call_filter: Option<Vec<SystemCallFilter>>,
+
+    /// Architectures whose system calls may be used.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#SystemCallArchitectures=
+    pub system_call_architectures: Option<Vec<String>>,
+
+    /// Error returned by filtered system calls instead of killing the process.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#SystemCallErrorNumber=
+    /// An errno name like EPERM, a number, or kill
+    pub system_call_error_number: Option<String>,
+
+    /// System calls to log, or with a deny list, system calls not to log.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#SystemCallLog=
+    pub system_call_log: Option<SystemCallFilter>,
+
     // Process settings
     /// Nice level.
     /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#Nice=
@@ -136,24 +729,153 @@ pub struct Exec {
     /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#OOMScoreAdjust=
     pub oom_score_adjust: Option<i32>,
 
+    /// CPU scheduling policy.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#CPUSchedulingPolicy=
+    pub cpu_scheduling_policy: Option<CpuSchedulingPolicy>,
+
+    /// CPU scheduling priority, 1 to 99 for the realtime policies.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#CPUSchedulingPriority=
+    pub cpu_scheduling_priority: Option<i32>,
+
+    /// Reset the scheduling policy and priority of forked processes.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#CPUSchedulingResetOnFork=
+    pub cpu_scheduling_reset_on_fork: Option<bool>,
+
+    /// CPUs executed processes may run on.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#CPUAffinity=
+    pub cpu_affinity: Option<CpuSet>,
+
+    /// NUMA memory policy.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#NUMAPolicy=
+    pub numa_policy: Option<NumaPolicy>,
+
+    /// NUMA nodes the NUMA policy applies to.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#NUMAMask=
+    pub numa_mask: Option<CpuSet>,
+
+    /// I/O scheduling class.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#IOSchedulingClass=
+    pub io_scheduling_class: Option<IoSchedulingClass>,
+
+    /// I/O scheduling priority, 0 (highest) to 7 (lowest).
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#IOSchedulingPriority=
+    pub io_scheduling_priority: Option<u8>,
+
+    /// Execution domain reported by uname(2), e.g. x86 for a 32-bit personality.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#Personality=
+    pub personality: Option<String>,
+
     // File system settings
     /// Read-write paths.
     /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#ReadWritePaths=
-    pub read_write_paths: Option<Vec<PathBuf>>,
+    pub read_write_paths: Option<Vec<NamespacePath>>,
 
     /// Read-only paths.
     /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#ReadOnlyPaths=
-    pub read_only_paths: Option<Vec<PathBuf>>,
+    pub read_only_paths: Option<Vec<NamespacePath>>,
 
     /// Inaccessible paths.
     /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#InaccessiblePaths=
-    pub inaccessible_paths: Option<Vec<PathBuf>>,
+    pub inaccessible_paths: Option<Vec<NamespacePath>>,
+
+    /// Paths programs may be executed from, even below NoExecPaths=.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#ExecPaths=
+    pub exec_paths: Option<Vec<NamespacePath>>,
+
+    /// Paths programs may not be executed from.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#NoExecPaths=
+    pub no_exec_paths: Option<Vec<NamespacePath>>,
+
+    /// Read-write bind mounts.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#BindPaths=
+    pub bind_paths: Option<Vec<BindPath>>,
+
+    /// Read-only bind mounts.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#BindReadOnlyPaths=
+    pub bind_read_only_paths: Option<Vec<BindPath>>,
+
+    /// Temporary file systems mounted over paths.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#TemporaryFileSystem=
+    pub temporary_file_system: Option<Vec<TemporaryFileSystem>>,
+
+    /// Mount propagation of the unit's mount namespace.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#MountFlags=
+    pub mount_flags: Option<MountFlags>,
+
+    /// Mount /proc, /sys, /dev and /run in the unit's mount namespace.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#MountAPIVFS=
+    pub mount_api_vfs: Option<bool>,
+
+    /// Disk images mounted into the unit's mount namespace.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#MountImages=
+    pub mount_images: Option<Vec<MountImage>>,
+
+    /// System extension images overlaid on /usr and /opt.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#ExtensionImages=
+    pub extension_images: Option<Vec<ExtensionImage>>,
+
+    /// Image policy the images of MountImages= must satisfy.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#MountImagePolicy=
+    pub mount_image_policy: Option<String>,
+
+    /// Image policy the images of ExtensionImages= must satisfy.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#ExtensionImagePolicy=
+    pub extension_image_policy: Option<String>,
+
+    // Managed directories
+    /// Directories below /run created when the unit starts.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#RuntimeDirectory=
+    pub runtime_directory: Option<Vec<String>>,
+
+    /// Directories below /var/lib created when the unit starts.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#StateDirectory=
+    pub state_directory: Option<Vec<String>>,
+
+    /// Directories below /var/cache created when the unit starts.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#CacheDirectory=
+    pub cache_directory: Option<Vec<String>>,
+
+    /// Directories below /var/log created when the unit starts.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#LogsDirectory=
+    pub logs_directory: Option<Vec<String>>,
+
+    /// Directories below /etc created when the unit starts.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#ConfigurationDirectory=
+    pub configuration_directory: Option<Vec<String>>,
+
+    /// Access mode of the runtime directories.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#RuntimeDirectoryMode=
+    pub runtime_directory_mode: Option<u32>,
+
+    /// Access mode of the state directories.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#RuntimeDirectoryMode=
+    pub state_directory_mode: Option<u32>,
+
+    /// Access mode of the cache directories.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#RuntimeDirectoryMode=
+    pub cache_directory_mode: Option<u32>,
+
+    /// Access mode of the logs directories.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#RuntimeDirectoryMode=
+    pub logs_directory_mode: Option<u32>,
+
+    /// Access mode of the configuration directories.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#RuntimeDirectoryMode=
+    pub configuration_directory_mode: Option<u32>,
+
+    /// Keep the runtime directories when the unit stops.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#RuntimeDirectoryPreserve=
+    pub runtime_directory_preserve: Option<RuntimeDirectoryPreserve>,
 
     // Network settings
     /// Private network.
     /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#PrivateNetwork=
     pub private_network: Option<bool>,
 
+    /// Socket address families processes may use.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#RestrictAddressFamilies=
+    pub restrict_address_families: Option<AddressFamilies>,
+
     /// Network namespace path.
     /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#NetworkNamespacePath=
     pub network_namespace_path: Option<PathBuf>,
@@ -168,25 +890,8 @@ impl fmt::Display for Exec {
         if let Some(ref path) = self.root_directory {
             writeln!(buf, "RootDirectory={}", path.display())?;
         }
-        if let Some(ref path) = self.root_image {
-            writeln!(buf, "RootImage={}", path.display())?;
-        }
-        write_vec!(buf, self.root_image_options, "RootImageOptions");
-        if let Some(ref path) = self.root_verity {
-            writeln!(buf, "RootVerity={}", path.display())?;
-        }
-        write_option!(buf, self.root_hash, "RootHash");
-        if let Some(ref path) = self.root_hash_signature {
-            writeln!(buf, "RootHashSignature={}", path.display())?;
-        }
-        if let Some(ref path) = self.root_hash_signature_key {
-            writeln!(buf, "RootHashSignature={}", path.display())?;
-        }
-        if let Some(ref path) = self.root_hash_signature_pcr_private_key {
-            writeln!(buf, "RootHashSignature={}", path.display())?;
-        }
-        if let Some(ref path) = self.root_hash_signature_pcr_public_key {
-            writeln!(buf, "RootHashSignature={}", path.display())?;
+        if let Some(ref image) = self.root_image {
+            image.fmt(buf)?;
         }
         write_bool!(buf, self.root_ephemeral, "RootEphemeral");
 
@@ -206,9 +911,59 @@ impl fmt::Display for Exec {
         write_vec!(buf, self.pass_environment, "PassEnvironment");
         write_vec!(buf, self.unset_environment, "UnsetEnvironment");
 
+        // Credentials
+        for (credentials, key) in [
+            (&self.load_credential, "LoadCredential"),
+            (&self.load_credential_encrypted, "LoadCredentialEncrypted"),
+        ] {
+            if let Some(credentials) = credentials {
+                for credential in credentials {
+                    writeln!(buf, "{}={}", key, credential)?;
+                }
+            }
+        }
+        for (credentials, key) in [
+            (&self.set_credential, "SetCredential"),
+            (&self.set_credential_encrypted, "SetCredentialEncrypted"),
+        ] {
+            if let Some(credentials) = credentials {
+                for credential in credentials {
+                    writeln!(buf, "{}={}", key, credential)?;
+                }
+            }
+        }
+        write_vec_multi!(buf, self.import_credential, "ImportCredential");
+
+        // Logging and standard I/O settings
+        write_option!(buf, self.standard_input, "StandardInput");
+        write_option!(buf, self.standard_output, "StandardOutput");
+        write_option!(buf, self.standard_error, "StandardError");
+        write_option!(buf, self.syslog_identifier, "SyslogIdentifier");
+        write_option!(buf, self.syslog_facility, "SyslogFacility");
+        write_option!(buf, self.log_level_max, "LogLevelMax");
+        write_vec_multi!(buf, self.log_extra_fields, "LogExtraFields");
+        write_option!(
+            buf,
+            self.log_rate_limit_interval_sec,
+            "LogRateLimitIntervalSec"
+        );
+        write_option!(buf, self.log_rate_limit_burst, "LogRateLimitBurst");
+        write_option!(buf, self.log_namespace, "LogNamespace");
+
         // Resource limits
-        write_option!(buf, self.limit_cpu, "LimitCPU");
-        write_option!(buf, self.limit_fsize, "LimitFSIZE");
+        if let Some(ref limits) = self.limits {
+            limits.fmt(buf)?;
+        }
+        #[allow(deprecated)]
+        {
+            let limits = self.limits.as_ref();
+            if limits.is_none_or(|limits| limits.cpu.is_none()) {
+                write_option!(buf, self.limit_cpu, "LimitCPU");
+            }
+            if limits.is_none_or(|limits| limits.fsize.is_none()) {
+                write_option!(buf, self.limit_fsize, "LimitFSIZE");
+            }
+        }
 
         // Security settings
         write_bool!(buf, self.no_new_privileges, "NoNewPrivileges");
@@ -217,11 +972,45 @@ impl fmt::Display for Exec {
         write_bool!(buf, self.protect_kernel_tunables, "ProtectKernelTunables");
         write_bool!(buf, self.protect_kernel_modules, "ProtectKernelModules");
         write_bool!(buf, self.protect_control_groups, "ProtectControlGroups");
+        write_option!(buf, self.protect_system, "ProtectSystem");
+        write_option!(buf, self.protect_home, "ProtectHome");
+        write_option!(buf, self.protect_proc, "ProtectProc");
+        write_option!(buf, self.proc_subset, "ProcSubset");
+        write_bool!(buf, self.protect_hostname, "ProtectHostname");
+        write_bool!(buf, self.protect_clock, "ProtectClock");
+        write_bool!(buf, self.protect_kernel_logs, "ProtectKernelLogs");
+        write_option!(buf, self.restrict_namespaces, "RestrictNamespaces");
+        write_bool!(buf, self.lock_personality, "LockPersonality");
+        write_bool!(
+            buf,
+            self.memory_deny_write_execute,
+            "MemoryDenyWriteExecute"
+        );
+        write_bool!(buf, self.restrict_realtime, "RestrictRealtime");
+        write_bool!(buf, self.restrict_suid_sgid, "RestrictSUIDSGID");
+        write_bool!(buf, self.remove_ipc, "RemoveIPC");
+        write_bool!(buf, self.private_users, "PrivateUsers");
+        write_bool!(buf, self.private_ipc, "PrivateIPC");
+        write_bool!(buf, self.dynamic_user, "DynamicUser");
 
         // Capability settings
         write_vec!(buf, self.capability_bounding_set, "CapabilityBoundingSet");
         write_vec!(buf, self.ambient_capabilities, "AmbientCapabilities");
 
+        // System call filtering
+        if let Some(ref filters) = self.system_call_filter {
+            for filter in filters {
+                writeln!(buf, "SystemCallFilter={}", filter)?;
+            }
+        }
+        write_vec!(
+            buf,
+            self.system_call_architectures,
+            "SystemCallArchitectures"
+        );
+        write_option!(buf, self.system_call_error_number, "SystemCallErrorNumber");
+        write_option!(buf, self.system_call_log, "SystemCallLog");
+
         // Process settings
         if let Some(nice) = self.nice {
             writeln!(buf, "Nice={}", nice)?;
@@ -229,26 +1018,64 @@ impl fmt::Display for Exec {
         if let Some(score) = self.oom_score_adjust {
             writeln!(buf, "OOMScoreAdjust={}", score)?;
         }
+        write_option!(buf, self.cpu_scheduling_policy, "CPUSchedulingPolicy");
+        write_option!(buf, self.cpu_scheduling_priority, "CPUSchedulingPriority");
+        write_bool!(
+            buf,
+            self.cpu_scheduling_reset_on_fork,
+            "CPUSchedulingResetOnFork"
+        );
+        write_option!(buf, self.cpu_affinity, "CPUAffinity");
+        write_option!(buf, self.numa_policy, "NUMAPolicy");
+        write_option!(buf, self.numa_mask, "NUMAMask");
+        write_option!(buf, self.io_scheduling_class, "IOSchedulingClass");
+        write_option!(buf, self.io_scheduling_priority, "IOSchedulingPriority");
+        write_option!(buf, self.personality, "Personality");
 
         // File system settings
-        if let Some(ref paths) = self.read_write_paths {
-            for path in paths {
-                writeln!(buf, "ReadWritePaths={}", path.display())?;
-            }
-        }
-        if let Some(ref paths) = self.read_only_paths {
-            for path in paths {
-                writeln!(buf, "ReadOnlyPaths={}", path.display())?;
-            }
-        }
-        if let Some(ref paths) = self.inaccessible_paths {
-            for path in paths {
-                writeln!(buf, "InaccessiblePaths={}", path.display())?;
-            }
-        }
+        write_vec_multi!(buf, self.read_write_paths, "ReadWritePaths");
+        write_vec_multi!(buf, self.read_only_paths, "ReadOnlyPaths");
+        write_vec_multi!(buf, self.inaccessible_paths, "InaccessiblePaths");
+        write_vec_multi!(buf, self.exec_paths, "ExecPaths");
+        write_vec_multi!(buf, self.no_exec_paths, "NoExecPaths");
+        write_vec_multi!(buf, self.bind_paths, "BindPaths");
+        write_vec_multi!(buf, self.bind_read_only_paths, "BindReadOnlyPaths");
+        write_vec_multi!(buf, self.temporary_file_system, "TemporaryFileSystem");
+        write_option!(buf, self.mount_flags, "MountFlags");
+        write_bool!(buf, self.mount_api_vfs, "MountAPIVFS");
+        write_vec_multi!(buf, self.mount_images, "MountImages");
+        write_option!(buf, self.mount_image_policy, "MountImagePolicy");
+        write_vec_multi!(buf, self.extension_images, "ExtensionImages");
+        write_option!(buf, self.extension_image_policy, "ExtensionImagePolicy");
+
+        // Managed directories
+        write_vec!(buf, self.runtime_directory, "RuntimeDirectory");
+        write_vec!(buf, self.state_directory, "StateDirectory");
+        write_vec!(buf, self.cache_directory, "CacheDirectory");
+        write_vec!(buf, self.logs_directory, "LogsDirectory");
+        write_vec!(buf, self.configuration_directory, "ConfigurationDirectory");
+        write_mode!(buf, self.runtime_directory_mode, "RuntimeDirectoryMode");
+        write_mode!(buf, self.state_directory_mode, "StateDirectoryMode");
+        write_mode!(buf, self.cache_directory_mode, "CacheDirectoryMode");
+        write_mode!(buf, self.logs_directory_mode, "LogsDirectoryMode");
+        write_mode!(
+            buf,
+            self.configuration_directory_mode,
+            "ConfigurationDirectoryMode"
+        );
+        write_option!(
+            buf,
+            self.runtime_directory_preserve,
+            "RuntimeDirectoryPreserve"
+        );
 
         // Network settings
         write_bool!(buf, self.private_network, "PrivateNetwork");
+        write_option!(
+            buf,
+            self.restrict_address_families,
+            "RestrictAddressFamilies"
+        );
         if let Some(ref path) = self.network_namespace_path {
             writeln!(buf, "NetworkNamespacePath={}", path.display())?;
         }
@@ -282,6 +1109,57 @@ impl Exec {
             }
         }
 
+        // Validate CPU scheduling priority (1 to 99 for realtime policies, 0 otherwise)
+        if let Some(priority) = self.cpu_scheduling_priority {
+            let range = match self.cpu_scheduling_policy {
+                Some(policy) if policy.is_realtime() => 1..=99,
+                Some(_) => 0..=0,
+                None => 0..=99,
+            };
+            if !range.contains(&priority) {
+                return Err(format!(
+                    "CPUSchedulingPriority {} must be between {} and {}",
+                    priority,
+                    range.start(),
+                    range.end()
+                ));
+            }
+        }
+
+        // Validate CPU and NUMA node sets
+        for (cpus, key) in [
+            (&self.cpu_affinity, "CPUAffinity"),
+            (&self.numa_mask, "NUMAMask"),
+        ] {
+            if let Some(cpus) = cpus {
+                cpus.validate().map_err(|e| format!("{}: {}", key, e))?;
+            }
+        }
+        if let Some(policy @ (NumaPolicy::Bind | NumaPolicy::Interleave)) = self.numa_policy
+            && self.numa_mask.as_ref().is_none_or(CpuSet::is_empty)
+        {
+            return Err(format!(
+                "NUMAPolicy={} requires a non-empty NUMAMask",
+                policy
+            ));
+        }
+
+        // Validate IO scheduling priority (0 to 7)
+        if let Some(priority) = self.io_scheduling_priority
+            && priority > 7
+        {
+            return Err(format!(
+                "IOSchedulingPriority {} must be between 0 and 7",
+                priority
+            ));
+        }
+
+        if let Some(ref personality) = self.personality
+            && !PERSONALITIES.contains(&personality.as_str())
+        {
+            return Err(format!("Unknown Personality '{}'", personality));
+        }
+
         // Validate environment variables (basic KEY=VALUE format)
         if let Some(ref env_vars) = self.environment {
             for env_var in env_vars {
@@ -342,41 +1220,320 @@ impl Exec {
             }
         }
 
-        // Validate supplementary groups (should not be empty)
-        if let Some(ref groups) = self.supplementary_groups {
-            for group in groups {
-                if group.is_empty() {
-                    return Err("Supplementary group names cannot be empty".to_string());
+        // Validate namespace types
+        if let Some(
+            RestrictNamespaces::Allow(ref namespaces) | RestrictNamespaces::Deny(ref namespaces),
+        ) = self.restrict_namespaces
+        {
+            if namespaces.is_empty() {
+                return Err("RestrictNamespaces must list at least one namespace type".to_string());
+            }
+            for namespace in namespaces {
+                if !NAMESPACES.contains(&namespace.as_str()) {
+                    return Err(format!(
+                        "Unknown namespace type '{}' in RestrictNamespaces, expected one of: {}",
+                        namespace,
+                        NAMESPACES.join(", ")
+                    ));
                 }
             }
         }
 
-        // Validate root image options (basic format check)
-        if let Some(ref options) = self.root_image_options {
-            for option in options {
-                if option.is_empty() {
-                    return Err("RootImageOptions cannot contain empty strings".to_string());
+        // A dynamic user is allocated at runtime and can never be root
+        if self.dynamic_user == Some(true)
+            && let Some(ref user) = self.user
+            && (user == "root" || user == "0")
+        {
+            return Err(format!("DynamicUser cannot be combined with User={}", user));
+        }
+
+        // Validate credentials
+        for (credentials, key) in [
+            (&self.load_credential, "LoadCredential"),
+            (&self.load_credential_encrypted, "LoadCredentialEncrypted"),
+        ] {
+            if let Some(credentials) = credentials {
+                for credential in credentials {
+                    credential.validate(key)?;
                 }
             }
         }
+        if let Some(ref credentials) = self.set_credential {
+            for credential in credentials {
+                credential.validate("SetCredential")?;
+            }
+        }
+        if let Some(ref credentials) = self.set_credential_encrypted {
+            for credential in credentials {
+                credential.validate_encrypted("SetCredentialEncrypted")?;
+            }
+        }
+        if let Some(ref globs) = self.import_credential {
+            for glob in globs {
+                credential::validate_credential_glob(glob)?;
+            }
+        }
 
-        Ok(())
-    }
-
-    // Builder pattern setters - implementing just a few key ones for now
-    pub fn working_directory(mut self, value: PathBuf) -> Self {
-        self.working_directory = Some(value);
-        self
-    }
+        // Validate logging and standard I/O
+        if let Some(ref input) = self.standard_input {
+            match input {
+                StandardInput::File(path) => validate_io_path(path, "StandardInput")?,
+                StandardInput::Fd(name) => validate_fd_name(name, "StandardInput")?,
+                _ => {}
+            }
+        }
+        for (output, key) in [
+            (&self.standard_output, "StandardOutput"),
+            (&self.standard_error, "StandardError"),
+        ] {
+            match output {
+                Some(
+                    StandardOutput::File(path)
+                    | StandardOutput::Append(path)
+                    | StandardOutput::Truncate(path),
+                ) => validate_io_path(path, key)?,
+                Some(StandardOutput::Fd(name)) => validate_fd_name(name, key)?,
+                _ => {}
+            }
+        }
+        if let Some(SyslogFacility::Local(n)) = self.syslog_facility
+            && n > 7
+        {
+            return Err(format!(
+                "SyslogFacility local{} must be between local0 and local7",
+                n
+            ));
+        }
+        if let Some(ref identifier) = self.syslog_identifier
+            && (identifier.is_empty() || identifier.chars().any(char::is_whitespace))
+        {
+            return Err(format!(
+                "SyslogIdentifier '{}' must be non-empty and cannot contain whitespace",
+                identifier
+            ));
+        }
+        if let Some(ref fields) = self.log_extra_fields {
+            for field in fields {
+                let name = field.split_once('=').map(|(name, _)| name).ok_or_else(|| {
+                    format!(
+                        "LogExtraFields entry '{}' must be in FIELD=value format",
+                        field
+                    )
+                })?;
+                // Journal field names consist of uppercase letters, digits and underscores,
+                // fields starting with an underscore are reserved for the journal itself
+                if name.is_empty()
+                    || name.starts_with('_')
+                    || name.starts_with(|c: char| c.is_ascii_digit())
+                    || !name
+                        .chars()
+                        .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+                {
+                    return Err(format!(
+                        "Invalid journal field name '{}' in LogExtraFields",
+                        name
+                    ));
+                }
+            }
+        }
+        if let Some(ref namespace) = self.log_namespace
+            && (namespace.is_empty()
+                || !namespace
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "_-.".contains(c)))
+        {
+            return Err(format!("Invalid LogNamespace '{}'", namespace));
+        }
 
-    pub fn user(mut self, value: impl Into<String>) -> Self {
-        self.user = Some(value.into());
-        self
-    }
+        if let Some(ref limits) = self.limits {
+            limits.validate()?;
+        }
+        #[allow(deprecated)]
+        if let Some(ref limits) = self.limits {
+            if self.limit_cpu.is_some() && limits.cpu.is_some() {
+                return Err("LimitCPU= is set by both limit_cpu and limits".to_string());
+            }
+            if self.limit_fsize.is_some() && limits.fsize.is_some() {
+                return Err("LimitFSIZE= is set by both limit_fsize and limits".to_string());
+            }
+        }
 
-    pub fn group(mut self, value: impl Into<String>) -> Self {
-        self.group = Some(value.into());
-        self
+        // Validate file system namespace
+        for (paths, key) in [
+            (&self.read_write_paths, "ReadWritePaths"),
+            (&self.read_only_paths, "ReadOnlyPaths"),
+            (&self.inaccessible_paths, "InaccessiblePaths"),
+            (&self.exec_paths, "ExecPaths"),
+            (&self.no_exec_paths, "NoExecPaths"),
+        ] {
+            if let Some(paths) = paths {
+                for path in paths {
+                    path.validate(key)?;
+                }
+            }
+        }
+        for (binds, key) in [
+            (&self.bind_paths, "BindPaths"),
+            (&self.bind_read_only_paths, "BindReadOnlyPaths"),
+        ] {
+            if let Some(binds) = binds {
+                for bind in binds {
+                    bind.validate(key)?;
+                }
+            }
+        }
+        if let Some(ref filesystems) = self.temporary_file_system {
+            for filesystem in filesystems {
+                filesystem.validate()?;
+            }
+        }
+        if let Some(ref images) = self.mount_images {
+            for image in images {
+                image.validate()?;
+            }
+        }
+        if let Some(ref images) = self.extension_images {
+            for image in images {
+                image.validate()?;
+            }
+        }
+        if let Some(ref image) = self.root_image {
+            image.validate()?;
+        }
+        for (policy, key) in [
+            (&self.mount_image_policy, "MountImagePolicy"),
+            (&self.extension_image_policy, "ExtensionImagePolicy"),
+        ] {
+            if let Some(policy) = policy {
+                namespace::validate_image_policy(policy, key)?;
+            }
+        }
+
+        // Validate network settings
+        if let Some(ref families) = self.restrict_address_families {
+            families.validate()?;
+        }
+        if let Some(ref path) = self.network_namespace_path
+            && !path.is_absolute()
+        {
+            return Err(format!(
+                "NetworkNamespacePath '{}' must be an absolute path",
+                path.display()
+            ));
+        }
+
+        // Validate managed directories
+        for kind in DIRECTORY_KINDS {
+            if let Some(names) = self.directories(kind) {
+                for name in names {
+                    validate_directory_name(name, kind)?;
+                }
+            }
+        }
+        for (mode, key) in [
+            (self.runtime_directory_mode, "RuntimeDirectoryMode"),
+            (self.state_directory_mode, "StateDirectoryMode"),
+            (self.cache_directory_mode, "CacheDirectoryMode"),
+            (self.logs_directory_mode, "LogsDirectoryMode"),
+            (
+                self.configuration_directory_mode,
+                "ConfigurationDirectoryMode",
+            ),
+        ] {
+            if let Some(mode) = mode
+                && mode > 0o7777
+            {
+                return Err(format!("{} {:o} is not a valid file mode", key, mode));
+            }
+        }
+
+        // Validate system call filtering
+        if let Some(ref filters) = self.system_call_filter {
+            for filter in filters {
+                filter.validate()?;
+            }
+        }
+        if let Some(ref archs) = self.system_call_architectures {
+            for arch in archs {
+                syscall::validate_architecture(arch)?;
+            }
+        }
+        if let Some(ref errno) = self.system_call_error_number
+            && errno != "kill"
+        {
+            syscall::validate_errno(errno, "SystemCallErrorNumber")?;
+        }
+        if let Some(ref log) = self.system_call_log {
+            log.validate_as("SystemCallLog", false)?;
+        }
+
+        // Validate supplementary groups (should not be empty)
+        if let Some(ref groups) = self.supplementary_groups {
+            for group in groups {
+                if group.is_empty() {
+                    return Err("Supplementary group names cannot be empty".to_string());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the directory names configured for `kind`.
+    pub fn directories(&self, kind: DirectoryKind) -> Option<&Vec<String>> {
+        match kind {
+            DirectoryKind::Runtime => self.runtime_directory.as_ref(),
+            DirectoryKind::State => self.state_directory.as_ref(),
+            DirectoryKind::Cache => self.cache_directory.as_ref(),
+            DirectoryKind::Logs => self.logs_directory.as_ref(),
+            DirectoryKind::Configuration => self.configuration_directory.as_ref(),
+        }
+    }
+
+    /// Returns the absolute paths of all managed directories the unit gets when run by the
+    /// service manager of `scope`, e.g. `/var/lib/app` for `StateDirectory=app` of a system service.
+    pub fn managed_directories(&self, scope: ManagerScope) -> crate::Result<Vec<PathBuf>> {
+        let mut paths = Vec::new();
+        for kind in DIRECTORY_KINDS {
+            if let Some(names) = self.directories(kind)
+                && !names.is_empty()
+            {
+                let base = kind.base(scope)?;
+                paths.extend(names.iter().map(|name| base.join(name)));
+            }
+        }
+        Ok(paths)
+    }
+
+    // Builder pattern setters - implementing just a few key ones for now
+    pub fn working_directory(mut self, value: PathBuf) -> Self {
+        self.working_directory = Some(value);
+        self
+    }
+
+    pub fn root_directory(mut self, value: impl Into<PathBuf>) -> Self {
+        self.root_directory = Some(value.into());
+        self
+    }
+
+    pub fn root_image(mut self, value: RootImage) -> Self {
+        self.root_image = Some(value);
+        self
+    }
+
+    pub fn root_ephemeral(mut self, value: bool) -> Self {
+        self.root_ephemeral = Some(value);
+        self
+    }
+
+    pub fn user(mut self, value: impl Into<String>) -> Self {
+        self.user = Some(value.into());
+        self
+    }
+
+    pub fn group(mut self, value: impl Into<String>) -> Self {
+        self.group = Some(value.into());
+        self
     }
 
     pub fn environment(mut self, value: Vec<impl Into<String>>) -> Self {
@@ -393,4 +1550,939 @@ impl Exec {
         self.private_tmp = Some(value);
         self
     }
+
+    pub fn private_devices(mut self, value: bool) -> Self {
+        self.private_devices = Some(value);
+        self
+    }
+
+    pub fn protect_kernel_tunables(mut self, value: bool) -> Self {
+        self.protect_kernel_tunables = Some(value);
+        self
+    }
+
+    pub fn protect_kernel_modules(mut self, value: bool) -> Self {
+        self.protect_kernel_modules = Some(value);
+        self
+    }
+
+    pub fn protect_control_groups(mut self, value: bool) -> Self {
+        self.protect_control_groups = Some(value);
+        self
+    }
+
+    pub fn load_credential(mut self, value: Vec<LoadCredential>) -> Self {
+        self.load_credential = Some(value);
+        self
+    }
+
+    pub fn load_credential_encrypted(mut self, value: Vec<LoadCredential>) -> Self {
+        self.load_credential_encrypted = Some(value);
+        self
+    }
+
+    pub fn set_credential(mut self, value: Vec<SetCredential>) -> Self {
+        self.set_credential = Some(value);
+        self
+    }
+
+    pub fn set_credential_encrypted(mut self, value: Vec<SetCredential>) -> Self {
+        self.set_credential_encrypted = Some(value);
+        self
+    }
+
+    pub fn import_credential(mut self, value: Vec<impl Into<String>>) -> Self {
+        self.import_credential = Some(value.into_iter().map(|s| s.into()).collect());
+        self
+    }
+
+    pub fn nice(mut self, value: i32) -> Self {
+        self.nice = Some(value);
+        self
+    }
+
+    pub fn oom_score_adjust(mut self, value: i32) -> Self {
+        self.oom_score_adjust = Some(value);
+        self
+    }
+
+    pub fn cpu_scheduling_policy(mut self, value: CpuSchedulingPolicy) -> Self {
+        self.cpu_scheduling_policy = Some(value);
+        self
+    }
+
+    pub fn cpu_scheduling_priority(mut self, value: i32) -> Self {
+        self.cpu_scheduling_priority = Some(value);
+        self
+    }
+
+    pub fn cpu_scheduling_reset_on_fork(mut self, value: bool) -> Self {
+        self.cpu_scheduling_reset_on_fork = Some(value);
+        self
+    }
+
+    pub fn cpu_affinity(mut self, value: CpuSet) -> Self {
+        self.cpu_affinity = Some(value);
+        self
+    }
+
+    pub fn numa_policy(mut self, value: NumaPolicy) -> Self {
+        self.numa_policy = Some(value);
+        self
+    }
+
+    pub fn numa_mask(mut self, value: CpuSet) -> Self {
+        self.numa_mask = Some(value);
+        self
+    }
+
+    pub fn io_scheduling_class(mut self, value: IoSchedulingClass) -> Self {
+        self.io_scheduling_class = Some(value);
+        self
+    }
+
+    pub fn io_scheduling_priority(mut self, value: u8) -> Self {
+        self.io_scheduling_priority = Some(value);
+        self
+    }
+
+    pub fn personality(mut self, value: impl Into<String>) -> Self {
+        self.personality = Some(value.into());
+        self
+    }
+
+    pub fn standard_input(mut self, value: StandardInput) -> Self {
+        self.standard_input = Some(value);
+        self
+    }
+
+    pub fn standard_output(mut self, value: StandardOutput) -> Self {
+        self.standard_output = Some(value);
+        self
+    }
+
+    pub fn standard_error(mut self, value: StandardOutput) -> Self {
+        self.standard_error = Some(value);
+        self
+    }
+
+    pub fn syslog_identifier(mut self, value: impl Into<String>) -> Self {
+        self.syslog_identifier = Some(value.into());
+        self
+    }
+
+    pub fn syslog_facility(mut self, value: SyslogFacility) -> Self {
+        self.syslog_facility = Some(value);
+        self
+    }
+
+    pub fn log_level_max(mut self, value: LogLevel) -> Self {
+        self.log_level_max = Some(value);
+        self
+    }
+
+    pub fn log_extra_fields(mut self, value: Vec<impl Into<String>>) -> Self {
+        self.log_extra_fields = Some(value.into_iter().map(|s| s.into()).collect());
+        self
+    }
+
+    pub fn log_rate_limit_interval_sec(mut self, value: impl Into<String>) -> Self {
+        self.log_rate_limit_interval_sec = Some(value.into());
+        self
+    }
+
+    pub fn log_rate_limit_burst(mut self, value: u32) -> Self {
+        self.log_rate_limit_burst = Some(value);
+        self
+    }
+
+    pub fn log_namespace(mut self, value: impl Into<String>) -> Self {
+        self.log_namespace = Some(value.into());
+        self
+    }
+
+    pub fn read_write_paths(mut self, value: Vec<impl Into<NamespacePath>>) -> Self {
+        self.read_write_paths = Some(value.into_iter().map(|p| p.into()).collect());
+        self
+    }
+
+    pub fn read_only_paths(mut self, value: Vec<impl Into<NamespacePath>>) -> Self {
+        self.read_only_paths = Some(value.into_iter().map(|p| p.into()).collect());
+        self
+    }
+
+    pub fn inaccessible_paths(mut self, value: Vec<impl Into<NamespacePath>>) -> Self {
+        self.inaccessible_paths = Some(value.into_iter().map(|p| p.into()).collect());
+        self
+    }
+
+    pub fn exec_paths(mut self, value: Vec<impl Into<NamespacePath>>) -> Self {
+        self.exec_paths = Some(value.into_iter().map(|p| p.into()).collect());
+        self
+    }
+
+    pub fn no_exec_paths(mut self, value: Vec<impl Into<NamespacePath>>) -> Self {
+        self.no_exec_paths = Some(value.into_iter().map(|p| p.into()).collect());
+        self
+    }
+
+    pub fn bind_paths(mut self, value: Vec<BindPath>) -> Self {
+        self.bind_paths = Some(value);
+        self
+    }
+
+    pub fn bind_read_only_paths(mut self, value: Vec<BindPath>) -> Self {
+        self.bind_read_only_paths = Some(value);
+        self
+    }
+
+    pub fn temporary_file_system(mut self, value: Vec<TemporaryFileSystem>) -> Self {
+        self.temporary_file_system = Some(value);
+        self
+    }
+
+    pub fn mount_flags(mut self, value: MountFlags) -> Self {
+        self.mount_flags = Some(value);
+        self
+    }
+
+    pub fn mount_api_vfs(mut self, value: bool) -> Self {
+        self.mount_api_vfs = Some(value);
+        self
+    }
+
+    pub fn mount_images(mut self, value: Vec<MountImage>) -> Self {
+        self.mount_images = Some(value);
+        self
+    }
+
+    pub fn extension_images(mut self, value: Vec<ExtensionImage>) -> Self {
+        self.extension_images = Some(value);
+        self
+    }
+
+    pub fn mount_image_policy(mut self, value: impl Into<String>) -> Self {
+        self.mount_image_policy = Some(value.into());
+        self
+    }
+
+    pub fn extension_image_policy(mut self, value: impl Into<String>) -> Self {
+        self.extension_image_policy = Some(value.into());
+        self
+    }
+
+    pub fn private_network(mut self, value: bool) -> Self {
+        self.private_network = Some(value);
+        self
+    }
+
+    pub fn restrict_address_families(mut self, value: AddressFamilies) -> Self {
+        self.restrict_address_families = Some(value);
+        self
+    }
+
+    pub fn network_namespace_path(mut self, value: impl Into<PathBuf>) -> Self {
+        self.network_namespace_path = Some(value.into());
+        self
+    }
+
+    pub fn runtime_directory(mut self, value: Vec<impl Into<String>>) -> Self {
+        self.runtime_directory = Some(value.into_iter().map(|s| s.into()).collect());
+        self
+    }
+
+    pub fn state_directory(mut self, value: Vec<impl Into<String>>) -> Self {
+        self.state_directory = Some(value.into_iter().map(|s| s.into()).collect());
+        self
+    }
+
+    pub fn cache_directory(mut self, value: Vec<impl Into<String>>) -> Self {
+        self.cache_directory = Some(value.into_iter().map(|s| s.into()).collect());
+        self
+    }
+
+    pub fn logs_directory(mut self, value: Vec<impl Into<String>>) -> Self {
+        self.logs_directory = Some(value.into_iter().map(|s| s.into()).collect());
+        self
+    }
+
+    pub fn configuration_directory(mut self, value: Vec<impl Into<String>>) -> Self {
+        self.configuration_directory = Some(value.into_iter().map(|s| s.into()).collect());
+        self
+    }
+
+    pub fn runtime_directory_mode(mut self, value: u32) -> Self {
+        self.runtime_directory_mode = Some(value);
+        self
+    }
+
+    pub fn state_directory_mode(mut self, value: u32) -> Self {
+        self.state_directory_mode = Some(value);
+        self
+    }
+
+    pub fn cache_directory_mode(mut self, value: u32) -> Self {
+        self.cache_directory_mode = Some(value);
+        self
+    }
+
+    pub fn logs_directory_mode(mut self, value: u32) -> Self {
+        self.logs_directory_mode = Some(value);
+        self
+    }
+
+    pub fn configuration_directory_mode(mut self, value: u32) -> Self {
+        self.configuration_directory_mode = Some(value);
+        self
+    }
+
+    pub fn runtime_directory_preserve(mut self, value: RuntimeDirectoryPreserve) -> Self {
+        self.runtime_directory_preserve = Some(value);
+        self
+    }
+
+    pub fn limits(mut self, value: Limits) -> Self {
+        self.limits = Some(value);
+        self
+    }
+
+    /// Appends a SystemCallFilter= line. Filters are rendered in the order they were added.
+    pub fn system_call_filter(mut self, value: SystemCallFilter) -> Self {
+        self.system_call_filter
+            .get_or_insert_with(Vec::new)
+            .push(value);
+        self
+    }
+
+    pub fn system_call_architectures(mut self, value: Vec<impl Into<String>>) -> Self {
+        self.system_call_architectures = Some(value.into_iter().map(|s| s.into()).collect());
+        self
+    }
+
+    pub fn system_call_error_number(mut self, value: impl Into<String>) -> Self {
+        self.system_call_error_number = Some(value.into());
+        self
+    }
+
+    pub fn system_call_log(mut self, value: SystemCallFilter) -> Self {
+        self.system_call_log = Some(value);
+        self
+    }
+
+    pub fn protect_system(mut self, value: ProtectSystem) -> Self {
+        self.protect_system = Some(value);
+        self
+    }
+
+    pub fn protect_home(mut self, value: ProtectHome) -> Self {
+        self.protect_home = Some(value);
+        self
+    }
+
+    pub fn protect_proc(mut self, value: ProtectProc) -> Self {
+        self.protect_proc = Some(value);
+        self
+    }
+
+    pub fn proc_subset(mut self, value: ProcSubset) -> Self {
+        self.proc_subset = Some(value);
+        self
+    }
+
+    pub fn protect_hostname(mut self, value: bool) -> Self {
+        self.protect_hostname = Some(value);
+        self
+    }
+
+    pub fn protect_clock(mut self, value: bool) -> Self {
+        self.protect_clock = Some(value);
+        self
+    }
+
+    pub fn protect_kernel_logs(mut self, value: bool) -> Self {
+        self.protect_kernel_logs = Some(value);
+        self
+    }
+
+    pub fn restrict_namespaces(mut self, value: RestrictNamespaces) -> Self {
+        self.restrict_namespaces = Some(value);
+        self
+    }
+
+    pub fn lock_personality(mut self, value: bool) -> Self {
+        self.lock_personality = Some(value);
+        self
+    }
+
+    pub fn memory_deny_write_execute(mut self, value: bool) -> Self {
+        self.memory_deny_write_execute = Some(value);
+        self
+    }
+
+    pub fn restrict_realtime(mut self, value: bool) -> Self {
+        self.restrict_realtime = Some(value);
+        self
+    }
+
+    pub fn restrict_suid_sgid(mut self, value: bool) -> Self {
+        self.restrict_suid_sgid = Some(value);
+        self
+    }
+
+    pub fn remove_ipc(mut self, value: bool) -> Self {
+        self.remove_ipc = Some(value);
+        self
+    }
+
+    pub fn private_users(mut self, value: bool) -> Self {
+        self.private_users = Some(value);
+        self
+    }
+
+    pub fn private_ipc(mut self, value: bool) -> Self {
+        self.private_ipc = Some(value);
+        self
+    }
+
+    pub fn dynamic_user(mut self, value: bool) -> Self {
+        self.dynamic_user = Some(value);
+        self
+    }
+}
+
+fn validate_io_path(path: &Path, key: &str) -> Result<(), String> {
+    if !path.is_absolute() {
+        return Err(format!(
+            "{} file '{}' must be an absolute path",
+            key,
+            path.display()
+        ));
+    }
+    Ok(())
+}
+
+/// File descriptor names follow the rules of FileDescriptorName=: up to 255 printable characters
+/// without colons.
+fn validate_fd_name(name: &str, key: &str) -> Result<(), String> {
+    if name.is_empty()
+        || name.len() > 255
+        || name.contains(':')
+        || name.chars().any(|c| c.is_control() || c.is_whitespace())
+    {
+        return Err(format!(
+            "Invalid file descriptor name '{}' in {}",
+            name, key
+        ));
+    }
+    Ok(())
+}
+
+/// Managed directory names must be normalized relative paths.
+pub(crate) fn validate_directory_name(name: &str, kind: DirectoryKind) -> Result<(), String> {
+    // `Path::components` normalizes away "." and repeated or trailing
+    // slashes, so check the raw segments instead.
+    if name
+        .split('/')
+        .any(|segment| segment.is_empty() || segment == "." || segment == "..")
+    {
+        return Err(format!(
+            "{} '{}' must be a relative path without '.' or '..' components",
+            kind, name
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systemd::limit::ResourceLimit;
+    use crate::systemd::resource_control::ByteSize;
+    use std::time::Duration;
+
+    #[test]
+    #[allow(deprecated)]
+    fn deprecated_limit_fields_are_rendered() {
+        let exec = Exec {
+            limit_cpu: Some("30s".to_string()),
+            limit_fsize: Some("1G".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(exec.validate(), Ok(()));
+        let rendered = exec.to_string();
+        assert!(rendered.contains("LimitCPU=30s\n"));
+        assert!(rendered.contains("LimitFSIZE=1G\n"));
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn typed_limits_take_precedence() {
+        let mut exec =
+            Exec::new().limits(Limits::new().cpu(ResourceLimit::new(Duration::from_secs(60))));
+        exec.limit_fsize = Some("1G".to_string());
+        let rendered = exec.to_string();
+        assert!(rendered.contains("LimitCPU=1min\n"));
+        assert!(rendered.contains("LimitFSIZE=1G\n"));
+
+        exec.limit_cpu = Some("30s".to_string());
+        assert!(exec.validate().is_err());
+        assert!(!exec.to_string().contains("LimitCPU=30s"));
+
+        exec.limits = Some(Limits::new().fsize(ResourceLimit::new(ByteSize::mib(512))));
+        exec.limit_cpu = None;
+        assert!(exec.validate().is_err());
+    }
+
+    #[test]
+    fn renders_sandboxing_settings() {
+        let exec = Exec::new()
+            .protect_system(ProtectSystem::Strict)
+            .protect_home(ProtectHome::ReadOnly)
+            .protect_proc(ProtectProc::Invisible)
+            .proc_subset(ProcSubset::Pid)
+            .restrict_namespaces(RestrictNamespaces::Deny(vec![
+                "user".to_string(),
+                "net".to_string(),
+            ]))
+            .restrict_suid_sgid(true)
+            .dynamic_user(true);
+
+        assert_eq!(exec.validate(), Ok(()));
+        let rendered = exec.to_string();
+        for line in [
+            "ProtectSystem=strict\n",
+            "ProtectHome=read-only\n",
+            "ProtectProc=invisible\n",
+            "ProcSubset=pid\n",
+            "RestrictNamespaces=~user net\n",
+            "RestrictSUIDSGID=yes\n",
+            "DynamicUser=yes\n",
+        ] {
+            assert!(rendered.contains(line), "{}", line);
+        }
+    }
+
+    #[test]
+    fn restrict_namespaces_must_list_known_types() {
+        let allow = |namespaces: &[&str]| {
+            Exec::new()
+                .restrict_namespaces(RestrictNamespaces::Allow(
+                    namespaces.iter().map(|n| n.to_string()).collect(),
+                ))
+                .validate()
+        };
+
+        assert_eq!(allow(&["cgroup", "ipc", "mnt"]), Ok(()));
+        assert_eq!(
+            allow(&[]),
+            Err("RestrictNamespaces must list at least one namespace type".to_string())
+        );
+        assert!(allow(&["network"]).unwrap_err().contains("'network'"));
+        assert_eq!(
+            RestrictNamespaces::Allow(vec!["ipc".to_string()]).to_string(),
+            "ipc"
+        );
+    }
+
+    #[test]
+    fn dynamic_user_cannot_be_root() {
+        for user in ["root", "0"] {
+            assert_eq!(
+                Exec::new().dynamic_user(true).user(user).validate(),
+                Err(format!("DynamicUser cannot be combined with User={}", user))
+            );
+        }
+        assert_eq!(
+            Exec::new().dynamic_user(true).user("app").validate(),
+            Ok(())
+        );
+        assert_eq!(
+            Exec::new().dynamic_user(false).user("root").validate(),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn standard_io_renders() {
+        for (input, rendered) in [
+            (StandardInput::Null, "null"),
+            (StandardInput::TtyForce, "tty-force"),
+            (StandardInput::Data, "data"),
+            (StandardInput::File("/dev/ttyS0".into()), "file:/dev/ttyS0"),
+            (StandardInput::Fd("stdin".to_string()), "fd:stdin"),
+        ] {
+            assert_eq!(input.to_string(), rendered);
+        }
+        for (output, rendered) in [
+            (StandardOutput::Inherit, "inherit"),
+            (StandardOutput::JournalConsole, "journal+console"),
+            (StandardOutput::KmsgConsole, "kmsg+console"),
+            (
+                StandardOutput::File("/var/log/app.log".into()),
+                "file:/var/log/app.log",
+            ),
+            (
+                StandardOutput::Append("/var/log/app.log".into()),
+                "append:/var/log/app.log",
+            ),
+            (
+                StandardOutput::Truncate("/var/log/app.log".into()),
+                "truncate:/var/log/app.log",
+            ),
+            (StandardOutput::Fd("log".to_string()), "fd:log"),
+        ] {
+            assert_eq!(output.to_string(), rendered);
+        }
+        assert_eq!(SyslogFacility::AuthPriv.to_string(), "authpriv");
+        assert_eq!(SyslogFacility::Local(3).to_string(), "local3");
+        assert_eq!(LogLevel::Warning.to_string(), "warning");
+    }
+
+    #[test]
+    fn renders_logging_settings() {
+        let exec = Exec::new()
+            .standard_output(StandardOutput::Journal)
+            .standard_error(StandardOutput::Append("/var/log/app.err".into()))
+            .syslog_identifier("app")
+            .syslog_facility(SyslogFacility::Local(0))
+            .log_level_max(LogLevel::Info)
+            .log_extra_fields(vec!["TENANT=acme"]);
+
+        assert_eq!(exec.validate(), Ok(()));
+        let rendered = exec.to_string();
+        for line in [
+            "StandardOutput=journal\n",
+            "StandardError=append:/var/log/app.err\n",
+            "SyslogIdentifier=app\n",
+            "SyslogFacility=local0\n",
+            "LogLevelMax=info\n",
+            "LogExtraFields=TENANT=acme\n",
+        ] {
+            assert!(rendered.contains(line), "{}", line);
+        }
+    }
+
+    #[test]
+    fn standard_io_targets_are_validated() {
+        assert_eq!(
+            Exec::new()
+                .standard_input(StandardInput::File("dev/null".into()))
+                .validate(),
+            Err("StandardInput file 'dev/null' must be an absolute path".to_string())
+        );
+        assert_eq!(
+            Exec::new()
+                .standard_error(StandardOutput::Truncate("app.log".into()))
+                .validate(),
+            Err("StandardError file 'app.log' must be an absolute path".to_string())
+        );
+        for name in ["", "a:b", "has space"] {
+            assert!(
+                Exec::new()
+                    .standard_output(StandardOutput::Fd(name.to_string()))
+                    .validate()
+                    .is_err(),
+                "{:?}",
+                name
+            );
+        }
+        assert!(
+            Exec::new()
+                .standard_output(StandardOutput::Fd("x".repeat(256)))
+                .validate()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn logging_settings_are_validated() {
+        assert_eq!(
+            Exec::new()
+                .syslog_facility(SyslogFacility::Local(7))
+                .validate(),
+            Ok(())
+        );
+        assert_eq!(
+            Exec::new()
+                .syslog_facility(SyslogFacility::Local(8))
+                .validate(),
+            Err("SyslogFacility local8 must be between local0 and local7".to_string())
+        );
+        assert!(Exec::new().syslog_identifier("my app").validate().is_err());
+        assert!(Exec::new().syslog_identifier("").validate().is_err());
+
+        for field in ["NO_VALUE", "lower=x", "_PRIVATE=x", "1ST=x", "=x"] {
+            assert!(
+                Exec::new()
+                    .log_extra_fields(vec![field])
+                    .validate()
+                    .is_err(),
+                "{}",
+                field
+            );
+        }
+        assert_eq!(
+            Exec::new().log_extra_fields(vec!["FIELD_2=a=b"]).validate(),
+            Ok(())
+        );
+
+        assert_eq!(Exec::new().log_namespace("tenant-a.1").validate(), Ok(()));
+        assert_eq!(
+            Exec::new().log_namespace("tenant/a").validate(),
+            Err("Invalid LogNamespace 'tenant/a'".to_string())
+        );
+    }
+
+    #[test]
+    fn directory_names_must_stay_below_the_base() {
+        for name in ["demo", "demo/cache", "demo.d/sub-dir"] {
+            assert_eq!(
+                validate_directory_name(name, DirectoryKind::State),
+                Ok(()),
+                "{}",
+                name
+            );
+        }
+        for name in [
+            "",
+            "/var/lib/demo",
+            "..",
+            "../etc",
+            "demo/../../etc",
+            "a/../b",
+            ".",
+            "./demo",
+            "a/./b",
+            "a//b",
+            "demo/",
+        ] {
+            assert_eq!(
+                validate_directory_name(name, DirectoryKind::State),
+                Err(format!(
+                    "StateDirectory '{}' must be a relative path without '.' or '..' components",
+                    name
+                ))
+            );
+        }
+        assert_eq!(
+            Exec::new().logs_directory(vec!["app", "../etc"]).validate(),
+            Err(
+                "LogsDirectory '../etc' must be a relative path without '.' or '..' components"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn managed_directories_for_the_system_manager() {
+        let exec = Exec::new()
+            .runtime_directory(vec!["demo"])
+            .state_directory(vec!["demo", "demo/db"])
+            .cache_directory(vec!["demo"])
+            .logs_directory(vec!["demo"])
+            .configuration_directory(vec!["demo"]);
+
+        assert_eq!(
+            exec.managed_directories(ManagerScope::System).unwrap(),
+            [
+                "/run/demo",
+                "/var/lib/demo",
+                "/var/lib/demo/db",
+                "/var/cache/demo",
+                "/var/log/demo",
+                "/etc/demo",
+            ]
+            .map(PathBuf::from)
+        );
+        assert!(
+            Exec::new()
+                .managed_directories(ManagerScope::System)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn managed_directories_for_the_user_manager() {
+        let exec = Exec::new()
+            .state_directory(vec!["demo"])
+            .cache_directory(vec!["demo"])
+            .logs_directory(vec!["demo"])
+            .configuration_directory(vec!["demo"]);
+
+        assert_eq!(
+            exec.managed_directories(ManagerScope::User).unwrap(),
+            vec![
+                xdg_dir("XDG_STATE_HOME", ".local/state")
+                    .unwrap()
+                    .join("demo"),
+                xdg_dir("XDG_CACHE_HOME", ".cache").unwrap().join("demo"),
+                xdg_dir("XDG_STATE_HOME", ".local/state")
+                    .unwrap()
+                    .join("log/demo"),
+                xdg_dir("XDG_CONFIG_HOME", ".config").unwrap().join("demo"),
+            ]
+        );
+
+        let runtime = Exec::new()
+            .runtime_directory(vec!["demo"])
+            .managed_directories(ManagerScope::User);
+        match std::env::var_os("XDG_RUNTIME_DIR").map(PathBuf::from) {
+            Some(dir) if dir.is_absolute() => assert_eq!(runtime.unwrap(), [dir.join("demo")]),
+            _ => assert!(runtime.is_err()),
+        }
+    }
+
+    #[test]
+    fn renders_scheduling_settings() {
+        let exec = Exec::new()
+            .nice(-5)
+            .cpu_scheduling_policy(CpuSchedulingPolicy::Fifo)
+            .cpu_scheduling_priority(50)
+            .cpu_scheduling_reset_on_fork(true)
+            .cpu_affinity(CpuSet::new().range(0, 3).cpu(8))
+            .numa_policy(NumaPolicy::Interleave)
+            .numa_mask(CpuSet::new().range(0, 1))
+            .io_scheduling_class(IoSchedulingClass::BestEffort)
+            .io_scheduling_priority(2);
+
+        assert_eq!(exec.validate(), Ok(()));
+        assert!(exec.to_string().contains(
+            "Nice=-5\nCPUSchedulingPolicy=fifo\nCPUSchedulingPriority=50\n\
+             CPUSchedulingResetOnFork=yes\nCPUAffinity=0-3,8\nNUMAPolicy=interleave\n\
+             NUMAMask=0-1\nIOSchedulingClass=best-effort\nIOSchedulingPriority=2\n"
+        ));
+    }
+
+    #[test]
+    fn nice_level_range() {
+        assert_eq!(Exec::new().nice(-20).validate(), Ok(()));
+        assert_eq!(Exec::new().nice(19).validate(), Ok(()));
+        assert_eq!(
+            Exec::new().nice(-21).validate(),
+            Err("Nice level -21 must be between -20 and 19".to_string())
+        );
+        assert_eq!(
+            Exec::new().nice(20).validate(),
+            Err("Nice level 20 must be between -20 and 19".to_string())
+        );
+    }
+
+    #[test]
+    fn cpu_scheduling_priority_depends_on_the_policy() {
+        let priority = |policy: Option<CpuSchedulingPolicy>, priority| {
+            let exec = Exec::new().cpu_scheduling_priority(priority);
+            match policy {
+                Some(policy) => exec.cpu_scheduling_policy(policy),
+                None => exec,
+            }
+            .validate()
+        };
+
+        for policy in [CpuSchedulingPolicy::Fifo, CpuSchedulingPolicy::Rr] {
+            assert_eq!(priority(Some(policy), 1), Ok(()));
+            assert_eq!(priority(Some(policy), 99), Ok(()));
+            assert_eq!(
+                priority(Some(policy), 0),
+                Err("CPUSchedulingPriority 0 must be between 1 and 99".to_string())
+            );
+            assert_eq!(
+                priority(Some(policy), 100),
+                Err("CPUSchedulingPriority 100 must be between 1 and 99".to_string())
+            );
+        }
+        for policy in [
+            CpuSchedulingPolicy::Other,
+            CpuSchedulingPolicy::Batch,
+            CpuSchedulingPolicy::Idle,
+        ] {
+            assert_eq!(priority(Some(policy), 0), Ok(()));
+            assert_eq!(
+                priority(Some(policy), 1),
+                Err("CPUSchedulingPriority 1 must be between 0 and 0".to_string())
+            );
+        }
+        assert_eq!(priority(None, 0), Ok(()));
+        assert_eq!(priority(None, 99), Ok(()));
+        assert_eq!(
+            priority(None, -1),
+            Err("CPUSchedulingPriority -1 must be between 0 and 99".to_string())
+        );
+    }
+
+    #[test]
+    fn numa_and_cpu_sets_are_validated() {
+        for policy in [NumaPolicy::Bind, NumaPolicy::Interleave] {
+            assert_eq!(
+                Exec::new().numa_policy(policy).validate(),
+                Err(format!(
+                    "NUMAPolicy={} requires a non-empty NUMAMask",
+                    policy
+                ))
+            );
+            assert_eq!(
+                Exec::new()
+                    .numa_policy(policy)
+                    .numa_mask(CpuSet::new())
+                    .validate(),
+                Err(format!(
+                    "NUMAPolicy={} requires a non-empty NUMAMask",
+                    policy
+                ))
+            );
+        }
+        for policy in [
+            NumaPolicy::Default,
+            NumaPolicy::Preferred,
+            NumaPolicy::Local,
+        ] {
+            assert_eq!(Exec::new().numa_policy(policy).validate(), Ok(()));
+        }
+
+        assert_eq!(
+            Exec::new()
+                .cpu_affinity(CpuSet::new().range(4, 2))
+                .validate(),
+            Err("CPUAffinity: Invalid CPU range '4-2'".to_string())
+        );
+        assert_eq!(
+            Exec::new().numa_mask(CpuSet::new().range(1, 0)).validate(),
+            Err("NUMAMask: Invalid CPU range '1-0'".to_string())
+        );
+    }
+
+    #[test]
+    fn io_scheduling_priority_range() {
+        assert_eq!(Exec::new().io_scheduling_priority(0).validate(), Ok(()));
+        assert_eq!(Exec::new().io_scheduling_priority(7).validate(), Ok(()));
+        assert_eq!(
+            Exec::new().io_scheduling_priority(8).validate(),
+            Err("IOSchedulingPriority 8 must be between 0 and 7".to_string())
+        );
+        assert_eq!(IoSchedulingClass::Realtime.to_string(), "realtime");
+        assert_eq!(IoSchedulingClass::Idle.to_string(), "idle");
+    }
+
+    #[test]
+    fn path_list_builders_accept_plain_paths() {
+        let exec = Exec::new()
+            .read_write_paths(vec!["/var/lib/demo"])
+            .read_only_paths(vec![String::from("/etc"), String::from("/usr")])
+            .inaccessible_paths(vec![PathBuf::from("/home")]);
+
+        assert_eq!(exec.validate(), Ok(()));
+        assert!(exec.to_string().contains(
+            "ReadWritePaths=/var/lib/demo\nReadOnlyPaths=/etc\nReadOnlyPaths=/usr\n\
+             InaccessiblePaths=/home\n"
+        ));
+
+        let exec = Exec::new().read_only_paths(vec![
+            NamespacePath::new("/etc"),
+            NamespacePath::new("/opt/demo").ignore_missing(),
+        ]);
+        assert!(
+            exec.to_string()
+                .contains("ReadOnlyPaths=/etc\nReadOnlyPaths=-/opt/demo\n")
+        );
+    }
 }
@@ -1,4 +1,5 @@
 use std::fmt;
+use std::path::{Path, PathBuf};
 
 /// Represents the [Install] section of a systemd unit file.
 ///
@@ -41,7 +42,110 @@ impl fmt::Display for Install {
     }
 }
 
+/// A symlink created when a unit is enabled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstallLink {
+    /// Path of the symlink.
+    pub path: PathBuf,
+    /// Unit file the symlink points to.
+    pub target: PathBuf,
+}
+
 impl Install {
+    /// Returns the symlinks `systemctl enable` creates in `unit_dir` for the unit file at `unit_file`:
+    /// one in the `.wants/`, `.requires/` or `.upholds/` directory of every listed unit,
+    /// and one per alias next to the unit file.
+    /// Templates are linked as their `DefaultInstance=`, and only aliased without one.
+    /// Units listed in `Also=` are not included, their links come from their own unit files.
+    pub fn links(&self, unit_dir: &Path, unit_file: &Path) -> Vec<InstallLink> {
+        let Some(unit_name) = unit_file.file_name().and_then(|name| name.to_str()) else {
+            return Vec::new();
+        };
+        let instance = match unit_name.split_once("@.") {
+            Some((prefix, suffix)) => self
+                .default_instance
+                .as_ref()
+                .map(|instance| format!("{}@{}.{}", prefix, instance, suffix)),
+            None => Some(unit_name.to_string()),
+        };
+        let mut links = Vec::new();
+        if let Some(ref instance) = instance {
+            for (units, suffix) in [
+                (&self.wanted_by, "wants"),
+                (&self.required_by, "requires"),
+                (&self.upheld_by, "upholds"),
+            ] {
+                for unit in units.iter().flatten() {
+                    links.push(InstallLink {
+                        path: unit_dir.join(format!("{}.{}", unit, suffix)).join(instance),
+                        target: unit_file.to_path_buf(),
+                    });
+                }
+            }
+        }
+        for alias in self.alias.iter().flatten() {
+            links.push(InstallLink {
+                path: unit_dir.join(alias),
+                target: unit_file.to_path_buf(),
+            });
+        }
+        links
+    }
+
+    /// Returns true if the section lists no units to be linked from, so enabling does nothing
+    /// except for the units in `Also=`.
+    pub fn is_empty(&self) -> bool {
+        [
+            &self.alias,
+            &self.wanted_by,
+            &self.required_by,
+            &self.upheld_by,
+        ]
+        .iter()
+        .all(|units| units.as_ref().is_none_or(|units| units.is_empty()))
+    }
+
+    /// Reads the `[Install]` section of a unit file, to follow the `Also=` units of a unit.
+    /// Unknown keys are ignored. Like systemd, values of repeated keys are accumulated,
+    /// an empty assignment resets the list, and lines ending with `\` are continued on the next line.
+    pub(crate) fn parse(contents: &str) -> Self {
+        let mut install = Install::default();
+        let mut in_section = false;
+        for line in logical_lines(contents) {
+            if line.starts_with('[') {
+                in_section = line == "[Install]";
+                continue;
+            }
+            if !in_section || line.starts_with(['#', ';']) {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let value = value.trim();
+            let units = match key.trim() {
+                "Alias" => &mut install.alias,
+                "WantedBy" => &mut install.wanted_by,
+                "RequiredBy" => &mut install.required_by,
+                "UpheldBy" => &mut install.upheld_by,
+                "Also" => &mut install.also,
+                "DefaultInstance" => {
+                    install.default_instance = Some(value.to_string()).filter(|v| !v.is_empty());
+                    continue;
+                }
+                _ => continue,
+            };
+            if value.is_empty() {
+                *units = None;
+                continue;
+            }
+            units
+                .get_or_insert_with(Vec::new)
+                .extend(value.split_whitespace().map(String::from));
+        }
+        install
+    }
+
     /// Validate the install configuration according to systemd specifications
     pub fn validate(&self) -> Result<(), String> {
         // DefaultInstance should be a valid instance identifier
@@ -94,3 +198,73 @@ impl Install {
         self
     }
 }
+
+/// Joins lines ending with a backslash with the following ones, separated by a space,
+/// skipping comment lines in between, as systemd does when reading unit files.
+fn logical_lines(contents: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut continued: Option<String> = None;
+    for line in contents.lines().map(str::trim) {
+        // Comments are never continued, and are dropped within a continued line
+        if line.starts_with(['#', ';']) {
+            if continued.is_none() {
+                lines.push(line.to_string());
+            }
+            continue;
+        }
+        let mut current = continued.take().unwrap_or_default();
+        match line.strip_suffix('\\') {
+            Some(head) => {
+                current.push_str(head);
+                current.push(' ');
+                continued = Some(current);
+            }
+            None => {
+                current.push_str(line);
+                lines.push(current);
+            }
+        }
+    }
+    lines.extend(continued);
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_accumulates_and_resets_lists() {
+        let install = Install::parse(
+            "[Unit]\nWantedBy=ignored.target\n\n[Install]\nWantedBy=a.target\n\
+             WantedBy=b.target c.target\nRequiredBy=x.target\nRequiredBy=\n\
+             Alias=demo-alias.service\nDefaultInstance=\n",
+        );
+
+        assert_eq!(
+            install.wanted_by,
+            Some(vec![
+                "a.target".to_string(),
+                "b.target".to_string(),
+                "c.target".to_string()
+            ])
+        );
+        assert_eq!(install.required_by, None);
+        assert_eq!(install.alias, Some(vec!["demo-alias.service".to_string()]));
+        assert_eq!(install.default_instance, None);
+    }
+
+    #[test]
+    fn parse_joins_continuation_lines() {
+        let install = Install::parse(
+            "[Install]\n# WantedBy=commented.target \\\nWantedBy=a.target \\\n\
+             # skipped within the continuation\n  b.target\nAlso=other.service\n",
+        );
+
+        assert_eq!(
+            install.wanted_by,
+            Some(vec!["a.target".to_string(), "b.target".to_string()])
+        );
+        assert_eq!(install.also, Some(vec!["other.service".to_string()]));
+    }
+}
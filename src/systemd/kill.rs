@@ -0,0 +1,287 @@
+use std::fmt;
+
+/// Kill mode for units owning processes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KillMode {
+    /// Kill all remaining processes in the control group
+    ControlGroup,
+    /// Send SIGTERM to the main process and the final SIGKILL to the whole control group
+    Mixed,
+    /// Only kill the main process
+    Process,
+    /// Do not kill any process (deprecated)
+    None,
+}
+
+impl fmt::Display for KillMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KillMode::ControlGroup => write!(f, "control-group"),
+            KillMode::Mixed => write!(f, "mixed"),
+            KillMode::Process => write!(f, "process"),
+            KillMode::None => write!(f, "none"),
+        }
+    }
+}
+
+/// Kill settings, shared by service, socket, mount, swap and scope units
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.kill.html#Options
+#[derive(Debug, Clone, Default)]
+pub struct Kill {
+    /// Specifies how processes of this unit shall be killed.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.kill.html#KillMode=
+    /// One of: control-group, mixed, process, or none
+    pub kill_mode: Option<KillMode>,
+
+    /// Signal to use when stopping the unit.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.kill.html#KillSignal=
+    pub kill_signal: Option<String>,
+
+    /// Signal to use when restarting the unit.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.kill.html#RestartKillSignal=
+    pub restart_kill_signal: Option<String>,
+
+    /// Whether to send SIGHUP to remaining processes immediately after the kill signal.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.kill.html#SendSIGHUP=
+    pub send_sighup: Option<bool>,
+
+    /// Whether to send the final kill signal to processes that did not terminate after a timeout.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.kill.html#SendSIGKILL=
+    pub send_sigkill: Option<bool>,
+
+    /// Signal to send to remaining processes after the stop timeout.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.kill.html#FinalKillSignal=
+    pub final_kill_signal: Option<String>,
+
+    /// Signal to send when the watchdog times out.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.kill.html#WatchdogSignal=
+    pub watchdog_signal: Option<String>,
+}
+
+impl fmt::Display for Kill {
+    fn fmt(&self, buf: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_option!(buf, self.kill_mode, "KillMode");
+        write_option!(buf, self.kill_signal, "KillSignal");
+        write_option!(buf, self.restart_kill_signal, "RestartKillSignal");
+        write_bool!(buf, self.send_sighup, "SendSIGHUP");
+        write_bool!(buf, self.send_sigkill, "SendSIGKILL");
+        write_option!(buf, self.final_kill_signal, "FinalKillSignal");
+        write_option!(buf, self.watchdog_signal, "WatchdogSignal");
+        Ok(())
+    }
+}
+
+impl Kill {
+    /// Creates a new empty `Kill` section.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Validate the kill configuration
+    pub fn validate(&self) -> Result<(), String> {
+        for (signal, key) in [
+            (&self.kill_signal, "KillSignal"),
+            (&self.restart_kill_signal, "RestartKillSignal"),
+            (&self.final_kill_signal, "FinalKillSignal"),
+            (&self.watchdog_signal, "WatchdogSignal"),
+        ] {
+            if let Some(signal) = signal {
+                validate_signal(signal, key)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns warnings about settings that are accepted but discouraged by systemd
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        if self.kill_mode == Some(KillMode::None) {
+            warnings.push(
+                "KillMode=none is deprecated: processes are left running when the unit stops"
+                    .to_string(),
+            );
+        }
+        if self.send_sigkill == Some(false) {
+            warnings.push(
+                "SendSIGKILL=no may leave processes running after the stop timeout".to_string(),
+            );
+        }
+        warnings
+    }
+
+    // Builder pattern setters
+    pub fn kill_mode(mut self, value: KillMode) -> Self {
+        self.kill_mode = Some(value);
+        self
+    }
+
+    pub fn kill_signal(mut self, value: impl Into<String>) -> Self {
+        self.kill_signal = Some(value.into());
+        self
+    }
+
+    pub fn restart_kill_signal(mut self, value: impl Into<String>) -> Self {
+        self.restart_kill_signal = Some(value.into());
+        self
+    }
+
+    pub fn send_sighup(mut self, value: bool) -> Self {
+        self.send_sighup = Some(value);
+        self
+    }
+
+    pub fn send_sigkill(mut self, value: bool) -> Self {
+        self.send_sigkill = Some(value);
+        self
+    }
+
+    pub fn final_kill_signal(mut self, value: impl Into<String>) -> Self {
+        self.final_kill_signal = Some(value.into());
+        self
+    }
+
+    pub fn watchdog_signal(mut self, value: impl Into<String>) -> Self {
+        self.watchdog_signal = Some(value.into());
+        self
+    }
+}
+
+/// Signal names accepted by systemd, without the `SIG` prefix
+const SIGNALS: [&str; 31] = [
+    "HUP", "INT", "QUIT", "ILL", "TRAP", "ABRT", "BUS", "FPE", "KILL", "USR1", "SEGV", "USR2",
+    "PIPE", "ALRM", "TERM", "STKFLT", "CHLD", "CONT", "STOP", "TSTP", "TTIN", "TTOU", "URG",
+    "XCPU", "XFSZ", "VTALRM", "PROF", "WINCH", "IO", "PWR", "SYS",
+];
+
+/// Number of real-time signals above `SIGRTMIN`, from `SIGRTMIN` (34) to `SIGRTMAX` (64)
+const REALTIME_SIGNALS: u32 = 30;
+
+/// Validates a signal given by name (`SIGTERM`, `TERM`), real-time offset (`SIGRTMIN+3`,
+/// `SIGRTMAX-2`) or number.
+pub(crate) fn validate_signal(signal: &str, key: &str) -> Result<(), String> {
+    let name = signal.strip_prefix("SIG").unwrap_or(signal);
+    // RTMIN counts up and RTMAX counts down, both at most to the other end of the range
+    let realtime = |prefix: &str, sign: char| {
+        name.strip_prefix(prefix).is_some_and(|offset| {
+            offset.is_empty()
+                || offset.strip_prefix(sign).is_some_and(|n| {
+                    n.bytes().all(|b| b.is_ascii_digit())
+                        && n.parse::<u32>().is_ok_and(|n| n <= REALTIME_SIGNALS)
+                })
+        })
+    };
+    let valid = match name.parse::<u32>() {
+        Ok(number) => (1..=64).contains(&number),
+        Err(_) => SIGNALS.contains(&name) || realtime("RTMIN", '+') || realtime("RTMAX", '-'),
+    };
+    if !valid {
+        return Err(format!("Invalid {} '{}': unknown signal", key, signal));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn valid(signal: &str) -> bool {
+        validate_signal(signal, "KillSignal").is_ok()
+    }
+
+    #[test]
+    fn signal_names() {
+        assert!(valid("SIGTERM"));
+        assert!(valid("TERM"));
+        assert!(valid("SIGKILL"));
+        assert!(valid("SIGWINCH"));
+        assert!(!valid("SIGFOO"));
+        assert!(!valid("sigterm"));
+        assert!(!valid(""));
+        assert_eq!(
+            validate_signal("SIGFOO", "KillSignal"),
+            Err("Invalid KillSignal 'SIGFOO': unknown signal".to_string())
+        );
+    }
+
+    #[test]
+    fn signal_numbers() {
+        assert!(!valid("0"));
+        assert!(valid("1"));
+        assert!(valid("64"));
+        assert!(!valid("65"));
+        assert!(!valid("-1"));
+    }
+
+    #[test]
+    fn realtime_signals() {
+        assert!(valid("SIGRTMIN"));
+        assert!(valid("RTMAX"));
+        assert!(valid("SIGRTMIN+0"));
+        assert!(valid("SIGRTMIN+30"));
+        assert!(!valid("SIGRTMIN+31"));
+        assert!(valid("SIGRTMAX-0"));
+        assert!(valid("SIGRTMAX-30"));
+        assert!(!valid("SIGRTMAX-31"));
+        assert!(!valid("SIGRTMIN-1"));
+        assert!(!valid("SIGRTMAX+1"));
+        assert!(!valid("SIGRTMIN++1"));
+        assert!(!valid("SIGRTMIN+"));
+    }
+
+    #[test]
+    fn validate_checks_every_signal() {
+        let kill = Kill::new()
+            .kill_signal("SIGTERM")
+            .restart_kill_signal("SIGRTMIN+2")
+            .final_kill_signal("9")
+            .watchdog_signal("SIGABRT");
+        assert!(kill.validate().is_ok());
+
+        assert_eq!(
+            Kill::new().watchdog_signal("SIGRTMAX+1").validate(),
+            Err("Invalid WatchdogSignal 'SIGRTMAX+1': unknown signal".to_string())
+        );
+    }
+
+    #[test]
+    fn warnings() {
+        assert!(
+            Kill::new()
+                .kill_mode(KillMode::Mixed)
+                .send_sigkill(true)
+                .warnings()
+                .is_empty()
+        );
+        assert_eq!(
+            Kill::new().kill_mode(KillMode::None).warnings(),
+            ["KillMode=none is deprecated: processes are left running when the unit stops"]
+        );
+        assert_eq!(
+            Kill::new().send_sigkill(false).warnings(),
+            ["SendSIGKILL=no may leave processes running after the stop timeout"]
+        );
+        assert_eq!(
+            Kill::new()
+                .kill_mode(KillMode::None)
+                .send_sigkill(false)
+                .warnings()
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn renders_settings() {
+        let kill = Kill::new()
+            .kill_mode(KillMode::Mixed)
+            .kill_signal("SIGINT")
+            .send_sighup(true)
+            .send_sigkill(false);
+
+        assert_eq!(
+            kill.to_string(),
+            "KillMode=mixed\nKillSignal=SIGINT\nSendSIGHUP=yes\nSendSIGKILL=no\n"
+        );
+    }
+}
@@ -0,0 +1,457 @@
+use super::resource_control::ByteSize;
+use std::fmt;
+use std::time::Duration;
+
+/// Highest value accepted by LimitNICE= in its raw form, which maps to nice level -20.
+const MAX_RAW_NICE: u64 = 40;
+
+/// Value types a resource limit can be expressed in.
+/// Counts are plain numbers, sizes render with `K`/`M`/`G` suffixes and times with `us`..`h` suffixes.
+pub trait LimitUnit: Copy + PartialOrd {
+    fn fmt_limit(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result;
+}
+
+impl LimitUnit for u64 {
+    fn fmt_limit(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl LimitUnit for ByteSize {
+    fn fmt_limit(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl LimitUnit for Duration {
+    fn fmt_limit(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let us = self.as_micros();
+        if us == 0 {
+            return write!(f, "0");
+        }
+        // Use the largest unit that represents the value exactly
+        for (unit, suffix) in [
+            (3_600_000_000, "h"),
+            (60_000_000, "min"),
+            (1_000_000, "s"),
+            (1_000, "ms"),
+        ] {
+            if us.is_multiple_of(unit) {
+                return write!(f, "{}{}", us / unit, suffix);
+            }
+        }
+        write!(f, "{}us", us)
+    }
+}
+
+/// A soft or hard limit value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitValue<T> {
+    Value(T),
+    /// No limit
+    Infinity,
+}
+
+impl<T: LimitUnit> fmt::Display for LimitValue<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LimitValue::Value(value) => value.fmt_limit(f),
+            LimitValue::Infinity => write!(f, "infinity"),
+        }
+    }
+}
+
+impl<T: LimitUnit> LimitValue<T> {
+    fn exceeds(&self, other: &Self) -> bool {
+        match (self, other) {
+            (LimitValue::Value(a), LimitValue::Value(b)) => a > b,
+            (LimitValue::Infinity, LimitValue::Value(_)) => true,
+            (_, LimitValue::Infinity) => false,
+        }
+    }
+}
+
+/// A process resource limit as set with setrlimit(2).
+/// Renders as a single value when soft and hard limits are the same, `soft:hard` otherwise.
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#Process%20Properties
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResourceLimit<T> {
+    pub soft: LimitValue<T>,
+    pub hard: LimitValue<T>,
+}
+
+impl<T: LimitUnit> fmt::Display for ResourceLimit<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.soft == self.hard {
+            write!(f, "{}", self.soft)
+        } else {
+            write!(f, "{}:{}", self.soft, self.hard)
+        }
+    }
+}
+
+impl<T: LimitUnit> ResourceLimit<T> {
+    /// Sets both the soft and the hard limit to `value`.
+    pub fn new(value: T) -> Self {
+        Self {
+            soft: LimitValue::Value(value),
+            hard: LimitValue::Value(value),
+        }
+    }
+
+    /// Removes both limits.
+    pub fn infinity() -> Self {
+        Self {
+            soft: LimitValue::Infinity,
+            hard: LimitValue::Infinity,
+        }
+    }
+
+    /// Sets different soft and hard limits.
+    pub fn soft_hard(soft: LimitValue<T>, hard: LimitValue<T>) -> Self {
+        Self { soft, hard }
+    }
+
+    /// Validate that the soft limit does not exceed the hard limit
+    pub fn validate(&self, key: &str) -> Result<(), String> {
+        if self.soft.exceeds(&self.hard) {
+            return Err(format!(
+                "{} soft limit {} exceeds the hard limit {}",
+                key, self.soft, self.hard
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Process resource limits
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#Process%20Properties
+#[derive(Debug, Clone, Default)]
+pub struct Limits {
+    /// CPU time limit.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#LimitCPU=
+    pub cpu: Option<ResourceLimit<Duration>>,
+
+    /// Maximum size of files the process may create.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#LimitFSIZE=
+    pub fsize: Option<ResourceLimit<ByteSize>>,
+
+    /// Maximum size of the data segment.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#LimitDATA=
+    pub data: Option<ResourceLimit<ByteSize>>,
+
+    /// Maximum size of the process stack.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#LimitSTACK=
+    pub stack: Option<ResourceLimit<ByteSize>>,
+
+    /// Maximum size of core dumps.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#LimitCORE=
+    pub core: Option<ResourceLimit<ByteSize>>,
+
+    /// Maximum resident set size.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#LimitRSS=
+    pub rss: Option<ResourceLimit<ByteSize>>,
+
+    /// Maximum number of open file descriptors.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#LimitNOFILE=
+    pub nofile: Option<ResourceLimit<u64>>,
+
+    /// Maximum size of the address space.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#LimitAS=
+    pub address_space: Option<ResourceLimit<ByteSize>>,
+
+    /// Maximum number of processes of the user.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#LimitNPROC=
+    pub nproc: Option<ResourceLimit<u64>>,
+
+    /// Maximum amount of memory that may be locked into RAM.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#LimitMEMLOCK=
+    pub memlock: Option<ResourceLimit<ByteSize>>,
+
+    /// Maximum number of file locks.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#LimitLOCKS=
+    pub locks: Option<ResourceLimit<u64>>,
+
+    /// Maximum number of queued signals.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#LimitSIGPENDING=
+    pub sigpending: Option<ResourceLimit<u64>>,
+
+    /// Maximum size of POSIX message queues.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#LimitMSGQUEUE=
+    pub msgqueue: Option<ResourceLimit<ByteSize>>,
+
+    /// Ceiling of the nice level in its raw form, where 0 maps to nice 20 and 40 to nice -20.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#LimitNICE=
+    pub nice: Option<ResourceLimit<u64>>,
+
+    /// Ceiling of the realtime priority.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#LimitRTPRIO=
+    pub rtprio: Option<ResourceLimit<u64>>,
+
+    /// CPU time a realtime process may consume without a blocking system call.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#LimitRTTIME=
+    pub rttime: Option<ResourceLimit<Duration>>,
+}
+
+impl fmt::Display for Limits {
+    fn fmt(&self, buf: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_option!(buf, self.cpu, "LimitCPU");
+        write_option!(buf, self.fsize, "LimitFSIZE");
+        write_option!(buf, self.data, "LimitDATA");
+        write_option!(buf, self.stack, "LimitSTACK");
+        write_option!(buf, self.core, "LimitCORE");
+        write_option!(buf, self.rss, "LimitRSS");
+        write_option!(buf, self.nofile, "LimitNOFILE");
+        write_option!(buf, self.address_space, "LimitAS");
+        write_option!(buf, self.nproc, "LimitNPROC");
+        write_option!(buf, self.memlock, "LimitMEMLOCK");
+        write_option!(buf, self.locks, "LimitLOCKS");
+        write_option!(buf, self.sigpending, "LimitSIGPENDING");
+        write_option!(buf, self.msgqueue, "LimitMSGQUEUE");
+        write_option!(buf, self.nice, "LimitNICE");
+        write_option!(buf, self.rtprio, "LimitRTPRIO");
+        write_option!(buf, self.rttime, "LimitRTTIME");
+        Ok(())
+    }
+}
+
+impl Limits {
+    /// Creates an empty set of limits.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Validate the resource limits
+    pub fn validate(&self) -> Result<(), String> {
+        validate_limit(&self.cpu, "LimitCPU")?;
+        validate_limit(&self.fsize, "LimitFSIZE")?;
+        validate_limit(&self.data, "LimitDATA")?;
+        validate_limit(&self.stack, "LimitSTACK")?;
+        validate_limit(&self.core, "LimitCORE")?;
+        validate_limit(&self.rss, "LimitRSS")?;
+        validate_limit(&self.nofile, "LimitNOFILE")?;
+        validate_limit(&self.address_space, "LimitAS")?;
+        validate_limit(&self.nproc, "LimitNPROC")?;
+        validate_limit(&self.memlock, "LimitMEMLOCK")?;
+        validate_limit(&self.locks, "LimitLOCKS")?;
+        validate_limit(&self.sigpending, "LimitSIGPENDING")?;
+        validate_limit(&self.msgqueue, "LimitMSGQUEUE")?;
+        validate_limit(&self.nice, "LimitNICE")?;
+        validate_limit(&self.rtprio, "LimitRTPRIO")?;
+        validate_limit(&self.rttime, "LimitRTTIME")?;
+
+        if let Some(ref nice) = self.nice {
+            for value in [nice.soft, nice.hard] {
+                if let LimitValue::Value(raw) = value
+                    && raw > MAX_RAW_NICE
+                {
+                    return Err(format!(
+                        "LimitNICE {} must be between 0 and {}",
+                        raw, MAX_RAW_NICE
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // Builder pattern setters
+    pub fn cpu(mut self, value: ResourceLimit<Duration>) -> Self {
+        self.cpu = Some(value);
+        self
+    }
+
+    pub fn fsize(mut self, value: ResourceLimit<ByteSize>) -> Self {
+        self.fsize = Some(value);
+        self
+    }
+
+    pub fn data(mut self, value: ResourceLimit<ByteSize>) -> Self {
+        self.data = Some(value);
+        self
+    }
+
+    pub fn stack(mut self, value: ResourceLimit<ByteSize>) -> Self {
+        self.stack = Some(value);
+        self
+    }
+
+    pub fn core(mut self, value: ResourceLimit<ByteSize>) -> Self {
+        self.core = Some(value);
+        self
+    }
+
+    pub fn rss(mut self, value: ResourceLimit<ByteSize>) -> Self {
+        self.rss = Some(value);
+        self
+    }
+
+    pub fn nofile(mut self, value: ResourceLimit<u64>) -> Self {
+        self.nofile = Some(value);
+        self
+    }
+
+    pub fn address_space(mut self, value: ResourceLimit<ByteSize>) -> Self {
+        self.address_space = Some(value);
+        self
+    }
+
+    pub fn nproc(mut self, value: ResourceLimit<u64>) -> Self {
+        self.nproc = Some(value);
+        self
+    }
+
+    pub fn memlock(mut self, value: ResourceLimit<ByteSize>) -> Self {
+        self.memlock = Some(value);
+        self
+    }
+
+    pub fn locks(mut self, value: ResourceLimit<u64>) -> Self {
+        self.locks = Some(value);
+        self
+    }
+
+    pub fn sigpending(mut self, value: ResourceLimit<u64>) -> Self {
+        self.sigpending = Some(value);
+        self
+    }
+
+    pub fn msgqueue(mut self, value: ResourceLimit<ByteSize>) -> Self {
+        self.msgqueue = Some(value);
+        self
+    }
+
+    pub fn nice(mut self, value: ResourceLimit<u64>) -> Self {
+        self.nice = Some(value);
+        self
+    }
+
+    pub fn rtprio(mut self, value: ResourceLimit<u64>) -> Self {
+        self.rtprio = Some(value);
+        self
+    }
+
+    pub fn rttime(mut self, value: ResourceLimit<Duration>) -> Self {
+        self.rttime = Some(value);
+        self
+    }
+}
+
+fn validate_limit<T: LimitUnit>(limit: &Option<ResourceLimit<T>>, key: &str) -> Result<(), String> {
+    match limit {
+        Some(limit) => limit.validate(key),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn durations_render_with_the_largest_exact_unit() {
+        for (duration, rendered) in [
+            (Duration::ZERO, "0"),
+            (Duration::from_micros(1500), "1500us"),
+            (Duration::from_millis(250), "250ms"),
+            (Duration::from_millis(1500), "1500ms"),
+            (Duration::from_secs(90), "90s"),
+            (Duration::from_secs(120), "2min"),
+            (Duration::from_secs(5400), "90min"),
+            (Duration::from_secs(7200), "2h"),
+        ] {
+            assert_eq!(ResourceLimit::new(duration).to_string(), rendered);
+        }
+    }
+
+    #[test]
+    fn sizes_and_counts_render() {
+        assert_eq!(ResourceLimit::new(ByteSize::mib(8)).to_string(), "8M");
+        assert_eq!(
+            ResourceLimit::new(ByteSize::bytes(1000)).to_string(),
+            "1000"
+        );
+        assert_eq!(ResourceLimit::new(65536u64).to_string(), "65536");
+        assert_eq!(ResourceLimit::<u64>::infinity().to_string(), "infinity");
+    }
+
+    #[test]
+    fn different_soft_and_hard_limits_render_as_pair() {
+        let limit = ResourceLimit::soft_hard(LimitValue::Value(1024u64), LimitValue::Infinity);
+        assert_eq!(limit.to_string(), "1024:infinity");
+
+        let limit = ResourceLimit::soft_hard(
+            LimitValue::Value(Duration::from_secs(60)),
+            LimitValue::Value(Duration::from_secs(120)),
+        );
+        assert_eq!(limit.to_string(), "1min:2min");
+    }
+
+    #[test]
+    fn soft_limit_must_not_exceed_hard_limit() {
+        let equal = ResourceLimit::new(1024u64);
+        let lower = ResourceLimit::soft_hard(LimitValue::Value(1024u64), LimitValue::Value(4096));
+        let unlimited = ResourceLimit::soft_hard(LimitValue::Value(1024u64), LimitValue::Infinity);
+        assert!(equal.validate("LimitNOFILE").is_ok());
+        assert!(lower.validate("LimitNOFILE").is_ok());
+        assert!(unlimited.validate("LimitNOFILE").is_ok());
+        assert!(
+            ResourceLimit::<u64>::infinity()
+                .validate("LimitNOFILE")
+                .is_ok()
+        );
+
+        let higher = ResourceLimit::soft_hard(LimitValue::Value(4096u64), LimitValue::Value(1024));
+        assert_eq!(
+            higher.validate("LimitNOFILE"),
+            Err("LimitNOFILE soft limit 4096 exceeds the hard limit 1024".to_string())
+        );
+        let infinite_soft =
+            ResourceLimit::soft_hard(LimitValue::Infinity, LimitValue::Value(ByteSize::gib(1)));
+        assert_eq!(
+            Limits::new().memlock(infinite_soft).validate(),
+            Err("LimitMEMLOCK soft limit infinity exceeds the hard limit 1G".to_string())
+        );
+    }
+
+    #[test]
+    fn nice_limit_is_between_0_and_40() {
+        assert!(Limits::new().nice(ResourceLimit::new(0)).validate().is_ok());
+        assert!(
+            Limits::new()
+                .nice(ResourceLimit::new(40))
+                .validate()
+                .is_ok()
+        );
+        assert!(
+            Limits::new()
+                .nice(ResourceLimit::infinity())
+                .validate()
+                .is_ok()
+        );
+        assert_eq!(
+            Limits::new().nice(ResourceLimit::new(41)).validate(),
+            Err("LimitNICE 41 must be between 0 and 40".to_string())
+        );
+        let hard_too_high = ResourceLimit::soft_hard(LimitValue::Value(10), LimitValue::Value(50));
+        assert!(Limits::new().nice(hard_too_high).validate().is_err());
+    }
+
+    #[test]
+    fn renders_limits() {
+        let limits = Limits::new()
+            .cpu(ResourceLimit::new(Duration::from_secs(3600)))
+            .nofile(ResourceLimit::soft_hard(
+                LimitValue::Value(1024),
+                LimitValue::Value(65536),
+            ))
+            .core(ResourceLimit::infinity())
+            .rttime(ResourceLimit::new(Duration::from_micros(500)));
+
+        assert_eq!(
+            limits.to_string(),
+            "LimitCPU=1h\nLimitCORE=infinity\nLimitNOFILE=1024:65536\nLimitRTTIME=500us\n"
+        );
+    }
+}
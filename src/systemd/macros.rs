@@ -52,3 +52,35 @@ macro_rules! write_bool {
         }
     };
 }
+
+/// Write an Option<PathBuf> field as "Key=/path"
+#[macro_export]
+macro_rules! write_path {
+    ($buf:expr, $field:expr, $key:expr) => {
+        if let Some(ref path) = $field {
+            writeln!($buf, "{}={}", $key, path.display())?;
+        }
+    };
+}
+
+/// Write an Option<Vec<PathBuf>> field with one line per path
+#[macro_export]
+macro_rules! write_path_multi {
+    ($buf:expr, $field:expr, $key:expr) => {
+        if let Some(ref paths) = $field {
+            for path in paths {
+                writeln!($buf, "{}={}", $key, path.display())?;
+            }
+        }
+    };
+}
+
+/// Write an Option<u32> file mode field in octal as "Key=0755"
+#[macro_export]
+macro_rules! write_mode {
+    ($buf:expr, $field:expr, $key:expr) => {
+        if let Some(mode) = $field {
+            writeln!($buf, "{}={:04o}", $key, mode)?;
+        }
+    };
+}
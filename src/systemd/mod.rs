@@ -1,31 +1,94 @@
 use crate::Result;
 use crate::error::Error;
 use std::fmt;
-use std::fs::{self, File};
-use std::io::Write;
-use std::os::unix::fs::PermissionsExt;
 use std::path::PathBuf;
-use std::process::Command;
+use transaction::Transaction;
 use which::which;
 
 #[macro_use]
 mod macros;
+pub mod apply;
+pub mod automount;
+pub mod credential;
+#[cfg(feature = "dbus")]
+pub mod dbus;
+pub mod enable;
 pub mod exec;
 pub mod install;
+pub mod kill;
+pub mod limit;
+pub mod mount;
+pub mod namespace;
+pub mod network;
+pub mod path;
+pub mod resource_control;
+pub mod run;
+pub mod runner;
+pub mod scope;
 pub mod service;
+pub mod slice;
+pub mod socket;
+pub mod state;
+pub mod swap;
+pub mod syscall;
+pub mod target;
+#[cfg(test)]
+mod testing;
+pub mod timer;
+mod transaction;
+pub mod uninstall;
 pub mod unit;
-
-pub use exec::Exec;
-pub use install::Install;
+pub mod unit_file;
+mod wait;
+
+pub use apply::{ApplyOptions, ApplyReport, FileChange};
+pub use automount::{Automount, AutomountConfig};
+pub use credential::{Credentials, LoadCredential, SetCredential};
+#[cfg(feature = "dbus")]
+pub use dbus::{DbusManager, JobResult, UnitFileChange};
+pub use enable::{LinkIssue, UnitFileState};
+pub use exec::{
+    CpuSchedulingPolicy, DirectoryKind, Exec, IoSchedulingClass, LogLevel, NumaPolicy, ProcSubset,
+    ProtectHome, ProtectProc, ProtectSystem, RestrictNamespaces, RuntimeDirectoryPreserve,
+    StandardInput, StandardOutput, SyslogFacility,
+};
+pub use install::{Install, InstallLink};
+pub use kill::Kill;
+pub use limit::{LimitValue, Limits, ResourceLimit};
+pub use mount::{Mount, MountConfig};
+pub use namespace::{
+    BindPath, ExtensionImage, MountFlags, MountImage, MountOption, NamespacePath, PartitionOptions,
+    RootHash, RootHashSignature, RootImage, TemporaryFileSystem,
+};
+pub use network::{
+    AddressFamilies, IpAddressRule, RestrictNetworkInterfaces, SocketBindFamily,
+    SocketBindProtocol, SocketBindRule,
+};
+pub use path::{Path, PathConfig};
+pub use resource_control::ResourceControl;
+pub use run::{Run, RunOutput};
+pub use runner::{CommandOutput, CommandRunner, MockRunner, ProcessRunner};
+pub use scope::Scope;
 pub use service::Service;
+pub use slice::SliceConfig;
+pub use socket::{Socket, SocketConfig};
+pub use state::{ActiveState, ExitReason, LoadState, UnitResult, UnitState};
+pub use swap::{Swap, SwapConfig};
+pub use syscall::SystemCallFilter;
+pub use target::TargetConfig;
+pub use timer::{Timer, TimerConfig};
+pub use uninstall::{UninstallOptions, UninstallReport};
 pub use unit::Unit;
+pub use unit_file::{UnitFile, UnitType};
 
 const SYSTEMCTL: &str = "systemctl";
+const JOURNALCTL: &str = "journalctl";
+const LOGINCTL: &str = "loginctl";
 const SERVICE_FILE_PERMISSIONS: u32 = 0o644;
 
 /// Configuration for a systemd service.
 /// This struct holds all necessary information to create and manage a systemd service.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Config {
     pub name: String,
     pub unit: Unit,
@@ -69,6 +132,11 @@ impl Config {
         self.install.validate().map_err(Error::ValidationError)?;
         Ok(())
     }
+
+    /// Returns warnings about settings that pass validation but are discouraged by systemd.
+    pub fn warnings(&self) -> Vec<String> {
+        self.service.warnings()
+    }
 }
 
 impl fmt::Display for Config {
@@ -81,16 +149,28 @@ impl fmt::Display for Config {
 }
 
 impl Config {
-    fn unit_name(&self) -> String {
+    pub(crate) fn unit_name(&self) -> String {
         format!("{}.service", self.name)
     }
 }
 
 /// A service manager for systemd.
-/// This struct provides methods to install, uninstall, start, stop, restart, and check the status of a systemd service.
+/// This struct provides methods to install, uninstall, start, stop, restart, and check the status of a systemd unit.
 #[derive(Debug)]
 pub struct Systemd {
-    config: Config,
+    config: UnitFile,
+    scope: ManagerScope,
+    root: Option<PathBuf>,
+    linger: bool,
+    runner: Box<dyn CommandRunner>,
+    #[cfg(feature = "dbus")]
+    dbus: Option<DbusManager>,
+    /// Replaces the unit directory of the scope, so tests do not touch the running system.
+    #[cfg(test)]
+    unit_dir: Option<PathBuf>,
+    /// Replaces the interval between two reads of the unit state while waiting.
+    #[cfg(test)]
+    poll_interval: Option<std::time::Duration>,
 }
 
 impl Systemd {
@@ -115,200 +195,350 @@ impl fmt::Display for Status {
 
 impl Systemd {
     /// Creates a new `Systemd` service manager with the given configuration.
-    pub fn new(config: Config) -> Self {
-        Self { config }
+    /// Accepts a service [`Config`] or any other unit configuration convertible into a [`UnitFile`].
+    pub fn new(config: impl Into<UnitFile>) -> Self {
+        Self {
+            config: config.into(),
+            scope: ManagerScope::System,
+            root: None,
+            linger: false,
+            runner: Box::new(ProcessRunner),
+            #[cfg(feature = "dbus")]
+            dbus: None,
+            #[cfg(test)]
+            unit_dir: None,
+            #[cfg(test)]
+            poll_interval: None,
+        }
     }
 
-    fn config_path(&self) -> PathBuf {
-        PathBuf::from(format!("/etc/systemd/system/{}", self.config.unit_name()))
+    /// Sets the service manager the unit is installed for, the system manager by default.
+    pub fn scope(mut self, scope: ManagerScope) -> Self {
+        self.scope = scope;
+        self
+    }
+
+    /// Installs into the file system tree at `root`, e.g. an image being built in a chroot,
+    /// instead of the running system. The unit file and its `[Install]` symlinks are created
+    /// directly and no service manager is contacted, so units cannot be started in this mode.
+    pub fn root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.root = Some(root.into());
+        self
+    }
+
+    /// Enables lingering of the calling user on install, so user services start at boot
+    /// and keep running without a login session. Only applies to [`ManagerScope::User`].
+    pub fn linger(mut self, value: bool) -> Self {
+        self.linger = value;
+        self
+    }
+
+    /// Replaces the runner executing `systemctl`, `journalctl` and `loginctl`, which by default
+    /// spawns them as child processes.
+    pub fn runner(mut self, runner: impl CommandRunner + 'static) -> Self {
+        self.runner = Box::new(runner);
+        self
+    }
+
+    /// Talks to the service manager over D-Bus instead of running `systemctl` to install,
+    /// uninstall, start, stop and restart the unit and to read its status.
+    /// The manager has to match the scope, e.g. [`DbusManager::session`] for user units.
+    /// Globally installed units are always enabled with `systemctl`.
+    #[cfg(feature = "dbus")]
+    pub fn dbus(mut self, manager: DbusManager) -> Self {
+        self.dbus = Some(manager);
+        self
     }
 
+    /// Returns the unit file managed by this manager.
+    pub fn unit_file(&self) -> &UnitFile {
+        &self.config
+    }
+
+    /// Returns the full name of the managed unit, e.g. `foo.service`.
+    pub fn unit_name(&self) -> String {
+        self.config.unit_name()
+    }
+
+    /// Validates the managed unit file.
+    pub fn validate(&self) -> Result<()> {
+        self.config.validate()
+    }
+
+    /// Returns the path the unit file is installed to, below the alternate root if one is set.
+    pub fn config_path(&self) -> Result<PathBuf> {
+        Ok(self.rooted(&self.unit_dir()?.join(self.config.unit_name())))
+    }
+
+    /// Returns the unit directory as seen by the service manager, without the alternate root.
+    fn unit_dir(&self) -> Result<PathBuf> {
+        if self.root.is_some() && self.scope == ManagerScope::User {
+            return Err(Error::ValidationError(
+                "An alternate root cannot be used with the user scope".to_string(),
+            ));
+        }
+        #[cfg(test)]
+        if let Some(ref dir) = self.unit_dir {
+            return Ok(dir.clone());
+        }
+        self.scope.unit_dir()
+    }
+
+    /// Prefixes `path` with the alternate root, if one is set.
+    fn rooted(&self, path: &std::path::Path) -> PathBuf {
+        match self.root {
+            Some(ref root) => root.join(path.strip_prefix("/").unwrap_or(path)),
+            None => path.to_path_buf(),
+        }
+    }
+
+    /// Installs and enables the unit. The unit file is written atomically, and if enabling
+    /// or reloading fails, the unit file is removed again so the install can be retried.
     pub fn install(&self) -> Result<()> {
-        let dst = self.config_path();
+        let dst = self.config_path()?;
         if dst.exists() {
             return Err(Error::ValidationError(format!(
                 "Service file '{dst:?}' already exists"
             )));
         }
-        let mut file = File::create(&dst)?;
-        file.write_all(format!("{}", self.config).as_bytes())?;
+        let mut transaction = Transaction::default();
+        self.write_unit_file(&mut transaction, &dst)?;
+
+        if self.root.is_some() {
+            return match self.enable() {
+                Ok(_) => Ok(()),
+                Err(e) => Err(self.roll_back(transaction, e)),
+            };
+        }
+        if let Err(e) = self.enable_and_reload() {
+            // Enabling may have created some of the symlinks before failing
+            let _ = self.disable_with_manager();
+            return Err(self.roll_back(transaction, e));
+        }
+        Ok(())
+    }
 
-        std::fs::set_permissions(
-            &dst,
-            std::fs::Permissions::from_mode(SERVICE_FILE_PERMISSIONS),
-        )?;
+    fn enable_and_reload(&self) -> Result<()> {
+        if self.linger && self.scope == ManagerScope::User {
+            self.enable_linger()?;
+        }
 
-        match Command::new(SYSTEMCTL)
-            .arg("enable")
-            .arg(self.config.unit_name())
-            .output()?
+        let unit_name = self.config.unit_name();
+        #[cfg(feature = "dbus")]
+        if let Some(ref manager) = self.dbus
+            && self.scope != ManagerScope::Global
         {
-            output if output.status.success() => {}
-            output => {
-                return Err(Error::CommandError(format!(
-                    "Failed to enable service: {}",
-                    String::from_utf8_lossy(&output.stderr)
-                )));
-            }
+            manager.enable_unit_files(&[&unit_name])?;
+            return self.daemon_reload();
         }
+        self.systemctl(&["enable", &unit_name], "enable service")?;
+        self.daemon_reload()
+    }
 
-        match Command::new(SYSTEMCTL).arg("daemon-reload").output()? {
-            output if output.status.success() => {}
-            output => {
-                return Err(Error::CommandError(format!(
-                    "Failed to reload systemd daemon: {}",
-                    String::from_utf8_lossy(&output.stderr)
-                )));
-            }
+    /// Writes the rendered unit file to `dst` as part of `transaction`.
+    fn write_unit_file(&self, transaction: &mut Transaction, dst: &std::path::Path) -> Result<()> {
+        transaction.write(
+            dst,
+            self.config.to_string().as_bytes(),
+            SERVICE_FILE_PERMISSIONS,
+        )?;
+        Ok(())
+    }
+
+    /// Undoes the changes of a failed install or apply and reloads the service manager,
+    /// returning `error`, or a description of both errors if the rollback failed as well.
+    fn roll_back(&self, transaction: Transaction, error: Error) -> Error {
+        let rolled_back = transaction.rollback();
+        let _ = self.daemon_reload();
+        match rolled_back {
+            Ok(()) => error,
+            Err(e) => Error::CommandError(format!("{}; rolling back failed: {}", error, e)),
         }
+    }
 
+    /// Makes the service manager reload all unit files.
+    /// Does nothing below an alternate root or in the global scope, where there is no manager to
+    /// reload and each user's manager picks the unit up on its own.
+    pub fn daemon_reload(&self) -> Result<()> {
+        if self.root.is_some() || self.scope == ManagerScope::Global {
+            return Ok(());
+        }
+        #[cfg(feature = "dbus")]
+        if let Some(ref manager) = self.dbus {
+            return manager.reload();
+        }
+        self.systemctl(&["daemon-reload"], "reload systemd daemon")?;
         Ok(())
     }
 
+    /// Uninstalls the unit with the default [`UninstallOptions`]: the unit is stopped, disabled,
+    /// its unit file and drop-ins are removed and the service manager is reloaded.
+    /// Uninstalling a unit that is not installed is not an error.
     pub fn uninstall(&self) -> Result<()> {
-        match Command::new(SYSTEMCTL)
-            .arg("disable")
-            .arg(self.config.unit_name())
-            .output()?
+        self.uninstall_with(&UninstallOptions::default())?;
+        Ok(())
+    }
+
+    /// Disables the unit through the service manager rather than natively.
+    fn disable_with_manager(&self) -> Result<()> {
+        let unit_name = self.config.unit_name();
+        #[cfg(feature = "dbus")]
+        if let Some(ref manager) = self.dbus
+            && self.scope != ManagerScope::Global
         {
-            output if output.status.success() => {}
-            output => {
-                return Err(Error::CommandError(format!(
-                    "Failed to disable service: {}",
-                    String::from_utf8_lossy(&output.stderr)
-                )));
-            }
+            manager.disable_unit_files(&[&unit_name])?;
+            return Ok(());
         }
-
-        fs::remove_file(self.config_path())?;
-
+        self.systemctl(&["disable", &unit_name], "disable service")?;
         Ok(())
     }
 
     pub fn start(&self) -> Result<()> {
-        match Command::new(SYSTEMCTL)
-            .arg("start")
-            .arg(self.config.unit_name())
-            .output()?
+        let unit_name = self.config.unit_name();
+        #[cfg(feature = "dbus")]
+        if let Some(ref manager) = self.dbus
+            && self.root.is_none()
         {
-            output if output.status.success() => {}
-            output => {
-                return Err(Error::CommandError(format!(
-                    "Failed to start service: {}",
-                    String::from_utf8_lossy(&output.stderr)
-                )));
-            }
+            return job_result(&unit_name, "start", manager.start_unit(&unit_name)?);
         }
-
+        self.systemctl(&["start", &unit_name], "start service")?;
         Ok(())
     }
 
     pub fn stop(&self) -> Result<()> {
-        match Command::new(SYSTEMCTL)
-            .arg("stop")
-            .arg(self.config.unit_name())
-            .output()?
+        let unit_name = self.config.unit_name();
+        #[cfg(feature = "dbus")]
+        if let Some(ref manager) = self.dbus
+            && self.root.is_none()
         {
-            output if output.status.success() => {}
-            output => {
-                return Err(Error::CommandError(format!(
-                    "Failed to stop service: {}",
-                    String::from_utf8_lossy(&output.stderr)
-                )));
-            }
+            return job_result(&unit_name, "stop", manager.stop_unit(&unit_name)?);
         }
-
+        self.systemctl(&["stop", &unit_name], "stop service")?;
         Ok(())
     }
 
     pub fn restart(&self) -> Result<()> {
-        match Command::new(SYSTEMCTL)
-            .arg("restart")
-            .arg(self.config.unit_name())
-            .output()?
+        let unit_name = self.config.unit_name();
+        #[cfg(feature = "dbus")]
+        if let Some(ref manager) = self.dbus
+            && self.root.is_none()
         {
-            output if output.status.success() => {}
-            output => {
-                return Err(Error::CommandError(format!(
-                    "Failed to restart service: {}",
-                    String::from_utf8_lossy(&output.stderr)
-                )));
-            }
+            return job_result(&unit_name, "restart", manager.restart_unit(&unit_name)?);
         }
-
+        self.systemctl(&["restart", &unit_name], "restart service")?;
         Ok(())
     }
 
     pub fn status(&self) -> Result<Status> {
-        let status = match Command::new(SYSTEMCTL)
-            .arg("is-active")
-            .arg(self.config.unit_name())
-            .output()?
-        {
-            output if output.status.success() => {
-                String::from_utf8_lossy(&output.stdout).trim().to_string()
-            }
-            output => {
-                return Err(Error::CommandError(format!(
-                    "Failed to get service status: {}",
-                    String::from_utf8_lossy(&output.stderr)
-                )));
-            }
-        };
+        Ok(self.state()?.status())
+    }
 
-        match status.as_str() {
-            "active" => Ok(Status::Running),
-            "inactive" => {
-                let status = match Command::new(SYSTEMCTL)
-                    .arg("list-unit-files")
-                    .arg("-t")
-                    .arg("service")
-                    .arg(self.config.unit_name())
-                    .output()?
-                {
-                    output if output.status.success() => {
-                        String::from_utf8_lossy(&output.stdout).to_string()
-                    }
-                    output => {
-                        return Err(Error::CommandError(format!(
-                            "Failed to list unit files: {}",
-                            String::from_utf8_lossy(&output.stderr)
-                        )));
-                    }
-                };
-
-                if status.contains(&self.config.unit_name()) {
-                    Ok(Status::Stopped)
-                } else {
-                    Ok(Status::NotInstalled)
-                }
-            }
-            "activating" => Ok(Status::Running),
-            "failed" => Ok(Status::Failed),
-            _ => Ok(Status::NotInstalled),
+    /// Returns the runtime state of the unit, read with `systemctl show`.
+    /// A unit without a unit file is not an error, its [`UnitState::load_state`] is `NotFound`.
+    pub fn state(&self) -> Result<UnitState> {
+        self.unit_state(&self.config.unit_name())
+    }
+
+    /// Returns the status of every member of a target, in the order they are listed in `Wants=`.
+    /// For other unit types this is the status of the unit alone.
+    pub fn group_status(&self) -> Result<Vec<(String, Status)>> {
+        match self.config {
+            UnitFile::Target(ref target) => target
+                .members()
+                .iter()
+                .map(|member| Ok((member.clone(), self.unit_state(member)?.status())))
+                .collect(),
+            _ => Ok(vec![(self.config.unit_name(), self.status()?)]),
         }
     }
 
-    pub fn logs(&self) -> Result<String> {
-        let output = match Command::new("journalctl")
-            .arg("-u")
-            .arg(self.config.unit_name())
-            .arg("--no-pager")
-            .output()?
+    fn unit_state(&self, unit_name: &str) -> Result<UnitState> {
+        #[cfg(feature = "dbus")]
+        if let Some(ref manager) = self.dbus
+            && self.root.is_none()
         {
-            output if output.status.success() => {
-                String::from_utf8_lossy(&output.stdout).to_string()
-            }
-            output => {
-                return Err(Error::CommandError(format!(
-                    "Failed to get service logs: {}",
-                    String::from_utf8_lossy(&output.stderr)
-                )));
-            }
+            return UnitState::from_dbus(manager, unit_name);
+        }
+        // Unlike is-active, show exits successfully for inactive and unknown units
+        let properties = format!("--property={}", state::PROPERTIES.join(","));
+        let output = self.systemctl(&["show", &properties, unit_name], "get unit state")?;
+        UnitState::parse(&output)
+    }
+
+    pub fn logs(&self) -> Result<String> {
+        self.journal(&["--no-pager"])
+    }
+
+    /// Returns the last `lines` log lines of the unit.
+    pub fn recent_logs(&self, lines: usize) -> Result<String> {
+        self.journal(&["-n", &lines.to_string(), "--no-pager"])
+    }
+
+    fn journal(&self, args: &[&str]) -> Result<String> {
+        let unit_name = self.config.unit_name();
+        let unit_flag = match self.scope {
+            ManagerScope::System => "-u",
+            ManagerScope::User | ManagerScope::Global => "--user-unit",
         };
+        let mut journal_args = vec![unit_flag, &unit_name];
+        journal_args.extend_from_slice(args);
+        self.command(JOURNALCTL, &journal_args, "get service logs")
+    }
+
+    /// Enables lingering of the calling user, so its user manager is started at boot
+    /// and user services keep running after the user logs out.
+    pub fn enable_linger(&self) -> Result<()> {
+        self.command(LOGINCTL, &["enable-linger"], "enable lingering")?;
+        Ok(())
+    }
 
-        Ok(output)
+    /// Disables lingering of the calling user.
+    pub fn disable_linger(&self) -> Result<()> {
+        self.command(LOGINCTL, &["disable-linger"], "disable lingering")?;
+        Ok(())
+    }
+
+    /// Runs `systemctl` with `args` against the manager of the scope, returning its standard output.
+    /// `action` describes the invocation in the error returned when it fails.
+    fn systemctl(&self, args: &[&str], action: &str) -> Result<String> {
+        if let Some(ref root) = self.root {
+            return Err(Error::ValidationError(format!(
+                "Cannot {} in alternate root '{}', no service manager is running there",
+                action,
+                root.display()
+            )));
+        }
+        let mut scoped = Vec::with_capacity(args.len() + 1);
+        if let Some(flag) = self.scope.flag() {
+            scoped.push(flag);
+        }
+        scoped.extend_from_slice(args);
+        self.command(SYSTEMCTL, &scoped, action)
     }
+
+    fn command(&self, program: &str, args: &[&str], action: &str) -> Result<String> {
+        let output = self.runner.run(program, args)?;
+        if !output.is_success() {
+            return Err(Error::CommandError(format!(
+                "Failed to {}: {}",
+                action,
+                output.stderr_lossy()
+            )));
+        }
+        Ok(output.stdout_lossy())
+    }
+}
+
+/// Turns the result of a job queued over D-Bus into an error unless it succeeded.
+#[cfg(feature = "dbus")]
+fn job_result(unit_name: &str, verb: &str, result: JobResult) -> Result<()> {
+    if !result.is_success() {
+        return Err(Error::CommandError(format!(
+            "Failed to {} service: job for {} finished with result '{}'",
+            verb, unit_name, result
+        )));
+    }
+    Ok(())
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Copy)]
@@ -319,3 +549,412 @@ pub enum Status {
     NotInstalled,
     Failed,
 }
+
+/// Service manager instance a unit is managed by.
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Default)]
+pub enum ManagerScope {
+    /// The system service manager, running as PID 1.
+    #[default]
+    System,
+    /// The per-user service manager of the calling user.
+    User,
+    /// The per-user service managers of all users. Only unit files can be installed and enabled
+    /// in this scope, the units are started by each user's manager.
+    Global,
+}
+
+impl ManagerScope {
+    /// Returns the `systemctl` flag selecting the manager, `None` for the system manager.
+    pub fn flag(&self) -> Option<&'static str> {
+        match self {
+            ManagerScope::System => None,
+            ManagerScope::User => Some("--user"),
+            ManagerScope::Global => Some("--global"),
+        }
+    }
+
+    /// Returns the directory administrator or user unit files are installed to:
+    /// `/etc/systemd/system`, `$XDG_CONFIG_HOME/systemd/user` or `/etc/systemd/user`.
+    pub fn unit_dir(&self) -> Result<PathBuf> {
+        match self {
+            ManagerScope::System => Ok(PathBuf::from("/etc/systemd/system")),
+            ManagerScope::User => Ok(xdg_dir("XDG_CONFIG_HOME", ".config")?.join("systemd/user")),
+            ManagerScope::Global => Ok(PathBuf::from("/etc/systemd/user")),
+        }
+    }
+}
+
+/// Resolves an XDG base directory of the calling user, falling back to `$HOME/<fallback>`.
+pub(crate) fn xdg_dir(var: &str, fallback: &str) -> Result<PathBuf> {
+    if let Some(dir) = std::env::var_os(var).map(PathBuf::from)
+        && dir.is_absolute()
+    {
+        return Ok(dir);
+    }
+    match std::env::var_os("HOME") {
+        Some(home) if !home.is_empty() => Ok(PathBuf::from(home).join(fallback)),
+        _ => Err(Error::IoError(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("Neither {} nor HOME is set", var),
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+    use std::sync::Arc;
+    use testing::TempDir;
+
+    const SHOW_RUNNING: &str = "LoadState=loaded\nActiveState=active\nSubState=running\n\
+        UnitFileState=enabled\nMainPID=4242\nExecMainStatus=0\nExecMainCode=0\nResult=success\n\
+        NRestarts=0\n";
+
+    fn ok() -> CommandOutput {
+        CommandOutput::success("")
+    }
+
+    fn config() -> Config {
+        Config::new("demo").service(Service {
+            exec_start: Some(vec!["/usr/bin/demo".to_string()]),
+            ..Default::default()
+        })
+    }
+
+    /// A manager of the `demo` service installing into `dir` and running commands with `runner`.
+    fn systemd(dir: &TempDir, runner: &Arc<MockRunner>) -> Systemd {
+        Systemd::new(config())
+            .runner(Arc::clone(runner))
+            .with_unit_dir(dir)
+    }
+
+    fn show_properties() -> String {
+        format!("--property={}", state::PROPERTIES.join(","))
+    }
+
+    #[test]
+    fn install_enables_then_reloads() {
+        let dir = TempDir::new();
+        let runner = Arc::new(
+            MockRunner::new()
+                .expect(SYSTEMCTL, &["enable", "demo.service"], ok())
+                .expect(SYSTEMCTL, &["daemon-reload"], ok()),
+        );
+
+        systemd(&dir, &runner).install().unwrap();
+
+        runner.assert_done();
+        assert_eq!(
+            fs::read_to_string(dir.join("demo.service")).unwrap(),
+            config().to_string()
+        );
+    }
+
+    #[test]
+    fn install_refuses_existing_unit_file() {
+        let dir = TempDir::new();
+        fs::write(dir.join("demo.service"), "[Service]\n").unwrap();
+        let runner = Arc::new(MockRunner::new());
+
+        let result = systemd(&dir, &runner).install();
+
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+        assert!(runner.calls().is_empty());
+    }
+
+    #[test]
+    fn uninstall_stops_disables_and_reloads() {
+        let dir = TempDir::new();
+        fs::write(dir.join("demo.service"), config().to_string()).unwrap();
+        let runner = Arc::new(
+            MockRunner::new()
+                .expect(SYSTEMCTL, &["stop", "demo.service"], ok())
+                .expect(SYSTEMCTL, &["reset-failed", "demo.service"], ok())
+                .expect(SYSTEMCTL, &["disable", "demo.service"], ok())
+                .expect(SYSTEMCTL, &["daemon-reload"], ok()),
+        );
+
+        systemd(&dir, &runner).uninstall().unwrap();
+
+        runner.assert_done();
+        assert!(!dir.join("demo.service").exists());
+    }
+
+    #[test]
+    fn start_stop_restart() {
+        let dir = TempDir::new();
+        let runner = Arc::new(
+            MockRunner::new()
+                .expect(SYSTEMCTL, &["start", "demo.service"], ok())
+                .expect(SYSTEMCTL, &["stop", "demo.service"], ok())
+                .expect(SYSTEMCTL, &["restart", "demo.service"], ok()),
+        );
+        let systemd = systemd(&dir, &runner);
+
+        systemd.start().unwrap();
+        systemd.stop().unwrap();
+        systemd.restart().unwrap();
+
+        runner.assert_done();
+    }
+
+    #[test]
+    fn failed_command_reports_stderr() {
+        let dir = TempDir::new();
+        let runner = Arc::new(MockRunner::new().expect(
+            SYSTEMCTL,
+            &["start", "demo.service"],
+            CommandOutput::failure(1, "Job for demo.service failed."),
+        ));
+
+        let error = systemd(&dir, &runner).start().unwrap_err();
+
+        runner.assert_done();
+        assert_eq!(
+            error.to_string(),
+            "Command Error: Failed to start service: Job for demo.service failed."
+        );
+    }
+
+    #[test]
+    fn status_reads_unit_properties() {
+        let dir = TempDir::new();
+        let properties = show_properties();
+        let runner = Arc::new(MockRunner::new().expect(
+            SYSTEMCTL,
+            &["show", &properties, "demo.service"],
+            CommandOutput::success(SHOW_RUNNING),
+        ));
+
+        let status = systemd(&dir, &runner).status().unwrap();
+
+        runner.assert_done();
+        assert_eq!(status, Status::Running);
+    }
+
+    #[test]
+    fn group_status_reads_every_member() {
+        let properties = show_properties();
+        let runner = Arc::new(
+            MockRunner::new()
+                .expect(
+                    SYSTEMCTL,
+                    &["show", &properties, "web.service"],
+                    CommandOutput::success(SHOW_RUNNING),
+                )
+                .expect(
+                    SYSTEMCTL,
+                    &["show", &properties, "worker.service"],
+                    CommandOutput::success(
+                        "LoadState=loaded\nActiveState=failed\nSubState=failed\nResult=exit-code\n",
+                    ),
+                ),
+        );
+        let (target, _members) =
+            TargetConfig::group("app", [Config::new("web"), Config::new("worker")]);
+
+        let status = Systemd::new(target)
+            .runner(Arc::clone(&runner))
+            .group_status()
+            .unwrap();
+
+        runner.assert_done();
+        assert_eq!(
+            status,
+            [
+                ("web.service".to_string(), Status::Running),
+                ("worker.service".to_string(), Status::Failed),
+            ]
+        );
+    }
+
+    #[test]
+    fn logs_of_system_unit() {
+        let dir = TempDir::new();
+        let runner = Arc::new(
+            MockRunner::new()
+                .expect(
+                    JOURNALCTL,
+                    &["-u", "demo.service", "--no-pager"],
+                    CommandOutput::success("started\n"),
+                )
+                .expect(
+                    JOURNALCTL,
+                    &["-u", "demo.service", "-n", "5", "--no-pager"],
+                    ok(),
+                ),
+        );
+        let systemd = systemd(&dir, &runner);
+
+        assert_eq!(systemd.logs().unwrap(), "started\n");
+        systemd.recent_logs(5).unwrap();
+
+        runner.assert_done();
+    }
+
+    #[test]
+    fn user_scope_prefixes_commands() {
+        let dir = TempDir::new();
+        let runner = Arc::new(
+            MockRunner::new()
+                .expect(SYSTEMCTL, &["--user", "enable", "demo.service"], ok())
+                .expect(SYSTEMCTL, &["--user", "daemon-reload"], ok())
+                .expect(SYSTEMCTL, &["--user", "start", "demo.service"], ok())
+                .expect(
+                    JOURNALCTL,
+                    &["--user-unit", "demo.service", "--no-pager"],
+                    ok(),
+                ),
+        );
+        let systemd = systemd(&dir, &runner).scope(ManagerScope::User);
+
+        systemd.install().unwrap();
+        systemd.start().unwrap();
+        systemd.logs().unwrap();
+
+        runner.assert_done();
+    }
+
+    #[test]
+    fn global_scope_enables_without_reload() {
+        let dir = TempDir::new();
+        let runner = Arc::new(
+            MockRunner::new()
+                .expect(SYSTEMCTL, &["--global", "enable", "demo.service"], ok())
+                .expect(SYSTEMCTL, &["--global", "disable", "demo.service"], ok()),
+        );
+        let systemd = systemd(&dir, &runner).scope(ManagerScope::Global);
+
+        systemd.install().unwrap();
+        assert!(dir.join("demo.service").exists());
+        let report = systemd
+            .uninstall_with(&UninstallOptions::default())
+            .unwrap();
+
+        runner.assert_done();
+        assert!(report.disabled);
+        assert!(!report.stopped);
+        assert!(!report.reloaded);
+    }
+
+    #[test]
+    fn install_below_root_links_without_manager() {
+        let root = TempDir::new();
+        let runner = Arc::new(MockRunner::new());
+        let config = config().install(
+            Install::default()
+                .wanted_by(vec!["multi-user.target"])
+                .required_by(vec!["network-online.target"])
+                .alias(vec!["demo-alias.service"]),
+        );
+        let systemd = Systemd::new(config.clone())
+            .root(root.path())
+            .runner(Arc::clone(&runner));
+
+        systemd.install().unwrap();
+
+        let unit_dir = root.join("etc/systemd/system");
+        let unit_file = unit_dir.join("demo.service");
+        assert_eq!(systemd.config_path().unwrap(), unit_file);
+        assert_eq!(fs::read_to_string(&unit_file).unwrap(), config.to_string());
+        let mode = fs::metadata(&unit_file).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, SERVICE_FILE_PERMISSIONS);
+        // Targets are as seen by the service manager of the image, without the root
+        for link in [
+            "multi-user.target.wants/demo.service",
+            "network-online.target.requires/demo.service",
+            "demo-alias.service",
+        ] {
+            assert_eq!(
+                fs::read_link(unit_dir.join(link)).unwrap(),
+                PathBuf::from("/etc/systemd/system/demo.service")
+            );
+        }
+        assert!(matches!(systemd.start(), Err(Error::ValidationError(_))));
+        assert!(runner.calls().is_empty());
+    }
+
+    #[test]
+    fn install_rolls_back_when_enable_fails() {
+        let dir = TempDir::new();
+        let runner = Arc::new(
+            MockRunner::new()
+                .expect(
+                    SYSTEMCTL,
+                    &["enable", "demo.service"],
+                    CommandOutput::failure(1, "Failed to enable unit"),
+                )
+                .expect(SYSTEMCTL, &["disable", "demo.service"], ok())
+                .expect(SYSTEMCTL, &["daemon-reload"], ok()),
+        );
+
+        let result = systemd(&dir, &runner).install();
+
+        runner.assert_done();
+        assert!(matches!(result, Err(Error::CommandError(_))));
+        assert!(!dir.join("demo.service").exists());
+    }
+
+    #[test]
+    fn install_rolls_back_when_reload_fails() {
+        let dir = TempDir::new();
+        let runner = Arc::new(
+            MockRunner::new()
+                .expect(SYSTEMCTL, &["enable", "demo.service"], ok())
+                .expect(
+                    SYSTEMCTL,
+                    &["daemon-reload"],
+                    CommandOutput::failure(1, "Access denied"),
+                )
+                .expect(SYSTEMCTL, &["disable", "demo.service"], ok())
+                .expect(SYSTEMCTL, &["daemon-reload"], ok()),
+        );
+
+        let error = systemd(&dir, &runner).install().unwrap_err();
+
+        runner.assert_done();
+        assert_eq!(
+            error.to_string(),
+            "Command Error: Failed to reload systemd daemon: Access denied"
+        );
+        assert!(!dir.join("demo.service").exists());
+        // Nothing is left behind, so the install can be retried
+        let retry = Arc::new(
+            MockRunner::new()
+                .expect(SYSTEMCTL, &["enable", "demo.service"], ok())
+                .expect(SYSTEMCTL, &["daemon-reload"], ok()),
+        );
+        systemd(&dir, &retry).install().unwrap();
+        retry.assert_done();
+    }
+
+    #[test]
+    fn apply_restores_previous_unit_file() {
+        let dir = TempDir::new();
+        let unit_file = dir.join("demo.service");
+        fs::write(&unit_file, "[Service]\nExecStart=/usr/bin/old\n").unwrap();
+        fs::set_permissions(&unit_file, fs::Permissions::from_mode(0o600)).unwrap();
+        let runner = Arc::new(
+            MockRunner::new()
+                .expect(
+                    SYSTEMCTL,
+                    &["daemon-reload"],
+                    CommandOutput::failure(1, "Access denied"),
+                )
+                .expect(SYSTEMCTL, &["daemon-reload"], ok()),
+        );
+
+        let result = systemd(&dir, &runner).apply(&ApplyOptions::default());
+
+        runner.assert_done();
+        assert!(result.is_err());
+        assert_eq!(
+            fs::read_to_string(&unit_file).unwrap(),
+            "[Service]\nExecStart=/usr/bin/old\n"
+        );
+        let mode = fs::metadata(&unit_file).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
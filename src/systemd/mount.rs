@@ -0,0 +1,297 @@
+use super::exec::Exec;
+use super::install::Install;
+use super::kill::Kill;
+use super::resource_control::ResourceControl;
+use super::unit::Unit;
+use super::unit_file::escape_path;
+use crate::error::Error;
+use std::fmt;
+use std::path::PathBuf;
+
+/// Mount section options
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.mount.html#Options
+#[derive(Debug, Clone, Default)]
+pub struct Mount {
+    /// Absolute path of a device node, file or other resource to mount.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.mount.html#What=
+    pub what: String,
+
+    /// Absolute path of the mount point. The unit name is derived from it.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.mount.html#Where=
+    pub where_: PathBuf,
+
+    /// File system type.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.mount.html#Type=
+    pub fs_type: Option<String>,
+
+    /// Mount options.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.mount.html#Options=
+    pub options: Option<Vec<String>>,
+
+    /// Tolerate unknown mount options.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.mount.html#SloppyOptions=
+    pub sloppy_options: Option<bool>,
+
+    /// Detach the file system immediately and clean up references later.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.mount.html#LazyUnmount=
+    pub lazy_unmount: Option<bool>,
+
+    /// Fail if the file system cannot be mounted read-write.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.mount.html#ReadWriteOnly=
+    pub read_write_only: Option<bool>,
+
+    /// Force an unmount, e.g. of an unreachable NFS file system.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.mount.html#ForceUnmount=
+    pub force_unmount: Option<bool>,
+
+    /// Access mode of automatically created mount point directories.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.mount.html#DirectoryMode=
+    pub directory_mode: Option<u32>,
+
+    /// Time to wait for the mount command to finish.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.mount.html#TimeoutSec=
+    pub timeout_sec: Option<String>,
+
+    pub exec: Option<Exec>,
+
+    pub resource_control: Option<ResourceControl>,
+
+    pub kill: Option<Kill>,
+}
+
+impl fmt::Display for Mount {
+    fn fmt(&self, buf: &mut fmt::Formatter<'_>) -> fmt::Result {
+        buf.write_str("[Mount]\n")?;
+        writeln!(buf, "What={}", self.what)?;
+        writeln!(buf, "Where={}", self.where_.display())?;
+        write_option!(buf, self.fs_type, "Type");
+        if let Some(ref options) = self.options {
+            writeln!(buf, "Options={}", options.join(","))?;
+        }
+        write_bool!(buf, self.sloppy_options, "SloppyOptions");
+        write_bool!(buf, self.lazy_unmount, "LazyUnmount");
+        write_bool!(buf, self.read_write_only, "ReadWriteOnly");
+        write_bool!(buf, self.force_unmount, "ForceUnmount");
+        write_mode!(buf, self.directory_mode, "DirectoryMode");
+        write_option!(buf, self.timeout_sec, "TimeoutSec");
+
+        if let Some(ref kill) = self.kill {
+            kill.fmt(buf)?;
+        }
+        if let Some(ref resource_control) = self.resource_control {
+            resource_control.fmt(buf)?;
+        }
+        if let Some(ref exec) = self.exec {
+            exec.fmt(buf)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Mount {
+    /// Creates a `Mount` section mounting `what` on `where_`.
+    pub fn new(what: impl Into<String>, where_: impl Into<PathBuf>) -> Self {
+        Self {
+            what: what.into(),
+            where_: where_.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Validate the mount configuration according to systemd specifications
+    pub fn validate(&self) -> Result<(), String> {
+        if self.what.is_empty() {
+            return Err("Mount What= cannot be empty".to_string());
+        }
+        if !self.where_.is_absolute() {
+            return Err(format!(
+                "Mount Where= '{}' must be an absolute path",
+                self.where_.display()
+            ));
+        }
+        if let Some(ref options) = self.options {
+            for option in options {
+                if option.is_empty() || option.contains(',') {
+                    return Err(format!(
+                        "Invalid mount option '{}': options must be non-empty and listed separately",
+                        option
+                    ));
+                }
+            }
+        }
+        if let Some(mode) = self.directory_mode
+            && mode > 0o7777
+        {
+            return Err(format!("DirectoryMode {:o} is not a valid file mode", mode));
+        }
+
+        if let Some(ref kill) = self.kill {
+            kill.validate()?;
+        }
+        if let Some(ref resource_control) = self.resource_control {
+            resource_control.validate()?;
+        }
+        if let Some(ref exec) = self.exec {
+            exec.validate()?;
+        }
+
+        Ok(())
+    }
+
+    // Builder pattern setters
+    pub fn what(mut self, value: impl Into<String>) -> Self {
+        self.what = value.into();
+        self
+    }
+
+    pub fn where_(mut self, value: impl Into<PathBuf>) -> Self {
+        self.where_ = value.into();
+        self
+    }
+
+    pub fn fs_type(mut self, value: impl Into<String>) -> Self {
+        self.fs_type = Some(value.into());
+        self
+    }
+
+    pub fn options(mut self, value: Vec<impl Into<String>>) -> Self {
+        self.options = Some(value.into_iter().map(|s| s.into()).collect());
+        self
+    }
+
+    pub fn sloppy_options(mut self, value: bool) -> Self {
+        self.sloppy_options = Some(value);
+        self
+    }
+
+    pub fn lazy_unmount(mut self, value: bool) -> Self {
+        self.lazy_unmount = Some(value);
+        self
+    }
+
+    pub fn read_write_only(mut self, value: bool) -> Self {
+        self.read_write_only = Some(value);
+        self
+    }
+
+    pub fn force_unmount(mut self, value: bool) -> Self {
+        self.force_unmount = Some(value);
+        self
+    }
+
+    pub fn directory_mode(mut self, value: u32) -> Self {
+        self.directory_mode = Some(value);
+        self
+    }
+
+    pub fn timeout_sec(mut self, value: impl Into<String>) -> Self {
+        self.timeout_sec = Some(value.into());
+        self
+    }
+
+    pub fn exec(mut self, value: Exec) -> Self {
+        self.exec = Some(value);
+        self
+    }
+
+    pub fn resource_control(mut self, value: ResourceControl) -> Self {
+        self.resource_control = Some(value);
+        self
+    }
+
+    pub fn kill(mut self, value: Kill) -> Self {
+        self.kill = Some(value);
+        self
+    }
+}
+
+/// Configuration for a systemd mount unit.
+/// The unit name is derived from the mount point, e.g. `/var/lib/data` becomes `var-lib-data.mount`.
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.mount.html
+#[derive(Debug, Clone)]
+pub struct MountConfig {
+    pub unit: Unit,
+    pub mount: Mount,
+    pub install: Install,
+}
+
+impl MountConfig {
+    pub fn new(mount: Mount) -> Self {
+        Self {
+            unit: Unit::default(),
+            mount,
+            install: Install::default(),
+        }
+    }
+
+    pub fn unit(mut self, unit: Unit) -> Self {
+        self.unit = unit;
+        self
+    }
+
+    pub fn mount(mut self, mount: Mount) -> Self {
+        self.mount = mount;
+        self
+    }
+
+    pub fn install(mut self, install: Install) -> Self {
+        self.install = install;
+        self
+    }
+
+    pub fn validate(&self) -> crate::Result<()> {
+        self.unit.validate().map_err(Error::ValidationError)?;
+        self.mount.validate().map_err(Error::ValidationError)?;
+        self.install.validate().map_err(Error::ValidationError)?;
+        Ok(())
+    }
+
+    /// Returns warnings about settings that pass validation but are discouraged by systemd.
+    pub fn warnings(&self) -> Vec<String> {
+        self.mount
+            .kill
+            .as_ref()
+            .map(Kill::warnings)
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn unit_name(&self) -> String {
+        format!("{}.mount", escape_path(&self.mount.where_))
+    }
+}
+
+impl fmt::Display for MountConfig {
+    fn fmt(&self, buf: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.unit.fmt(buf)?;
+        self.mount.fmt(buf)?;
+        self.install.fmt(buf)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_mount_unit() {
+        let config = MountConfig::new(
+            Mount::new("/dev/disk/by-label/data", "/var/lib/data")
+                .fs_type("ext4")
+                .options(vec!["noatime", "nodev"])
+                .directory_mode(0o755)
+                .timeout_sec("30s"),
+        )
+        .install(Install::default().wanted_by(vec!["local-fs.target"]));
+
+        assert!(config.validate().is_ok());
+        assert_eq!(config.unit_name(), "var-lib-data.mount");
+        assert_eq!(
+            config.to_string(),
+            "[Unit]\n[Mount]\nWhat=/dev/disk/by-label/data\nWhere=/var/lib/data\nType=ext4\n\
+             Options=noatime,nodev\nDirectoryMode=0755\nTimeoutSec=30s\n\
+             [Install]\nWantedBy=local-fs.target\n"
+        );
+    }
+}
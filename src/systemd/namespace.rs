@@ -0,0 +1,865 @@
+use super::resource_control::ByteSize;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Partition designators of discoverable disk images.
+/// https://uapi-group.org/specifications/specs/discoverable_partitions_specification/
+const PARTITIONS: &[&str] = &[
+    "root", "usr", "home", "srv", "esp", "xbootldr", "tmp", "var",
+];
+
+/// Partition designators image policies may refer to, in addition to those of [`PARTITIONS`].
+const POLICY_PARTITIONS: &[&str] = &[
+    "swap",
+    "root-verity",
+    "root-verity-sig",
+    "usr-verity",
+    "usr-verity-sig",
+];
+
+/// Flags of a partition in an image policy.
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.image-policy.html
+const POLICY_FLAGS: &[&str] = &[
+    "verity",
+    "signed",
+    "encrypted",
+    "unprotected",
+    "unused",
+    "absent",
+    "open",
+    "ignore",
+    "read-only-on",
+    "read-only-off",
+    "growfs-on",
+    "growfs-off",
+];
+
+/// A path of ReadWritePaths=, ReadOnlyPaths=, InaccessiblePaths=, ExecPaths= or NoExecPaths=.
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#ReadWritePaths=
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamespacePath {
+    pub path: PathBuf,
+    /// Ignore the path if it does not exist, rendered with the `-` prefix.
+    pub ignore_missing: bool,
+    /// Resolve the path relative to RootDirectory= or RootImage=, rendered with the `+` prefix.
+    pub relative_to_root: bool,
+}
+
+impl fmt::Display for NamespacePath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ignore_missing {
+            f.write_str("-")?;
+        }
+        if self.relative_to_root {
+            f.write_str("+")?;
+        }
+        write!(f, "{}", self.path.display())
+    }
+}
+
+impl<T: Into<PathBuf>> From<T> for NamespacePath {
+    fn from(path: T) -> Self {
+        Self::new(path)
+    }
+}
+
+impl NamespacePath {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            ignore_missing: false,
+            relative_to_root: false,
+        }
+    }
+
+    pub fn ignore_missing(mut self) -> Self {
+        self.ignore_missing = true;
+        self
+    }
+
+    pub fn relative_to_root(mut self) -> Self {
+        self.relative_to_root = true;
+        self
+    }
+
+    pub fn validate(&self, key: &str) -> Result<(), String> {
+        validate_absolute(&self.path, key)
+    }
+}
+
+/// A typed mount option.
+/// https://www.man7.org/linux/man-pages/man8/mount.8.html#FILESYSTEM-INDEPENDENT_MOUNT_OPTIONS
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MountOption {
+    ReadOnly,
+    ReadWrite,
+    NoSuid,
+    NoDev,
+    NoExec,
+    /// Access mode of the file system root, e.g. of a tmpfs.
+    Mode(u32),
+    /// Size limit of a tmpfs.
+    Size(ByteSize),
+    /// Maximum number of inodes of a tmpfs.
+    NrInodes(u64),
+    Uid(u32),
+    Gid(u32),
+    /// Any other option, passed through as is.
+    Other(String),
+}
+
+impl fmt::Display for MountOption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MountOption::ReadOnly => write!(f, "ro"),
+            MountOption::ReadWrite => write!(f, "rw"),
+            MountOption::NoSuid => write!(f, "nosuid"),
+            MountOption::NoDev => write!(f, "nodev"),
+            MountOption::NoExec => write!(f, "noexec"),
+            MountOption::Mode(mode) => write!(f, "mode={:04o}", mode),
+            MountOption::Size(size) => write!(f, "size={}", size),
+            MountOption::NrInodes(count) => write!(f, "nr_inodes={}", count),
+            MountOption::Uid(uid) => write!(f, "uid={}", uid),
+            MountOption::Gid(gid) => write!(f, "gid={}", gid),
+            MountOption::Other(option) => write!(f, "{}", option),
+        }
+    }
+}
+
+impl MountOption {
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            MountOption::Mode(mode) if *mode > 0o7777 => Err(format!(
+                "Mount option mode {:o} is not a valid file mode",
+                mode
+            )),
+            MountOption::Other(option)
+                if option.is_empty()
+                    || option.contains([',', ':'])
+                    || option.chars().any(char::is_whitespace) =>
+            {
+                Err(format!(
+                    "Invalid mount option '{}': options must be non-empty and listed separately",
+                    option
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+fn join_options(options: &[MountOption]) -> String {
+    options
+        .iter()
+        .map(|option| option.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn validate_options(options: &[MountOption], key: &str) -> Result<(), String> {
+    for option in options {
+        option.validate().map_err(|e| format!("{}: {}", key, e))?;
+    }
+    Ok(())
+}
+
+/// A bind mount of BindPaths= or BindReadOnlyPaths=, rendered as `[-]source[:destination[:options]]`.
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#BindPaths=
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BindPath {
+    pub source: PathBuf,
+    /// Mount point in the unit's namespace, the source path when not set.
+    pub destination: Option<PathBuf>,
+    /// Ignore the bind mount if the source does not exist, rendered with the `-` prefix.
+    pub ignore_missing: bool,
+    /// Whether submounts of the source are bind mounted as well (`rbind`, the default) or not (`norbind`).
+    pub recursive: Option<bool>,
+}
+
+impl fmt::Display for BindPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ignore_missing {
+            f.write_str("-")?;
+        }
+        write!(f, "{}", self.source.display())?;
+        // Options can only be given together with a destination
+        let destination = self
+            .destination
+            .as_ref()
+            .or(self.recursive.map(|_| &self.source));
+        if let Some(destination) = destination {
+            write!(f, ":{}", destination.display())?;
+        }
+        match self.recursive {
+            Some(true) => write!(f, ":rbind"),
+            Some(false) => write!(f, ":norbind"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl BindPath {
+    pub fn new(source: impl Into<PathBuf>) -> Self {
+        Self {
+            source: source.into(),
+            destination: None,
+            ignore_missing: false,
+            recursive: None,
+        }
+    }
+
+    pub fn destination(mut self, value: impl Into<PathBuf>) -> Self {
+        self.destination = Some(value.into());
+        self
+    }
+
+    pub fn ignore_missing(mut self) -> Self {
+        self.ignore_missing = true;
+        self
+    }
+
+    pub fn recursive(mut self, value: bool) -> Self {
+        self.recursive = Some(value);
+        self
+    }
+
+    pub fn validate(&self, key: &str) -> Result<(), String> {
+        validate_mount_path(&self.source, key)?;
+        if let Some(ref destination) = self.destination {
+            validate_mount_path(destination, key)?;
+        }
+        Ok(())
+    }
+}
+
+/// A tmpfs mounted over a path, rendered as `path[:options]`.
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#TemporaryFileSystem=
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemporaryFileSystem {
+    pub path: PathBuf,
+    pub options: Vec<MountOption>,
+}
+
+impl fmt::Display for TemporaryFileSystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.path.display())?;
+        if !self.options.is_empty() {
+            write!(f, ":{}", join_options(&self.options))?;
+        }
+        Ok(())
+    }
+}
+
+impl TemporaryFileSystem {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            options: Vec::new(),
+        }
+    }
+
+    pub fn option(mut self, value: MountOption) -> Self {
+        self.options.push(value);
+        self
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        validate_mount_path(&self.path, "TemporaryFileSystem")?;
+        validate_options(&self.options, "TemporaryFileSystem")
+    }
+}
+
+/// Mount options for one partition of a disk image, rendered as `partition:options`.
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#RootImageOptions=
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartitionOptions {
+    /// Partition designator, e.g. `root` or `usr`.
+    pub partition: String,
+    pub options: Vec<MountOption>,
+}
+
+impl fmt::Display for PartitionOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.partition, join_options(&self.options))
+    }
+}
+
+impl PartitionOptions {
+    pub fn new(partition: impl Into<String>, options: Vec<MountOption>) -> Self {
+        Self {
+            partition: partition.into(),
+            options,
+        }
+    }
+
+    pub fn validate(&self, key: &str) -> Result<(), String> {
+        if !PARTITIONS.contains(&self.partition.as_str()) {
+            return Err(format!(
+                "Unknown partition '{}' in {}, expected one of: {}",
+                self.partition,
+                key,
+                PARTITIONS.join(", ")
+            ));
+        }
+        validate_options(&self.options, key)
+    }
+}
+
+/// A disk image mounted into the unit's namespace, rendered as
+/// `[-]source:destination[:partition:options...]`.
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#MountImages=
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MountImage {
+    pub source: PathBuf,
+    pub destination: PathBuf,
+    /// Ignore the image if it does not exist, rendered with the `-` prefix.
+    pub ignore_missing: bool,
+    pub options: Vec<PartitionOptions>,
+}
+
+impl fmt::Display for MountImage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ignore_missing {
+            f.write_str("-")?;
+        }
+        write!(
+            f,
+            "{}:{}",
+            self.source.display(),
+            self.destination.display()
+        )?;
+        for options in &self.options {
+            write!(f, ":{}", options)?;
+        }
+        Ok(())
+    }
+}
+
+impl MountImage {
+    pub fn new(source: impl Into<PathBuf>, destination: impl Into<PathBuf>) -> Self {
+        Self {
+            source: source.into(),
+            destination: destination.into(),
+            ignore_missing: false,
+            options: Vec::new(),
+        }
+    }
+
+    pub fn ignore_missing(mut self) -> Self {
+        self.ignore_missing = true;
+        self
+    }
+
+    pub fn options(mut self, value: PartitionOptions) -> Self {
+        self.options.push(value);
+        self
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        validate_mount_path(&self.source, "MountImages")?;
+        validate_mount_path(&self.destination, "MountImages")?;
+        for options in &self.options {
+            options.validate("MountImages")?;
+        }
+        Ok(())
+    }
+}
+
+/// An extension image overlaid on /usr and /opt, rendered as `[-]source[:partition:options...]`.
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#ExtensionImages=
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtensionImage {
+    pub source: PathBuf,
+    /// Ignore the image if it does not exist, rendered with the `-` prefix.
+    pub ignore_missing: bool,
+    pub options: Vec<PartitionOptions>,
+}
+
+impl fmt::Display for ExtensionImage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ignore_missing {
+            f.write_str("-")?;
+        }
+        write!(f, "{}", self.source.display())?;
+        for options in &self.options {
+            write!(f, ":{}", options)?;
+        }
+        Ok(())
+    }
+}
+
+impl ExtensionImage {
+    pub fn new(source: impl Into<PathBuf>) -> Self {
+        Self {
+            source: source.into(),
+            ignore_missing: false,
+            options: Vec::new(),
+        }
+    }
+
+    pub fn ignore_missing(mut self) -> Self {
+        self.ignore_missing = true;
+        self
+    }
+
+    pub fn options(mut self, value: PartitionOptions) -> Self {
+        self.options.push(value);
+        self
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        validate_mount_path(&self.source, "ExtensionImages")?;
+        for options in &self.options {
+            options.validate("ExtensionImages")?;
+        }
+        Ok(())
+    }
+}
+
+/// Mount propagation of the unit's mount namespace.
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#MountFlags=
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MountFlags {
+    Shared,
+    Slave,
+    Private,
+}
+
+impl fmt::Display for MountFlags {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MountFlags::Shared => write!(f, "shared"),
+            MountFlags::Slave => write!(f, "slave"),
+            MountFlags::Private => write!(f, "private"),
+        }
+    }
+}
+
+/// Root hash of the dm-verity protected root image.
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#RootHash=
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RootHash {
+    /// The hash in hexadecimal.
+    Hex(String),
+    /// A file containing the hash in hexadecimal.
+    Path(PathBuf),
+}
+
+impl fmt::Display for RootHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RootHash::Hex(hash) => write!(f, "{}", hash),
+            RootHash::Path(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+impl RootHash {
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            RootHash::Hex(hash) => {
+                if hash.is_empty()
+                    || !hash.len().is_multiple_of(2)
+                    || !hash.chars().all(|c| c.is_ascii_hexdigit())
+                {
+                    return Err(format!("RootHash '{}' must be a hexadecimal string", hash));
+                }
+                Ok(())
+            }
+            RootHash::Path(path) => validate_absolute(path, "RootHash"),
+        }
+    }
+}
+
+/// PKCS#7 signature of the root hash.
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#RootHashSignature=
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RootHashSignature {
+    /// A DER encoded signature file.
+    Path(PathBuf),
+    /// The DER encoded signature in base64, rendered with the `base64:` prefix.
+    Base64(String),
+}
+
+impl fmt::Display for RootHashSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RootHashSignature::Path(path) => write!(f, "{}", path.display()),
+            RootHashSignature::Base64(data) => write!(f, "base64:{}", data),
+        }
+    }
+}
+
+impl RootHashSignature {
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            RootHashSignature::Path(path) => validate_absolute(path, "RootHashSignature"),
+            RootHashSignature::Base64(data) => {
+                if data.is_empty()
+                    || !data
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || "+/=".contains(c))
+                {
+                    return Err("RootHashSignature data must be base64 encoded".to_string());
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// A disk image mounted as the root directory of the unit, together with its verity data.
+/// Each field is rendered to its own setting: RootImage=, RootImageOptions=, RootHash=,
+/// RootHashSignature=, RootVerity= and RootImagePolicy=.
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#RootImage=
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RootImage {
+    pub image: PathBuf,
+    /// Mount options per partition.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#RootImageOptions=
+    pub options: Vec<PartitionOptions>,
+    /// Root hash of the verity protected partition.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#RootHash=
+    pub hash: Option<RootHash>,
+    /// Signature of the root hash.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#RootHashSignature=
+    pub hash_signature: Option<RootHashSignature>,
+    /// Verity data, if it is not part of the image.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#RootVerity=
+    pub verity: Option<PathBuf>,
+    /// Image policy the image must satisfy, e.g. `root=verity+signed:usr=absent`.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#RootImagePolicy=
+    pub policy: Option<String>,
+}
+
+impl fmt::Display for RootImage {
+    fn fmt(&self, buf: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(buf, "RootImage={}", self.image.display())?;
+        if !self.options.is_empty() {
+            let options: Vec<String> = self.options.iter().map(|o| o.to_string()).collect();
+            writeln!(buf, "RootImageOptions={}", options.join(" "))?;
+        }
+        write_option!(buf, self.hash, "RootHash");
+        write_option!(buf, self.hash_signature, "RootHashSignature");
+        write_path!(buf, self.verity, "RootVerity");
+        write_option!(buf, self.policy, "RootImagePolicy");
+        Ok(())
+    }
+}
+
+impl RootImage {
+    pub fn new(image: impl Into<PathBuf>) -> Self {
+        Self {
+            image: image.into(),
+            options: Vec::new(),
+            hash: None,
+            hash_signature: None,
+            verity: None,
+            policy: None,
+        }
+    }
+
+    pub fn options(mut self, value: PartitionOptions) -> Self {
+        self.options.push(value);
+        self
+    }
+
+    pub fn hash(mut self, value: RootHash) -> Self {
+        self.hash = Some(value);
+        self
+    }
+
+    pub fn hash_signature(mut self, value: RootHashSignature) -> Self {
+        self.hash_signature = Some(value);
+        self
+    }
+
+    pub fn verity(mut self, value: impl Into<PathBuf>) -> Self {
+        self.verity = Some(value.into());
+        self
+    }
+
+    pub fn policy(mut self, value: impl Into<String>) -> Self {
+        self.policy = Some(value.into());
+        self
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        validate_absolute(&self.image, "RootImage")?;
+        for options in &self.options {
+            options.validate("RootImageOptions")?;
+        }
+        if let Some(ref hash) = self.hash {
+            hash.validate()?;
+        }
+        if let Some(ref signature) = self.hash_signature {
+            signature.validate()?;
+        }
+        if let Some(ref verity) = self.verity {
+            validate_absolute(verity, "RootVerity")?;
+        }
+        if let Some(ref policy) = self.policy {
+            validate_image_policy(policy, "RootImagePolicy")?;
+        }
+        Ok(())
+    }
+}
+
+/// Checks an image policy of RootImagePolicy=, MountImagePolicy= or ExtensionImagePolicy=:
+/// `*`, `-`, `~` or `:` separated `partition=flag+flag` rules.
+pub(crate) fn validate_image_policy(policy: &str, key: &str) -> Result<(), String> {
+    if matches!(policy, "*" | "-" | "~") {
+        return Ok(());
+    }
+    if policy.is_empty() {
+        return Err(format!("{} cannot be empty", key));
+    }
+    let mut seen = Vec::new();
+    for rule in policy.split(':') {
+        let Some((partition, flags)) = rule.split_once('=') else {
+            return Err(format!(
+                "Invalid rule '{}' in {}, expected partition=flags",
+                rule, key
+            ));
+        };
+        // An empty designator sets the default for unlisted partitions
+        if !partition.is_empty()
+            && !PARTITIONS.contains(&partition)
+            && !POLICY_PARTITIONS.contains(&partition)
+        {
+            return Err(format!("Unknown partition '{}' in {}", partition, key));
+        }
+        if seen.contains(&partition) {
+            return Err(format!(
+                "Partition '{}' is listed twice in {}",
+                partition, key
+            ));
+        }
+        seen.push(partition);
+        for flag in flags.split('+').filter(|f| !f.is_empty()) {
+            if !POLICY_FLAGS.contains(&flag) {
+                return Err(format!(
+                    "Unknown flag '{}' for partition '{}' in {}",
+                    flag, partition, key
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn validate_absolute(path: &Path, key: &str) -> Result<(), String> {
+    if !path.is_absolute() {
+        return Err(format!(
+            "{} path '{}' must be an absolute path",
+            key,
+            path.display()
+        ));
+    }
+    Ok(())
+}
+
+/// Colons separate the fields of mount settings, so they cannot be part of the paths.
+fn validate_mount_path(path: &Path, key: &str) -> Result<(), String> {
+    validate_absolute(path, key)?;
+    if path.to_string_lossy().contains(':') {
+        return Err(format!(
+            "{} path '{}' cannot contain ':'",
+            key,
+            path.display()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systemd::exec::Exec;
+
+    /// Returns the rendered lines of `exec` starting with one of the image keys.
+    fn image_lines(exec: &Exec) -> Vec<String> {
+        exec.to_string()
+            .lines()
+            .filter(|line| line.starts_with("Root") || line.starts_with("MountImagePolicy="))
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn root_image_settings_render_to_their_own_keys() {
+        let image = RootImage::new("/var/lib/machines/app.raw")
+            .options(PartitionOptions::new(
+                "root",
+                vec![MountOption::ReadOnly, MountOption::NoDev],
+            ))
+            .options(PartitionOptions::new("usr", vec![MountOption::NoSuid]))
+            .hash(RootHash::Hex("0a1b2c3d".to_string()))
+            .hash_signature(RootHashSignature::Path(
+                "/var/lib/machines/app.roothash.p7s".into(),
+            ))
+            .verity("/var/lib/machines/app.verity")
+            .policy("root=verity+signed:usr=absent");
+        let exec = Exec::new()
+            .root_image(image)
+            .mount_image_policy("root=verity:usr=unused");
+
+        assert_eq!(exec.validate(), Ok(()));
+        assert_eq!(
+            image_lines(&exec),
+            [
+                "RootImage=/var/lib/machines/app.raw",
+                "RootImageOptions=root:ro,nodev usr:nosuid",
+                "RootHash=0a1b2c3d",
+                "RootHashSignature=/var/lib/machines/app.roothash.p7s",
+                "RootVerity=/var/lib/machines/app.verity",
+                "RootImagePolicy=root=verity+signed:usr=absent",
+                "MountImagePolicy=root=verity:usr=unused",
+            ]
+        );
+    }
+
+    #[test]
+    fn root_hash_from_file_and_base64_signature() {
+        let image = RootImage::new("/srv/app.raw")
+            .hash(RootHash::Path("/srv/app.roothash".into()))
+            .hash_signature(RootHashSignature::Base64("MIIB+w==".to_string()));
+        let exec = Exec::new().root_image(image);
+
+        assert_eq!(exec.validate(), Ok(()));
+        assert_eq!(
+            image_lines(&exec),
+            [
+                "RootImage=/srv/app.raw",
+                "RootHash=/srv/app.roothash",
+                "RootHashSignature=base64:MIIB+w==",
+            ]
+        );
+    }
+
+    #[test]
+    fn root_hash_signature_without_root_hash() {
+        // The root hash may come from the image's verity partition or a .roothash file
+        let exec = Exec::new().root_image(
+            RootImage::new("/srv/app.raw")
+                .hash_signature(RootHashSignature::Path("/srv/app.roothash.p7s".into())),
+        );
+
+        assert_eq!(exec.validate(), Ok(()));
+        assert_eq!(
+            image_lines(&exec),
+            [
+                "RootImage=/srv/app.raw",
+                "RootHashSignature=/srv/app.roothash.p7s",
+            ]
+        );
+    }
+
+    #[test]
+    fn every_root_image_setting_is_validated() {
+        let image = || RootImage::new("/srv/app.raw");
+        let cases = [
+            (
+                RootImage::new("srv/app.raw"),
+                "RootImage=srv/app.raw",
+                "RootImage path 'srv/app.raw' must be an absolute path",
+            ),
+            (
+                image().options(PartitionOptions::new("boot", vec![MountOption::ReadOnly])),
+                "RootImageOptions=boot:ro",
+                "Unknown partition 'boot' in RootImageOptions, expected one of: \
+                 root, usr, home, srv, esp, xbootldr, tmp, var",
+            ),
+            (
+                image().options(PartitionOptions::new(
+                    "root",
+                    vec![MountOption::Mode(0o10000)],
+                )),
+                "RootImageOptions=root:mode=10000",
+                "RootImageOptions: Mount option mode 10000 is not a valid file mode",
+            ),
+            (
+                image().options(PartitionOptions::new(
+                    "root",
+                    vec![MountOption::Other("ro,nodev".to_string())],
+                )),
+                "RootImageOptions=root:ro,nodev",
+                "RootImageOptions: Invalid mount option 'ro,nodev': \
+                 options must be non-empty and listed separately",
+            ),
+            (
+                image().hash(RootHash::Hex("0a1".to_string())),
+                "RootHash=0a1",
+                "RootHash '0a1' must be a hexadecimal string",
+            ),
+            (
+                image().hash(RootHash::Hex("xyz0".to_string())),
+                "RootHash=xyz0",
+                "RootHash 'xyz0' must be a hexadecimal string",
+            ),
+            (
+                image().hash(RootHash::Path("app.roothash".into())),
+                "RootHash=app.roothash",
+                "RootHash path 'app.roothash' must be an absolute path",
+            ),
+            (
+                image().hash_signature(RootHashSignature::Path("app.p7s".into())),
+                "RootHashSignature=app.p7s",
+                "RootHashSignature path 'app.p7s' must be an absolute path",
+            ),
+            (
+                image().hash_signature(RootHashSignature::Base64("MII B".to_string())),
+                "RootHashSignature=base64:MII B",
+                "RootHashSignature data must be base64 encoded",
+            ),
+            (
+                image().verity("app.verity"),
+                "RootVerity=app.verity",
+                "RootVerity path 'app.verity' must be an absolute path",
+            ),
+            (
+                image().policy("root=verity+trusted"),
+                "RootImagePolicy=root=verity+trusted",
+                "Unknown flag 'trusted' for partition 'root' in RootImagePolicy",
+            ),
+        ];
+
+        for (image, line, error) in cases {
+            let exec = Exec::new().root_image(image);
+            assert!(image_lines(&exec).contains(&line.to_string()), "{}", line);
+            assert_eq!(exec.validate(), Err(error.to_string()), "{}", line);
+        }
+    }
+
+    #[test]
+    fn mount_image_policy_is_validated() {
+        for policy in ["*", "-", "~", "=unused", "root=verity+signed:usr=absent"] {
+            let exec = Exec::new().mount_image_policy(policy);
+            assert_eq!(exec.validate(), Ok(()), "{}", policy);
+            assert_eq!(image_lines(&exec), [format!("MountImagePolicy={}", policy)]);
+        }
+        for (policy, error) in [
+            ("", "MountImagePolicy cannot be empty"),
+            (
+                "root",
+                "Invalid rule 'root' in MountImagePolicy, expected partition=flags",
+            ),
+            (
+                "boot=verity",
+                "Unknown partition 'boot' in MountImagePolicy",
+            ),
+            (
+                "root=verity:root=signed",
+                "Partition 'root' is listed twice in MountImagePolicy",
+            ),
+            (
+                "usr=readonly",
+                "Unknown flag 'readonly' for partition 'usr' in MountImagePolicy",
+            ),
+        ] {
+            let exec = Exec::new().mount_image_policy(policy);
+            assert_eq!(image_lines(&exec), [format!("MountImagePolicy={}", policy)]);
+            assert_eq!(exec.validate(), Err(error.to_string()));
+        }
+    }
+}
@@ -0,0 +1,384 @@
+use std::fmt;
+use std::net::IpAddr;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Address families accepted by RestrictAddressFamilies=.
+const ADDRESS_FAMILIES: &[&str] = &[
+    "AF_UNIX",
+    "AF_LOCAL",
+    "AF_INET",
+    "AF_AX25",
+    "AF_IPX",
+    "AF_APPLETALK",
+    "AF_NETROM",
+    "AF_BRIDGE",
+    "AF_ATMPVC",
+    "AF_X25",
+    "AF_INET6",
+    "AF_ROSE",
+    "AF_DECnet",
+    "AF_NETBEUI",
+    "AF_SECURITY",
+    "AF_KEY",
+    "AF_NETLINK",
+    "AF_PACKET",
+    "AF_ASH",
+    "AF_ECONET",
+    "AF_ATMSVC",
+    "AF_RDS",
+    "AF_SNA",
+    "AF_IRDA",
+    "AF_PPPOX",
+    "AF_WANPIPE",
+    "AF_LLC",
+    "AF_IB",
+    "AF_MPLS",
+    "AF_CAN",
+    "AF_TIPC",
+    "AF_BLUETOOTH",
+    "AF_IUCV",
+    "AF_RXRPC",
+    "AF_ISDN",
+    "AF_PHONET",
+    "AF_IEEE802154",
+    "AF_CAIF",
+    "AF_ALG",
+    "AF_NFC",
+    "AF_VSOCK",
+    "AF_KCM",
+    "AF_QIPCRTR",
+    "AF_SMC",
+    "AF_XDP",
+    "AF_MCTP",
+];
+
+/// BPF file system programs of IPIngressFilterPath= and IPEgressFilterPath= are pinned to.
+const BPF_FS: &str = "/sys/fs/bpf";
+
+/// Longest alternative name of a network interface.
+const MAX_INTERFACE_NAME: usize = 127;
+
+/// Socket address families processes may use.
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#RestrictAddressFamilies=
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AddressFamilies {
+    /// Deny all address families.
+    None,
+    /// Allow only the listed families, e.g. `AF_UNIX` or `AF_INET6`.
+    Allow(Vec<String>),
+    /// Deny only the listed families.
+    Deny(Vec<String>),
+}
+
+impl fmt::Display for AddressFamilies {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AddressFamilies::None => write!(f, "none"),
+            AddressFamilies::Allow(families) => write!(f, "{}", families.join(" ")),
+            AddressFamilies::Deny(families) => write!(f, "~{}", families.join(" ")),
+        }
+    }
+}
+
+impl AddressFamilies {
+    pub fn validate(&self) -> Result<(), String> {
+        let families = match self {
+            AddressFamilies::None => return Ok(()),
+            AddressFamilies::Allow(families) | AddressFamilies::Deny(families) => families,
+        };
+        if families.is_empty() {
+            return Err(
+                "RestrictAddressFamilies must list at least one address family".to_string(),
+            );
+        }
+        for family in families {
+            if !ADDRESS_FAMILIES.contains(&family.as_str()) {
+                return Err(format!(
+                    "Unknown address family '{}' in RestrictAddressFamilies",
+                    family
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An entry of IPAddressAllow= or IPAddressDeny=.
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.resource-control.html#IPAddressAllow=ADDRESS%5B/PREFIXLENGTH%5D%E2%80%A6
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IpAddressRule {
+    /// All addresses, 0.0.0.0/0 and ::/0.
+    Any,
+    /// Loopback addresses, 127.0.0.0/8 and ::1/128.
+    Localhost,
+    /// Link-local addresses, 169.254.0.0/16 and fe80::/64.
+    LinkLocal,
+    /// Multicast addresses, 224.0.0.0/4 and ff00::/8.
+    Multicast,
+    /// An address with an optional prefix length, e.g. `10.0.0.0/8`.
+    Address(IpAddr, Option<u8>),
+}
+
+impl fmt::Display for IpAddressRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IpAddressRule::Any => write!(f, "any"),
+            IpAddressRule::Localhost => write!(f, "localhost"),
+            IpAddressRule::LinkLocal => write!(f, "link-local"),
+            IpAddressRule::Multicast => write!(f, "multicast"),
+            IpAddressRule::Address(addr, Some(prefix)) => write!(f, "{}/{}", addr, prefix),
+            IpAddressRule::Address(addr, None) => write!(f, "{}", addr),
+        }
+    }
+}
+
+impl FromStr for IpAddressRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rule = match s {
+            "any" => IpAddressRule::Any,
+            "localhost" => IpAddressRule::Localhost,
+            "link-local" => IpAddressRule::LinkLocal,
+            "multicast" => IpAddressRule::Multicast,
+            _ => {
+                let (addr, prefix) = match s.split_once('/') {
+                    Some((addr, prefix)) => (
+                        addr,
+                        Some(
+                            prefix
+                                .parse::<u8>()
+                                .map_err(|_| format!("Invalid prefix length in '{}'", s))?,
+                        ),
+                    ),
+                    None => (s, None),
+                };
+                let addr = addr
+                    .parse::<IpAddr>()
+                    .map_err(|_| format!("Invalid IP address '{}'", s))?;
+                IpAddressRule::Address(addr, prefix)
+            }
+        };
+        rule.validate()?;
+        Ok(rule)
+    }
+}
+
+impl IpAddressRule {
+    pub fn validate(&self) -> Result<(), String> {
+        if let IpAddressRule::Address(addr, Some(prefix)) = self {
+            let max = if addr.is_ipv4() { 32 } else { 128 };
+            if *prefix > max {
+                return Err(format!(
+                    "Prefix length {} of '{}' must not exceed {}",
+                    prefix, addr, max
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Address family of a socket bind rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SocketBindFamily {
+    Ipv4,
+    Ipv6,
+}
+
+impl fmt::Display for SocketBindFamily {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SocketBindFamily::Ipv4 => write!(f, "ipv4"),
+            SocketBindFamily::Ipv6 => write!(f, "ipv6"),
+        }
+    }
+}
+
+/// Transport protocol of a socket bind rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SocketBindProtocol {
+    Tcp,
+    Udp,
+}
+
+impl fmt::Display for SocketBindProtocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SocketBindProtocol::Tcp => write!(f, "tcp"),
+            SocketBindProtocol::Udp => write!(f, "udp"),
+        }
+    }
+}
+
+/// An entry of SocketBindAllow= or SocketBindDeny=, rendered as `[family:][protocol:][ports]`.
+/// Unset parts match everything and are omitted, a rule with no part set is rendered as `any`.
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.resource-control.html#SocketBindAllow=bind-rule
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SocketBindRule {
+    pub family: Option<SocketBindFamily>,
+    pub protocol: Option<SocketBindProtocol>,
+    /// Inclusive port range, a single port when both ends are equal.
+    pub ports: Option<(u16, u16)>,
+}
+
+impl fmt::Display for SocketBindRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(family) = self.family {
+            parts.push(family.to_string());
+        }
+        if let Some(protocol) = self.protocol {
+            parts.push(protocol.to_string());
+        }
+        match self.ports {
+            Some((first, last)) if first == last => parts.push(first.to_string()),
+            Some((first, last)) => parts.push(format!("{}-{}", first, last)),
+            None => {}
+        }
+        // systemd only accepts `any` as the whole rule, not as a port part
+        if parts.is_empty() {
+            return write!(f, "any");
+        }
+        write!(f, "{}", parts.join(":"))
+    }
+}
+
+impl SocketBindRule {
+    /// A rule matching every bind.
+    pub fn any() -> Self {
+        Self::default()
+    }
+
+    pub fn family(mut self, value: SocketBindFamily) -> Self {
+        self.family = Some(value);
+        self
+    }
+
+    pub fn protocol(mut self, value: SocketBindProtocol) -> Self {
+        self.protocol = Some(value);
+        self
+    }
+
+    pub fn port(mut self, value: u16) -> Self {
+        self.ports = Some((value, value));
+        self
+    }
+
+    pub fn ports(mut self, first: u16, last: u16) -> Self {
+        self.ports = Some((first, last));
+        self
+    }
+
+    pub fn validate(&self, key: &str) -> Result<(), String> {
+        if let Some((first, last)) = self.ports
+            && first > last
+        {
+            return Err(format!(
+                "Invalid port range '{}-{}' in {}",
+                first, last, key
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Network interfaces sockets may use.
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.resource-control.html#RestrictNetworkInterfaces=
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RestrictNetworkInterfaces {
+    /// Allow only the listed interfaces.
+    Allow(Vec<String>),
+    /// Deny only the listed interfaces.
+    Deny(Vec<String>),
+}
+
+impl fmt::Display for RestrictNetworkInterfaces {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RestrictNetworkInterfaces::Allow(interfaces) => write!(f, "{}", interfaces.join(" ")),
+            RestrictNetworkInterfaces::Deny(interfaces) => write!(f, "~{}", interfaces.join(" ")),
+        }
+    }
+}
+
+impl RestrictNetworkInterfaces {
+    pub fn validate(&self) -> Result<(), String> {
+        let (RestrictNetworkInterfaces::Allow(interfaces)
+        | RestrictNetworkInterfaces::Deny(interfaces)) = self;
+        if interfaces.is_empty() {
+            return Err("RestrictNetworkInterfaces must list at least one interface".to_string());
+        }
+        for interface in interfaces {
+            if interface.is_empty()
+                || interface.len() > MAX_INTERFACE_NAME
+                || interface == "."
+                || interface == ".."
+                || interface.contains('/')
+                || interface
+                    .chars()
+                    .any(|c| c.is_whitespace() || c.is_control())
+            {
+                return Err(format!(
+                    "Invalid interface name '{}' in RestrictNetworkInterfaces",
+                    interface
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Checks the path of a pinned BPF program of IPIngressFilterPath= or IPEgressFilterPath=.
+pub(crate) fn validate_bpf_path(path: &Path, key: &str) -> Result<(), String> {
+    if !path.starts_with(BPF_FS) || path == Path::new(BPF_FS) {
+        return Err(format!(
+            "{} '{}' must be a program pinned below {}",
+            key,
+            path.display(),
+            BPF_FS
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn socket_bind_rule_omits_unset_parts() {
+        let cases = [
+            (SocketBindRule::any(), "any"),
+            (SocketBindRule::any().family(SocketBindFamily::Ipv4), "ipv4"),
+            (
+                SocketBindRule::any()
+                    .family(SocketBindFamily::Ipv4)
+                    .protocol(SocketBindProtocol::Tcp),
+                "ipv4:tcp",
+            ),
+            (
+                SocketBindRule::any().protocol(SocketBindProtocol::Udp),
+                "udp",
+            ),
+            (
+                SocketBindRule::any()
+                    .protocol(SocketBindProtocol::Tcp)
+                    .port(443),
+                "tcp:443",
+            ),
+            (
+                SocketBindRule::any()
+                    .family(SocketBindFamily::Ipv6)
+                    .protocol(SocketBindProtocol::Udp)
+                    .ports(5000, 5010),
+                "ipv6:udp:5000-5010",
+            ),
+            (SocketBindRule::any().port(80), "80"),
+        ];
+        for (rule, expected) in cases {
+            assert_eq!(rule.to_string(), expected);
+        }
+    }
+}
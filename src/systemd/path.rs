@@ -0,0 +1,262 @@
+use super::install::Install;
+use super::unit::Unit;
+use crate::error::Error;
+use std::fmt;
+use std::path::PathBuf;
+
+/// Path section options
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.path.html#Options
+#[derive(Debug, Clone, Default)]
+pub struct Path {
+    /// Activate when the path exists.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.path.html#PathExists=
+    pub path_exists: Option<Vec<PathBuf>>,
+
+    /// Activate when a file matching the glob exists.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.path.html#PathExistsGlob=
+    pub path_exists_glob: Option<Vec<String>>,
+
+    /// Activate when the file is closed after being written to.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.path.html#PathChanged=
+    pub path_changed: Option<Vec<PathBuf>>,
+
+    /// Activate on every write to the file.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.path.html#PathModified=
+    pub path_modified: Option<Vec<PathBuf>>,
+
+    /// Activate when the directory contains at least one file.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.path.html#DirectoryNotEmpty=
+    pub directory_not_empty: Option<Vec<PathBuf>>,
+
+    /// Unit to activate when any of the paths changes.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.path.html#Unit=
+    pub unit: Option<String>,
+
+    /// Create the watched directories before watching.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.path.html#MakeDirectory=
+    pub make_directory: Option<bool>,
+
+    /// Access mode of the directories created with MakeDirectory=.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.path.html#DirectoryMode=
+    pub directory_mode: Option<u32>,
+
+    /// Interval used by the activation rate limit.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.path.html#TriggerLimitIntervalSec=
+    pub trigger_limit_interval_sec: Option<String>,
+
+    /// Number of activations allowed within the rate limit interval.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.path.html#TriggerLimitIntervalSec=
+    pub trigger_limit_burst: Option<u32>,
+}
+
+impl fmt::Display for Path {
+    fn fmt(&self, buf: &mut fmt::Formatter<'_>) -> fmt::Result {
+        buf.write_str("[Path]\n")?;
+        write_path_multi!(buf, self.path_exists, "PathExists");
+        write_vec_multi!(buf, self.path_exists_glob, "PathExistsGlob");
+        write_path_multi!(buf, self.path_changed, "PathChanged");
+        write_path_multi!(buf, self.path_modified, "PathModified");
+        write_path_multi!(buf, self.directory_not_empty, "DirectoryNotEmpty");
+        write_option!(buf, self.unit, "Unit");
+        write_bool!(buf, self.make_directory, "MakeDirectory");
+        write_mode!(buf, self.directory_mode, "DirectoryMode");
+        write_option!(
+            buf,
+            self.trigger_limit_interval_sec,
+            "TriggerLimitIntervalSec"
+        );
+        write_option!(buf, self.trigger_limit_burst, "TriggerLimitBurst");
+        Ok(())
+    }
+}
+
+impl Path {
+    /// Creates a new empty `Path` section.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Validate the path configuration according to systemd specifications
+    pub fn validate(&self) -> Result<(), String> {
+        let watched = [
+            &self.path_exists,
+            &self.path_changed,
+            &self.path_modified,
+            &self.directory_not_empty,
+        ];
+        let has_watch = watched
+            .iter()
+            .any(|paths| paths.iter().flatten().count() > 0)
+            || self
+                .path_exists_glob
+                .as_ref()
+                .is_some_and(|g| !g.is_empty());
+        if !has_watch {
+            return Err("Path unit must watch at least one path".to_string());
+        }
+
+        for path in watched.into_iter().flatten().flatten() {
+            if !path.is_absolute() {
+                return Err(format!(
+                    "Watched path '{}' must be absolute",
+                    path.display()
+                ));
+            }
+        }
+        for glob in self.path_exists_glob.iter().flatten() {
+            if !glob.starts_with('/') {
+                return Err(format!("PathExistsGlob '{}' must be absolute", glob));
+            }
+        }
+
+        if let Some(mode) = self.directory_mode
+            && mode > 0o7777
+        {
+            return Err(format!("DirectoryMode {:o} is not a valid file mode", mode));
+        }
+
+        if let Some(ref unit) = self.unit
+            && unit.ends_with(".path")
+        {
+            return Err(format!("Path unit cannot activate another path '{}'", unit));
+        }
+
+        Ok(())
+    }
+
+    // Builder pattern setters
+    pub fn path_exists(mut self, value: Vec<PathBuf>) -> Self {
+        self.path_exists = Some(value);
+        self
+    }
+
+    pub fn path_exists_glob(mut self, value: Vec<impl Into<String>>) -> Self {
+        self.path_exists_glob = Some(value.into_iter().map(|s| s.into()).collect());
+        self
+    }
+
+    pub fn path_changed(mut self, value: Vec<PathBuf>) -> Self {
+        self.path_changed = Some(value);
+        self
+    }
+
+    pub fn path_modified(mut self, value: Vec<PathBuf>) -> Self {
+        self.path_modified = Some(value);
+        self
+    }
+
+    pub fn directory_not_empty(mut self, value: Vec<PathBuf>) -> Self {
+        self.directory_not_empty = Some(value);
+        self
+    }
+
+    pub fn unit(mut self, value: impl Into<String>) -> Self {
+        self.unit = Some(value.into());
+        self
+    }
+
+    pub fn make_directory(mut self, value: bool) -> Self {
+        self.make_directory = Some(value);
+        self
+    }
+
+    pub fn directory_mode(mut self, value: u32) -> Self {
+        self.directory_mode = Some(value);
+        self
+    }
+
+    pub fn trigger_limit_interval_sec(mut self, value: impl Into<String>) -> Self {
+        self.trigger_limit_interval_sec = Some(value.into());
+        self
+    }
+
+    pub fn trigger_limit_burst(mut self, value: u32) -> Self {
+        self.trigger_limit_burst = Some(value);
+        self
+    }
+}
+
+/// Configuration for a systemd path unit.
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.path.html
+#[derive(Debug, Clone)]
+pub struct PathConfig {
+    pub name: String,
+    pub unit: Unit,
+    pub path: Path,
+    pub install: Install,
+}
+
+impl PathConfig {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            unit: Unit::default(),
+            path: Path::default(),
+            install: Install::default(),
+        }
+    }
+
+    pub fn name(mut self, s: impl Into<String>) -> Self {
+        self.name = s.into();
+        self
+    }
+
+    pub fn unit(mut self, unit: Unit) -> Self {
+        self.unit = unit;
+        self
+    }
+
+    pub fn path(mut self, path: Path) -> Self {
+        self.path = path;
+        self
+    }
+
+    pub fn install(mut self, install: Install) -> Self {
+        self.install = install;
+        self
+    }
+
+    pub fn validate(&self) -> crate::Result<()> {
+        self.unit.validate().map_err(Error::ValidationError)?;
+        self.path.validate().map_err(Error::ValidationError)?;
+        self.install.validate().map_err(Error::ValidationError)?;
+        Ok(())
+    }
+
+    pub(crate) fn unit_name(&self) -> String {
+        format!("{}.path", self.name)
+    }
+}
+
+impl fmt::Display for PathConfig {
+    fn fmt(&self, buf: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.unit.fmt(buf)?;
+        self.path.fmt(buf)?;
+        self.install.fmt(buf)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_path_unit() {
+        let config = PathConfig::new("spool").path(
+            Path::new()
+                .directory_not_empty(vec![PathBuf::from("/var/spool/demo")])
+                .make_directory(true)
+                .directory_mode(0o750)
+                .unit("demo.service"),
+        );
+
+        assert!(config.validate().is_ok());
+        assert_eq!(config.unit_name(), "spool.path");
+        assert_eq!(
+            config.to_string(),
+            "[Unit]\n[Path]\nDirectoryNotEmpty=/var/spool/demo\nUnit=demo.service\nMakeDirectory=yes\n\
+             DirectoryMode=0750\n[Install]\n"
+        );
+    }
+}
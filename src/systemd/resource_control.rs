@@ -0,0 +1,936 @@
+use super::network::{self, IpAddressRule, RestrictNetworkInterfaces, SocketBindRule};
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// Size in bytes, rendered with the largest exact binary suffix (`K`, `M`, `G`, `T`).
+/// The `kib` to `tib` constructors saturate at `u64::MAX`, which systemd treats as no limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ByteSize(pub u64);
+
+impl ByteSize {
+    pub fn bytes(value: u64) -> Self {
+        Self(value)
+    }
+
+    pub fn kib(value: u64) -> Self {
+        Self(value.saturating_mul(1 << 10))
+    }
+
+    pub fn mib(value: u64) -> Self {
+        Self(value.saturating_mul(1 << 20))
+    }
+
+    pub fn gib(value: u64) -> Self {
+        Self(value.saturating_mul(1 << 30))
+    }
+
+    pub fn tib(value: u64) -> Self {
+        Self(value.saturating_mul(1 << 40))
+    }
+}
+
+impl fmt::Display for ByteSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const SUFFIXES: [(u64, &str); 4] = [
+            (1 << 40, "T"),
+            (1 << 30, "G"),
+            (1 << 20, "M"),
+            (1 << 10, "K"),
+        ];
+        for (factor, suffix) in SUFFIXES {
+            if self.0 != 0 && self.0.is_multiple_of(factor) {
+                return write!(f, "{}{}", self.0 / factor, suffix);
+            }
+        }
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Memory limit for `MemoryMin=`, `MemoryLow=`, `MemoryHigh=`, `MemoryMax=` and `MemorySwapMax=`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemoryLimit {
+    /// Absolute size
+    Size(ByteSize),
+    /// Percentage of the physical memory, between 0 and 100
+    Percent(u8),
+    /// No limit
+    Infinity,
+}
+
+impl fmt::Display for MemoryLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MemoryLimit::Size(size) => write!(f, "{}", size),
+            MemoryLimit::Percent(percent) => write!(f, "{}%", percent),
+            MemoryLimit::Infinity => write!(f, "infinity"),
+        }
+    }
+}
+
+/// Limit for `TasksMax=`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TasksLimit {
+    /// Absolute number of tasks
+    Count(u64),
+    /// Percentage of the system-wide task limit, between 0 and 100
+    Percent(u8),
+    /// No limit
+    Infinity,
+}
+
+impl fmt::Display for TasksLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TasksLimit::Count(count) => write!(f, "{}", count),
+            TasksLimit::Percent(percent) => write!(f, "{}%", percent),
+            TasksLimit::Infinity => write!(f, "infinity"),
+        }
+    }
+}
+
+/// Weight for `CPUWeight=` and `StartupCPUWeight=`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CpuWeight {
+    /// Weight between 1 and 10000
+    Weight(u16),
+    /// Idle scheduling, only receives CPU time nobody else wants
+    Idle,
+}
+
+impl fmt::Display for CpuWeight {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CpuWeight::Weight(weight) => write!(f, "{}", weight),
+            CpuWeight::Idle => write!(f, "idle"),
+        }
+    }
+}
+
+/// Controller delegation for `Delegate=`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Delegate {
+    /// Delegate all controllers
+    Yes,
+    /// Do not delegate
+    No,
+    /// Delegate only the listed controllers (e.g. `cpu`, `memory`, `io`, `pids`)
+    Controllers(Vec<String>),
+}
+
+impl fmt::Display for Delegate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Delegate::Yes => write!(f, "yes"),
+            Delegate::No => write!(f, "no"),
+            Delegate::Controllers(controllers) => write!(f, "{}", controllers.join(" ")),
+        }
+    }
+}
+
+/// Per-device I/O setting, such as `IOReadBandwidthMax=/dev/sda 10M`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IoDeviceLimit<T> {
+    /// Block device node or a path on the file system backed by it
+    pub device: PathBuf,
+    pub value: T,
+}
+
+impl<T> IoDeviceLimit<T> {
+    pub fn new(device: impl Into<PathBuf>, value: T) -> Self {
+        Self {
+            device: device.into(),
+            value,
+        }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for IoDeviceLimit<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.device.display(), self.value)
+    }
+}
+
+/// Set of CPU (or NUMA node) indices, rendered as ranges like `0-3,8`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CpuSet {
+    ranges: Vec<(u32, u32)>,
+}
+
+impl CpuSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a single index to the set.
+    pub fn cpu(self, index: u32) -> Self {
+        self.range(index, index)
+    }
+
+    /// Adds the inclusive range `first-last` to the set.
+    pub fn range(mut self, first: u32, last: u32) -> Self {
+        self.ranges.push((first, last));
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Returns the largest index in the set.
+    pub fn max(&self) -> Option<u32> {
+        self.ranges
+            .iter()
+            .map(|&(first, last)| first.max(last))
+            .max()
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        for &(first, last) in &self.ranges {
+            if first > last {
+                return Err(format!("Invalid CPU range '{}-{}'", first, last));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for CpuSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ranges = self
+            .ranges
+            .iter()
+            .map(|&(first, last)| {
+                if first == last {
+                    first.to_string()
+                } else {
+                    format!("{}-{}", first, last)
+                }
+            })
+            .collect::<Vec<_>>();
+        write!(f, "{}", ranges.join(","))
+    }
+}
+
+impl FromStr for CpuSet {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |value: &str| {
+            value
+                .trim()
+                .parse::<u32>()
+                .map_err(|_| format!("Invalid CPU index '{}' in '{}'", value, s))
+        };
+        let mut set = CpuSet::new();
+        for item in s.split([',', ' ']).filter(|item| !item.is_empty()) {
+            set = match item.split_once('-') {
+                Some((first, last)) => set.range(parse(first)?, parse(last)?),
+                None => set.cpu(parse(item)?),
+            };
+        }
+        set.validate()?;
+        Ok(set)
+    }
+}
+
+/// Resource control options shared by slice, scope, service, socket, mount and swap units
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.resource-control.html#Options
+#[derive(Debug, Clone, Default)]
+pub struct ResourceControl {
+    // CPU
+    /// Turn on CPU usage accounting.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.resource-control.html#CPUAccounting=
+    pub cpu_accounting: Option<bool>,
+
+    /// Relative CPU time share of the unit.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.resource-control.html#CPUWeight=weight
+    pub cpu_weight: Option<CpuWeight>,
+
+    /// CPU weight applied during system startup and shutdown.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.resource-control.html#CPUWeight=weight
+    pub startup_cpu_weight: Option<CpuWeight>,
+
+    /// Maximum CPU time as a percentage of a single CPU; may exceed 100 on multi-CPU systems.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.resource-control.html#CPUQuota=
+    pub cpu_quota: Option<u32>,
+
+    /// CPUs the unit may run on.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.resource-control.html#AllowedCPUs=
+    pub allowed_cpus: Option<CpuSet>,
+
+    /// NUMA memory nodes the unit may allocate from.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.resource-control.html#AllowedMemoryNodes=
+    pub allowed_memory_nodes: Option<CpuSet>,
+
+    // Memory
+    /// Turn on memory accounting.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.resource-control.html#MemoryAccounting=
+    pub memory_accounting: Option<bool>,
+
+    /// Memory usage protection, memory below this is never reclaimed.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.resource-control.html#MemoryMin=bytes
+    pub memory_min: Option<MemoryLimit>,
+
+    /// Best-effort memory usage protection.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.resource-control.html#MemoryLow=bytes
+    pub memory_low: Option<MemoryLimit>,
+
+    /// Throttling limit on memory usage.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.resource-control.html#MemoryHigh=bytes
+    pub memory_high: Option<MemoryLimit>,
+
+    /// Absolute limit on memory usage.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.resource-control.html#MemoryMax=bytes
+    pub memory_max: Option<MemoryLimit>,
+
+    /// Absolute limit on swap usage.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.resource-control.html#MemorySwapMax=bytes
+    pub memory_swap_max: Option<MemoryLimit>,
+
+    // Process accounting and control
+    /// Turn on task accounting.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.resource-control.html#TasksAccounting=
+    pub tasks_accounting: Option<bool>,
+
+    /// Maximum number of tasks that may be created in the unit.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.resource-control.html#TasksMax=N
+    pub tasks_max: Option<TasksLimit>,
+
+    // IO
+    /// Turn on block I/O accounting.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.resource-control.html#IOAccounting=
+    pub io_accounting: Option<bool>,
+
+    /// Default I/O weight, between 1 and 10000.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.resource-control.html#IOWeight=weight
+    pub io_weight: Option<u16>,
+
+    /// I/O weight applied during system startup and shutdown, between 1 and 10000.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.resource-control.html#IOWeight=weight
+    pub startup_io_weight: Option<u16>,
+
+    /// Per-device I/O weight, between 1 and 10000.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.resource-control.html#IODeviceWeight=device%20weight
+    pub io_device_weight: Option<Vec<IoDeviceLimit<u16>>>,
+
+    /// Per-device read bandwidth limit, in bytes per second.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.resource-control.html#IOReadBandwidthMax=device%20bytes
+    pub io_read_bandwidth_max: Option<Vec<IoDeviceLimit<ByteSize>>>,
+
+    /// Per-device write bandwidth limit, in bytes per second.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.resource-control.html#IOReadBandwidthMax=device%20bytes
+    pub io_write_bandwidth_max: Option<Vec<IoDeviceLimit<ByteSize>>>,
+
+    /// Per-device read operations per second limit.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.resource-control.html#IOReadIOPSMax=device%20IOPS
+    pub io_read_iops_max: Option<Vec<IoDeviceLimit<u64>>>,
+
+    /// Per-device write operations per second limit.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.resource-control.html#IOReadIOPSMax=device%20IOPS
+    pub io_write_iops_max: Option<Vec<IoDeviceLimit<u64>>>,
+
+    // Network
+    /// Addresses sockets may communicate with, taking precedence over IPAddressDeny=.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.resource-control.html#IPAddressAllow=ADDRESS%5B/PREFIXLENGTH%5D%E2%80%A6
+    pub ip_address_allow: Option<Vec<IpAddressRule>>,
+
+    /// Addresses sockets may not communicate with.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.resource-control.html#IPAddressAllow=ADDRESS%5B/PREFIXLENGTH%5D%E2%80%A6
+    pub ip_address_deny: Option<Vec<IpAddressRule>>,
+
+    /// Binds sockets may perform, taking precedence over SocketBindDeny=.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.resource-control.html#SocketBindAllow=bind-rule
+    pub socket_bind_allow: Option<Vec<SocketBindRule>>,
+
+    /// Binds sockets may not perform.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.resource-control.html#SocketBindAllow=bind-rule
+    pub socket_bind_deny: Option<Vec<SocketBindRule>>,
+
+    /// Network interfaces sockets may use.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.resource-control.html#RestrictNetworkInterfaces=
+    pub restrict_network_interfaces: Option<RestrictNetworkInterfaces>,
+
+    /// Pinned BPF programs filtering ingress traffic.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.resource-control.html#IPIngressFilterPath=BPF_FS_PROGRAM_PATH
+    pub ip_ingress_filter_path: Option<Vec<PathBuf>>,
+
+    /// Pinned BPF programs filtering egress traffic.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.resource-control.html#IPIngressFilterPath=BPF_FS_PROGRAM_PATH
+    pub ip_egress_filter_path: Option<Vec<PathBuf>>,
+
+    // Control group management
+    /// Delegate control of the unit's cgroup subtree to its processes.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.resource-control.html#Delegate=
+    pub delegate: Option<Delegate>,
+
+    /// Name of the slice unit to place the unit in.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.resource-control.html#Slice=
+    pub slice: Option<String>,
+}
+
+impl fmt::Display for ResourceControl {
+    fn fmt(&self, buf: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // CPU
+        write_bool!(buf, self.cpu_accounting, "CPUAccounting");
+        write_option!(buf, self.cpu_weight, "CPUWeight");
+        write_option!(buf, self.startup_cpu_weight, "StartupCPUWeight");
+        if let Some(quota) = self.cpu_quota {
+            writeln!(buf, "CPUQuota={}%", quota)?;
+        }
+        write_option!(buf, self.allowed_cpus, "AllowedCPUs");
+        write_option!(buf, self.allowed_memory_nodes, "AllowedMemoryNodes");
+
+        // Memory
+        write_bool!(buf, self.memory_accounting, "MemoryAccounting");
+        write_option!(buf, self.memory_min, "MemoryMin");
+        write_option!(buf, self.memory_low, "MemoryLow");
+        write_option!(buf, self.memory_high, "MemoryHigh");
+        write_option!(buf, self.memory_max, "MemoryMax");
+        write_option!(buf, self.memory_swap_max, "MemorySwapMax");
+
+        // Process accounting and control
+        write_bool!(buf, self.tasks_accounting, "TasksAccounting");
+        write_option!(buf, self.tasks_max, "TasksMax");
+
+        // IO
+        write_bool!(buf, self.io_accounting, "IOAccounting");
+        write_option!(buf, self.io_weight, "IOWeight");
+        write_option!(buf, self.startup_io_weight, "StartupIOWeight");
+        write_vec_multi!(buf, self.io_device_weight, "IODeviceWeight");
+        write_vec_multi!(buf, self.io_read_bandwidth_max, "IOReadBandwidthMax");
+        write_vec_multi!(buf, self.io_write_bandwidth_max, "IOWriteBandwidthMax");
+        write_vec_multi!(buf, self.io_read_iops_max, "IOReadIOPSMax");
+        write_vec_multi!(buf, self.io_write_iops_max, "IOWriteIOPSMax");
+
+        // Network
+        write_vec!(buf, self.ip_address_allow, "IPAddressAllow");
+        write_vec!(buf, self.ip_address_deny, "IPAddressDeny");
+        write_vec_multi!(buf, self.socket_bind_allow, "SocketBindAllow");
+        write_vec_multi!(buf, self.socket_bind_deny, "SocketBindDeny");
+        write_option!(
+            buf,
+            self.restrict_network_interfaces,
+            "RestrictNetworkInterfaces"
+        );
+        write_path_multi!(buf, self.ip_ingress_filter_path, "IPIngressFilterPath");
+        write_path_multi!(buf, self.ip_egress_filter_path, "IPEgressFilterPath");
+
+        // Control group management
+        write_option!(buf, self.delegate, "Delegate");
+        write_option!(buf, self.slice, "Slice");
+
+        Ok(())
+    }
+}
+
+impl ResourceControl {
+    /// Creates a new empty `ResourceControl` section.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Validate the resource control configuration
+    pub fn validate(&self) -> Result<(), String> {
+        for (weight, key) in [
+            (self.cpu_weight, "CPUWeight"),
+            (self.startup_cpu_weight, "StartupCPUWeight"),
+        ] {
+            if let Some(CpuWeight::Weight(weight)) = weight {
+                validate_weight(weight, key)?;
+            }
+        }
+
+        if self.cpu_quota == Some(0) {
+            return Err("CPUQuota must be greater than 0%".to_string());
+        }
+
+        for (cpus, key) in [
+            (&self.allowed_cpus, "AllowedCPUs"),
+            (&self.allowed_memory_nodes, "AllowedMemoryNodes"),
+        ] {
+            if let Some(cpus) = cpus {
+                cpus.validate().map_err(|e| format!("{}: {}", key, e))?;
+            }
+        }
+
+        for (limit, key) in [
+            (self.memory_min, "MemoryMin"),
+            (self.memory_low, "MemoryLow"),
+            (self.memory_high, "MemoryHigh"),
+            (self.memory_max, "MemoryMax"),
+            (self.memory_swap_max, "MemorySwapMax"),
+        ] {
+            if let Some(MemoryLimit::Percent(percent)) = limit {
+                validate_percent(percent, key)?;
+            }
+        }
+
+        if let Some(TasksLimit::Percent(percent)) = self.tasks_max {
+            validate_percent(percent, "TasksMax")?;
+        }
+
+        for (weight, key) in [
+            (self.io_weight, "IOWeight"),
+            (self.startup_io_weight, "StartupIOWeight"),
+        ] {
+            if let Some(weight) = weight {
+                validate_weight(weight, key)?;
+            }
+        }
+
+        if let Some(ref weights) = self.io_device_weight {
+            for weight in weights {
+                validate_weight(weight.value, "IODeviceWeight")?;
+            }
+        }
+
+        validate_devices(&self.io_device_weight, "IODeviceWeight")?;
+        validate_devices(&self.io_read_bandwidth_max, "IOReadBandwidthMax")?;
+        validate_devices(&self.io_write_bandwidth_max, "IOWriteBandwidthMax")?;
+        validate_devices(&self.io_read_iops_max, "IOReadIOPSMax")?;
+        validate_devices(&self.io_write_iops_max, "IOWriteIOPSMax")?;
+
+        for rules in [&self.ip_address_allow, &self.ip_address_deny]
+            .into_iter()
+            .flatten()
+        {
+            for rule in rules {
+                rule.validate()?;
+            }
+        }
+        for (rules, key) in [
+            (&self.socket_bind_allow, "SocketBindAllow"),
+            (&self.socket_bind_deny, "SocketBindDeny"),
+        ] {
+            if let Some(rules) = rules {
+                for rule in rules {
+                    rule.validate(key)?;
+                }
+            }
+        }
+        if let Some(ref interfaces) = self.restrict_network_interfaces {
+            interfaces.validate()?;
+        }
+        for (paths, key) in [
+            (&self.ip_ingress_filter_path, "IPIngressFilterPath"),
+            (&self.ip_egress_filter_path, "IPEgressFilterPath"),
+        ] {
+            if let Some(paths) = paths {
+                for path in paths {
+                    network::validate_bpf_path(path, key)?;
+                }
+            }
+        }
+
+        if let Some(Delegate::Controllers(ref controllers)) = self.delegate {
+            // An empty Delegate= resets delegation instead of delegating nothing
+            if controllers.is_empty() {
+                return Err(
+                    "Delegate controllers must not be empty, use Delegate::No to disable delegation"
+                        .to_string(),
+                );
+            }
+            for controller in controllers {
+                if !DELEGATE_CONTROLLERS.contains(&controller.as_str()) {
+                    return Err(format!(
+                        "Unknown Delegate controller '{}': must be one of {}",
+                        controller,
+                        DELEGATE_CONTROLLERS.join(", ")
+                    ));
+                }
+            }
+        }
+
+        if let Some(ref slice) = self.slice
+            && !slice.ends_with(".slice")
+        {
+            return Err(format!("Slice '{}' must end with '.slice'", slice));
+        }
+
+        Ok(())
+    }
+
+    // Builder pattern setters
+    pub fn cpu_accounting(mut self, value: bool) -> Self {
+        self.cpu_accounting = Some(value);
+        self
+    }
+
+    pub fn cpu_weight(mut self, value: CpuWeight) -> Self {
+        self.cpu_weight = Some(value);
+        self
+    }
+
+    pub fn startup_cpu_weight(mut self, value: CpuWeight) -> Self {
+        self.startup_cpu_weight = Some(value);
+        self
+    }
+
+    pub fn cpu_quota(mut self, percent: u32) -> Self {
+        self.cpu_quota = Some(percent);
+        self
+    }
+
+    pub fn allowed_cpus(mut self, value: CpuSet) -> Self {
+        self.allowed_cpus = Some(value);
+        self
+    }
+
+    pub fn allowed_memory_nodes(mut self, value: CpuSet) -> Self {
+        self.allowed_memory_nodes = Some(value);
+        self
+    }
+
+    pub fn memory_accounting(mut self, value: bool) -> Self {
+        self.memory_accounting = Some(value);
+        self
+    }
+
+    pub fn memory_min(mut self, value: MemoryLimit) -> Self {
+        self.memory_min = Some(value);
+        self
+    }
+
+    pub fn memory_low(mut self, value: MemoryLimit) -> Self {
+        self.memory_low = Some(value);
+        self
+    }
+
+    pub fn memory_high(mut self, value: MemoryLimit) -> Self {
+        self.memory_high = Some(value);
+        self
+    }
+
+    pub fn memory_max(mut self, value: MemoryLimit) -> Self {
+        self.memory_max = Some(value);
+        self
+    }
+
+    pub fn memory_swap_max(mut self, value: MemoryLimit) -> Self {
+        self.memory_swap_max = Some(value);
+        self
+    }
+
+    pub fn tasks_accounting(mut self, value: bool) -> Self {
+        self.tasks_accounting = Some(value);
+        self
+    }
+
+    pub fn tasks_max(mut self, value: TasksLimit) -> Self {
+        self.tasks_max = Some(value);
+        self
+    }
+
+    pub fn io_accounting(mut self, value: bool) -> Self {
+        self.io_accounting = Some(value);
+        self
+    }
+
+    pub fn io_weight(mut self, value: u16) -> Self {
+        self.io_weight = Some(value);
+        self
+    }
+
+    pub fn startup_io_weight(mut self, value: u16) -> Self {
+        self.startup_io_weight = Some(value);
+        self
+    }
+
+    pub fn io_device_weight(mut self, value: Vec<IoDeviceLimit<u16>>) -> Self {
+        self.io_device_weight = Some(value);
+        self
+    }
+
+    pub fn io_read_bandwidth_max(mut self, value: Vec<IoDeviceLimit<ByteSize>>) -> Self {
+        self.io_read_bandwidth_max = Some(value);
+        self
+    }
+
+    pub fn io_write_bandwidth_max(mut self, value: Vec<IoDeviceLimit<ByteSize>>) -> Self {
+        self.io_write_bandwidth_max = Some(value);
+        self
+    }
+
+    pub fn io_read_iops_max(mut self, value: Vec<IoDeviceLimit<u64>>) -> Self {
+        self.io_read_iops_max = Some(value);
+        self
+    }
+
+    pub fn io_write_iops_max(mut self, value: Vec<IoDeviceLimit<u64>>) -> Self {
+        self.io_write_iops_max = Some(value);
+        self
+    }
+
+    pub fn ip_address_allow(mut self, value: Vec<IpAddressRule>) -> Self {
+        self.ip_address_allow = Some(value);
+        self
+    }
+
+    pub fn ip_address_deny(mut self, value: Vec<IpAddressRule>) -> Self {
+        self.ip_address_deny = Some(value);
+        self
+    }
+
+    pub fn socket_bind_allow(mut self, value: Vec<SocketBindRule>) -> Self {
+        self.socket_bind_allow = Some(value);
+        self
+    }
+
+    pub fn socket_bind_deny(mut self, value: Vec<SocketBindRule>) -> Self {
+        self.socket_bind_deny = Some(value);
+        self
+    }
+
+    pub fn restrict_network_interfaces(mut self, value: RestrictNetworkInterfaces) -> Self {
+        self.restrict_network_interfaces = Some(value);
+        self
+    }
+
+    pub fn ip_ingress_filter_path(mut self, value: Vec<impl Into<PathBuf>>) -> Self {
+        self.ip_ingress_filter_path = Some(value.into_iter().map(|p| p.into()).collect());
+        self
+    }
+
+    pub fn ip_egress_filter_path(mut self, value: Vec<impl Into<PathBuf>>) -> Self {
+        self.ip_egress_filter_path = Some(value.into_iter().map(|p| p.into()).collect());
+        self
+    }
+
+    pub fn delegate(mut self, value: Delegate) -> Self {
+        self.delegate = Some(value);
+        self
+    }
+
+    pub fn slice(mut self, value: impl Into<String>) -> Self {
+        self.slice = Some(value.into());
+        self
+    }
+}
+
+/// Controllers accepted by `Delegate=`
+const DELEGATE_CONTROLLERS: [&str; 7] =
+    ["cpu", "cpuacct", "cpuset", "io", "blkio", "memory", "pids"];
+
+fn validate_weight(weight: u16, key: &str) -> Result<(), String> {
+    if !(1..=10000).contains(&weight) {
+        return Err(format!("{} {} must be between 1 and 10000", key, weight));
+    }
+    Ok(())
+}
+
+fn validate_percent(percent: u8, key: &str) -> Result<(), String> {
+    if percent > 100 {
+        return Err(format!("{} {}% must be between 0% and 100%", key, percent));
+    }
+    Ok(())
+}
+
+fn validate_devices<T>(limits: &Option<Vec<IoDeviceLimit<T>>>, key: &str) -> Result<(), String> {
+    for limit in limits.iter().flatten() {
+        if !limit.device.is_absolute() {
+            return Err(format!(
+                "{} device '{}' must be an absolute path",
+                key,
+                limit.device.display()
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn byte_size_renders_largest_exact_suffix() {
+        assert_eq!(ByteSize::bytes(0).to_string(), "0");
+        assert_eq!(ByteSize::bytes(1000).to_string(), "1000");
+        assert_eq!(ByteSize::bytes(1536).to_string(), "1536");
+        assert_eq!(ByteSize::kib(1).to_string(), "1K");
+        assert_eq!(ByteSize::kib(1536).to_string(), "1536K");
+        assert_eq!(ByteSize::mib(512).to_string(), "512M");
+        assert_eq!(ByteSize::mib(2048).to_string(), "2G");
+        assert_eq!(ByteSize::gib(3).to_string(), "3G");
+        assert_eq!(ByteSize::tib(2).to_string(), "2T");
+        assert_eq!(ByteSize::gib(1 << 20).to_string(), "1024T");
+    }
+
+    #[test]
+    fn byte_size_saturates() {
+        assert_eq!(ByteSize::kib(u64::MAX), ByteSize(u64::MAX));
+        assert_eq!(ByteSize::mib(1 << 44), ByteSize(u64::MAX));
+        assert_eq!(ByteSize::gib(1 << 34), ByteSize(u64::MAX));
+        assert_eq!(ByteSize::tib(1 << 24), ByteSize(u64::MAX));
+        assert_eq!(ByteSize::tib((1 << 24) - 1).to_string(), "16777215T");
+        assert_eq!(ByteSize(u64::MAX).to_string(), u64::MAX.to_string());
+    }
+
+    #[test]
+    fn limits_render() {
+        assert_eq!(MemoryLimit::Size(ByteSize::mib(256)).to_string(), "256M");
+        assert_eq!(MemoryLimit::Percent(50).to_string(), "50%");
+        assert_eq!(MemoryLimit::Infinity.to_string(), "infinity");
+        assert_eq!(TasksLimit::Count(512).to_string(), "512");
+        assert_eq!(TasksLimit::Percent(20).to_string(), "20%");
+        assert_eq!(CpuWeight::Idle.to_string(), "idle");
+        assert_eq!(
+            Delegate::Controllers(vec!["cpu".into(), "memory".into()]).to_string(),
+            "cpu memory"
+        );
+    }
+
+    #[test]
+    fn cpu_set_from_str() {
+        assert_eq!(
+            "0-3,8".parse::<CpuSet>(),
+            Ok(CpuSet::new().range(0, 3).cpu(8))
+        );
+        assert_eq!(
+            "1 2 4-5".parse::<CpuSet>(),
+            Ok(CpuSet::new().cpu(1).cpu(2).range(4, 5))
+        );
+        assert_eq!("".parse::<CpuSet>(), Ok(CpuSet::new()));
+        assert_eq!("0-3,8".parse::<CpuSet>().unwrap().to_string(), "0-3,8");
+        assert_eq!("7,2-4".parse::<CpuSet>().unwrap().max(), Some(7));
+        assert_eq!(
+            "3-1".parse::<CpuSet>(),
+            Err("Invalid CPU range '3-1'".to_string())
+        );
+        assert_eq!(
+            "0,x".parse::<CpuSet>(),
+            Err("Invalid CPU index 'x' in '0,x'".to_string())
+        );
+        assert!("1-".parse::<CpuSet>().is_err());
+        assert!("-1".parse::<CpuSet>().is_err());
+    }
+
+    #[test]
+    fn weights_must_be_between_1_and_10000() {
+        for weight in [1, 100, 10000] {
+            assert!(
+                ResourceControl::new()
+                    .cpu_weight(CpuWeight::Weight(weight))
+                    .validate()
+                    .is_ok()
+            );
+            assert!(ResourceControl::new().io_weight(weight).validate().is_ok());
+        }
+        assert!(
+            ResourceControl::new()
+                .cpu_weight(CpuWeight::Idle)
+                .validate()
+                .is_ok()
+        );
+        assert_eq!(
+            ResourceControl::new()
+                .cpu_weight(CpuWeight::Weight(0))
+                .validate(),
+            Err("CPUWeight 0 must be between 1 and 10000".to_string())
+        );
+        assert_eq!(
+            ResourceControl::new()
+                .startup_cpu_weight(CpuWeight::Weight(10001))
+                .validate(),
+            Err("StartupCPUWeight 10001 must be between 1 and 10000".to_string())
+        );
+        assert_eq!(
+            ResourceControl::new().startup_io_weight(0).validate(),
+            Err("StartupIOWeight 0 must be between 1 and 10000".to_string())
+        );
+        assert_eq!(
+            ResourceControl::new()
+                .io_device_weight(vec![IoDeviceLimit::new("/dev/sda", 10001)])
+                .validate(),
+            Err("IODeviceWeight 10001 must be between 1 and 10000".to_string())
+        );
+    }
+
+    #[test]
+    fn percentages_must_be_at_most_100() {
+        assert!(
+            ResourceControl::new()
+                .memory_max(MemoryLimit::Percent(100))
+                .validate()
+                .is_ok()
+        );
+        assert!(
+            ResourceControl::new()
+                .tasks_max(TasksLimit::Percent(0))
+                .validate()
+                .is_ok()
+        );
+        assert_eq!(
+            ResourceControl::new()
+                .memory_high(MemoryLimit::Percent(101))
+                .validate(),
+            Err("MemoryHigh 101% must be between 0% and 100%".to_string())
+        );
+        assert_eq!(
+            ResourceControl::new()
+                .tasks_max(TasksLimit::Percent(150))
+                .validate(),
+            Err("TasksMax 150% must be between 0% and 100%".to_string())
+        );
+        // CPUQuota may exceed 100% on multi-CPU systems
+        assert!(ResourceControl::new().cpu_quota(250).validate().is_ok());
+        assert_eq!(
+            ResourceControl::new().cpu_quota(0).validate(),
+            Err("CPUQuota must be greater than 0%".to_string())
+        );
+    }
+
+    #[test]
+    fn validate_rejects_invalid_settings() {
+        assert_eq!(
+            ResourceControl::new()
+                .allowed_cpus(CpuSet::new().range(4, 2))
+                .validate(),
+            Err("AllowedCPUs: Invalid CPU range '4-2'".to_string())
+        );
+        assert_eq!(
+            ResourceControl::new()
+                .io_read_bandwidth_max(vec![IoDeviceLimit::new("sda", ByteSize::mib(10))])
+                .validate(),
+            Err("IOReadBandwidthMax device 'sda' must be an absolute path".to_string())
+        );
+        assert_eq!(
+            ResourceControl::new()
+                .delegate(Delegate::Controllers(vec!["cpu".into(), "gpu".into()]))
+                .validate(),
+            Err("Unknown Delegate controller 'gpu': must be one of cpu, cpuacct, cpuset, io, blkio, memory, pids".to_string())
+        );
+        assert!(
+            ResourceControl::new()
+                .delegate(Delegate::Controllers(vec![]))
+                .validate()
+                .is_err()
+        );
+        assert_eq!(
+            ResourceControl::new().slice("tenants").validate(),
+            Err("Slice 'tenants' must end with '.slice'".to_string())
+        );
+    }
+
+    #[test]
+    fn renders_settings() {
+        let resource_control = ResourceControl::new()
+            .cpu_weight(CpuWeight::Weight(200))
+            .cpu_quota(150)
+            .allowed_cpus(CpuSet::new().range(0, 3))
+            .memory_high(MemoryLimit::Size(ByteSize::mib(768)))
+            .memory_max(MemoryLimit::Size(ByteSize::gib(1)))
+            .tasks_max(TasksLimit::Count(64))
+            .io_read_bandwidth_max(vec![IoDeviceLimit::new("/dev/sda", ByteSize::mib(10))])
+            .delegate(Delegate::Controllers(vec!["cpu".into(), "pids".into()]))
+            .slice("tenants-acme.slice");
+
+        assert!(resource_control.validate().is_ok());
+        assert_eq!(
+            resource_control.to_string(),
+            "CPUWeight=200\nCPUQuota=150%\nAllowedCPUs=0-3\nMemoryHigh=768M\nMemoryMax=1G\n\
+             TasksMax=64\nIOReadBandwidthMax=/dev/sda 10M\nDelegate=cpu pids\nSlice=tenants-acme.slice\n"
+        );
+    }
+}
@@ -0,0 +1,369 @@
+use super::scope::Scope;
+use super::service::Service;
+use crate::Result;
+use crate::error::Error;
+use std::ffi::OsString;
+use std::process::{Command, ExitStatus};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+const SYSTEMD_RUN: &str = "systemd-run";
+
+/// Suffix of the next generated unit name, unique within the process.
+static NEXT_UNIT: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone)]
+enum Transient {
+    Scope(Box<Scope>),
+    Service(Box<Service>),
+}
+
+/// Runs a command in a transient scope or service unit through `systemd-run`.
+///
+/// A scope keeps the command as a child of the calling process and only places it in its own cgroup,
+/// so resource control and kill settings apply. A service is spawned by the service manager,
+/// so every setting of [`Service`], including its `Exec` section, applies.
+#[derive(Debug, Clone)]
+pub struct Run {
+    transient: Transient,
+    unit: Option<String>,
+    description: Option<String>,
+}
+
+/// Result of a command run in a transient unit.
+#[derive(Debug, Clone)]
+pub struct RunOutput {
+    /// Full name of the transient unit, e.g. `maintenance.scope`.
+    pub unit: String,
+    /// Exit status of the command.
+    pub status: ExitStatus,
+}
+
+impl Run {
+    /// Runs the command in a transient scope unit, like `systemd-run --scope`.
+    pub fn scope(scope: Scope) -> Self {
+        Self {
+            transient: Transient::Scope(Box::new(scope)),
+            unit: None,
+            description: None,
+        }
+    }
+
+    /// Runs the command as a transient service and waits for it to exit, like `systemd-run --wait --pipe`.
+    pub fn service(service: Service) -> Self {
+        Self {
+            transient: Transient::Service(Box::new(service)),
+            unit: None,
+            description: None,
+        }
+    }
+
+    /// Sets the unit name without the type suffix. A unique name is generated when not set.
+    pub fn unit(mut self, name: impl Into<String>) -> Self {
+        self.unit = Some(name.into());
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if let Some(ref unit) = self.unit
+            && (unit.is_empty()
+                || !unit
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || ":_.\\-@".contains(c)))
+        {
+            return Err(Error::ValidationError(format!(
+                "Invalid unit name '{}'",
+                unit
+            )));
+        }
+        match self.transient {
+            Transient::Scope(ref scope) => scope.validate().map_err(Error::ValidationError),
+            Transient::Service(ref service) => {
+                if service.exec_start.is_some() {
+                    return Err(Error::ValidationError(
+                        "ExecStart= cannot be set, the command to run is used instead".to_string(),
+                    ));
+                }
+                service.validate().map_err(Error::ValidationError)
+            }
+        }
+    }
+
+    /// Returns the `Key=Value` properties passed to `systemd-run` with `-p`.
+    pub fn properties(&self) -> Vec<String> {
+        let rendered = match self.transient {
+            Transient::Scope(ref scope) => scope.to_string(),
+            Transient::Service(ref service) => service.to_string(),
+        };
+        rendered
+            .lines()
+            .filter(|line| !line.is_empty() && !line.starts_with('['))
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Runs `command` in the transient unit and waits for it to finish.
+    ///
+    /// The program, arguments, environment and working directory are taken from `command`,
+    /// standard input and output are inherited from the calling process.
+    pub fn run(&self, command: &Command) -> Result<RunOutput> {
+        let (unit, mut run) = self.command(command)?;
+        let status = run.status()?;
+        Ok(RunOutput { unit, status })
+    }
+
+    /// Builds the `systemd-run` command running `command` in the transient unit, without spawning it.
+    /// Returns the full name of the unit along with the command.
+    ///
+    /// A scope inherits the environment of `systemd-run`, which is the environment of the calling
+    /// process with the variables set or removed on `command` applied. A service starts from the
+    /// environment of the service manager instead: variables set on `command` are passed with
+    /// `--setenv=`, removed ones with `UnsetEnvironment=`. [`Command::env_clear`] cannot be
+    /// read back from `command` and is lost, remove the variables one by one instead.
+    ///
+    /// `systemd-run` is looked up in the `PATH` of the returned command, which can be changed
+    /// before spawning it, e.g. to point to a wrapper.
+    pub fn command(&self, command: &Command) -> Result<(String, Command)> {
+        self.validate()?;
+        let unit = self.unit_name();
+
+        let mut run = Command::new(SYSTEMD_RUN);
+        run.args(self.args(&unit));
+        match self.transient {
+            Transient::Scope(_) => {
+                // The scope command is forked by systemd-run itself and inherits its environment.
+                for (key, value) in command.get_envs() {
+                    match value {
+                        Some(value) => run.env(key, value),
+                        None => run.env_remove(key),
+                    };
+                }
+                if let Some(dir) = command.get_current_dir() {
+                    run.current_dir(dir);
+                }
+            }
+            Transient::Service(_) => {
+                for (key, value) in command.get_envs() {
+                    match value {
+                        Some(value) => {
+                            let mut setenv = OsString::from("--setenv=");
+                            setenv.push(key);
+                            setenv.push("=");
+                            setenv.push(value);
+                            run.arg(setenv);
+                        }
+                        None => {
+                            let mut unset = OsString::from("UnsetEnvironment=");
+                            unset.push(key);
+                            run.arg("-p").arg(unset);
+                        }
+                    }
+                }
+                if let Some(dir) = command.get_current_dir() {
+                    let mut working_directory = OsString::from("--working-directory=");
+                    working_directory.push(dir);
+                    run.arg(working_directory);
+                }
+            }
+        }
+        run.arg("--")
+            .arg(command.get_program())
+            .args(command.get_args());
+        Ok((unit, run))
+    }
+
+    fn unit_name(&self) -> String {
+        let name = match self.unit {
+            Some(ref unit) => unit.clone(),
+            None => format!(
+                "sudoservice-run-{}-{}",
+                std::process::id(),
+                NEXT_UNIT.fetch_add(1, Ordering::Relaxed)
+            ),
+        };
+        match self.transient {
+            Transient::Scope(_) => format!("{}.scope", name),
+            Transient::Service(_) => format!("{}.service", name),
+        }
+    }
+
+    fn args(&self, unit: &str) -> Vec<String> {
+        let mut args = match self.transient {
+            Transient::Scope(_) => vec!["--scope".to_string()],
+            Transient::Service(_) => vec![
+                "--wait".to_string(),
+                "--pipe".to_string(),
+                "--collect".to_string(),
+            ],
+        };
+        args.push("--quiet".to_string());
+        args.push(format!("--unit={}", unit));
+        if let Some(ref description) = self.description {
+            args.push(format!("--description={}", description));
+        }
+        for property in self.properties() {
+            args.push("-p".to_string());
+            args.push(property);
+        }
+        args
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systemd::testing::TempDir;
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+
+    /// Writes a `systemd-run` stub recording its arguments, one per line, and its exported variables,
+    /// and exiting with `code`.
+    fn stub(dir: &TempDir, code: i32) {
+        let script = format!(
+            "#!/bin/sh\nfor arg in \"$@\"; do printf '%s\\n' \"$arg\"; done > {}\nexport -p > {}\nexit {}\n",
+            dir.join("args").display(),
+            dir.join("env").display(),
+            code
+        );
+        let path = dir.join(SYSTEMD_RUN);
+        fs::write(&path, script).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    fn recorded(dir: &TempDir) -> Vec<String> {
+        fs::read_to_string(dir.join("args"))
+            .unwrap()
+            .lines()
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn scope_runs_stub_on_path() {
+        let dir = TempDir::new();
+        stub(&dir, 3);
+        let scope = Scope {
+            runtime_max_sec: Some("10s".to_string()),
+            ..Default::default()
+        };
+        let run = Run::scope(scope)
+            .unit("maintenance")
+            .description("Maintenance");
+        let mut command = Command::new("backup");
+        command.arg("--full");
+
+        let (unit, mut run) = run.command(&command).unwrap();
+        let status = run.env("PATH", dir.path()).status().unwrap();
+
+        assert_eq!(unit, "maintenance.scope");
+        assert_eq!(status.code(), Some(3));
+        assert_eq!(
+            recorded(&dir),
+            [
+                "--scope",
+                "--quiet",
+                "--unit=maintenance.scope",
+                "--description=Maintenance",
+                "-p",
+                "RuntimeMaxSec=10s",
+                "--",
+                "backup",
+                "--full",
+            ]
+        );
+    }
+
+    #[test]
+    fn service_passes_environment_as_arguments() {
+        let dir = TempDir::new();
+        stub(&dir, 0);
+        let service = Service {
+            runtime_max_sec: Some("1min".to_string()),
+            ..Default::default()
+        };
+        let mut command = Command::new("report");
+        command.env("MODE", "fast").current_dir("/srv");
+
+        let (unit, mut run) = Run::service(service)
+            .unit("report")
+            .command(&command)
+            .unwrap();
+        let status = run.env("PATH", dir.path()).status().unwrap();
+
+        assert_eq!(unit, "report.service");
+        assert!(status.success());
+        assert_eq!(
+            recorded(&dir),
+            [
+                "--wait",
+                "--pipe",
+                "--collect",
+                "--quiet",
+                "--unit=report.service",
+                "-p",
+                "RuntimeMaxSec=1min",
+                "--setenv=MODE=fast",
+                "--working-directory=/srv",
+                "--",
+                "report",
+            ]
+        );
+    }
+
+    #[test]
+    fn scope_does_not_inherit_removed_variables() {
+        // Set by cargo for the test process, so the stub would inherit it
+        const INHERITED: &str = "CARGO_MANIFEST_DIR";
+        assert!(std::env::var_os(INHERITED).is_some());
+        let dir = TempDir::new();
+        stub(&dir, 0);
+        let mut command = Command::new("backup");
+        command.env_remove(INHERITED).env("MODE", "fast");
+
+        let (_, mut run) = Run::scope(Scope::new()).command(&command).unwrap();
+        let status = run.env("PATH", dir.path()).status().unwrap();
+
+        assert!(status.success());
+        let env = fs::read_to_string(dir.join("env")).unwrap();
+        // Exported variables as listed by the shell, e.g. `export MODE='fast'`
+        assert!(env.contains("MODE="));
+        assert!(!env.contains(INHERITED));
+    }
+
+    #[test]
+    fn service_unsets_removed_variables() {
+        let mut command = Command::new("report");
+        command.env_remove("HOME");
+
+        let (_, run) = Run::service(Service::default())
+            .unit("report")
+            .command(&command)
+            .unwrap();
+
+        let args = run.get_args().collect::<Vec<_>>();
+        assert_eq!(
+            args,
+            [
+                "--wait",
+                "--pipe",
+                "--collect",
+                "--quiet",
+                "--unit=report.service",
+                "-p",
+                "UnsetEnvironment=HOME",
+                "--",
+                "report",
+            ]
+        );
+    }
+
+    #[test]
+    fn generated_unit_names_are_unique() {
+        let run = Run::scope(Scope::new());
+        assert_ne!(run.unit_name(), run.unit_name());
+    }
+}
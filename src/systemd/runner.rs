@@ -0,0 +1,156 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::io;
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+
+/// Output of a command run by a [`CommandRunner`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommandOutput {
+    /// Exit code, `None` if the process was terminated by a signal.
+    pub code: Option<i32>,
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+}
+
+impl CommandOutput {
+    /// A successful run printing `stdout`.
+    pub fn success(stdout: impl Into<Vec<u8>>) -> Self {
+        Self {
+            code: Some(0),
+            stdout: stdout.into(),
+            stderr: Vec::new(),
+        }
+    }
+
+    /// A failed run exiting with `code` and printing `stderr`.
+    pub fn failure(code: i32, stderr: impl Into<Vec<u8>>) -> Self {
+        Self {
+            code: Some(code),
+            stdout: Vec::new(),
+            stderr: stderr.into(),
+        }
+    }
+
+    /// Sets the standard output of the run.
+    pub fn stdout(mut self, value: impl Into<Vec<u8>>) -> Self {
+        self.stdout = value.into();
+        self
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.code == Some(0)
+    }
+
+    pub fn stdout_lossy(&self) -> String {
+        String::from_utf8_lossy(&self.stdout).to_string()
+    }
+
+    pub fn stderr_lossy(&self) -> String {
+        String::from_utf8_lossy(&self.stderr).to_string()
+    }
+}
+
+impl From<std::process::Output> for CommandOutput {
+    fn from(output: std::process::Output) -> Self {
+        Self {
+            code: output.status.code(),
+            stdout: output.stdout,
+            stderr: output.stderr,
+        }
+    }
+}
+
+/// Runs the `systemctl`, `journalctl` and `loginctl` invocations of [`Systemd`](super::Systemd).
+/// Implement it to route commands through another transport, or use [`MockRunner`] in tests.
+pub trait CommandRunner: fmt::Debug + Send + Sync {
+    /// Runs `program` with `args` to completion and returns its output.
+    /// A non-zero exit status is not an error, only failing to run the program is.
+    fn run(&self, program: &str, args: &[&str]) -> io::Result<CommandOutput>;
+}
+
+impl<T: CommandRunner + ?Sized> CommandRunner for Arc<T> {
+    fn run(&self, program: &str, args: &[&str]) -> io::Result<CommandOutput> {
+        (**self).run(program, args)
+    }
+}
+
+impl<T: CommandRunner + ?Sized> CommandRunner for Box<T> {
+    fn run(&self, program: &str, args: &[&str]) -> io::Result<CommandOutput> {
+        (**self).run(program, args)
+    }
+}
+
+/// Runs commands as child processes of the calling process.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ProcessRunner;
+
+impl CommandRunner for ProcessRunner {
+    fn run(&self, program: &str, args: &[&str]) -> io::Result<CommandOutput> {
+        Ok(Command::new(program).args(args).output()?.into())
+    }
+}
+
+/// A runner returning scripted output for an exact sequence of expected invocations.
+///
+/// Every invocation is recorded. An invocation that does not match the next expectation panics,
+/// so a test fails at the first unexpected command.
+/// Share it with [`Systemd`](super::Systemd) through an [`Arc`] to inspect it afterwards.
+#[derive(Debug, Default)]
+pub struct MockRunner {
+    expected: Mutex<VecDeque<(Vec<String>, CommandOutput)>>,
+    calls: Mutex<Vec<Vec<String>>>,
+}
+
+impl MockRunner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Expects `program` to be run with `args` next, answering with `output`.
+    pub fn expect(self, program: &str, args: &[&str], output: CommandOutput) -> Self {
+        let invocation = std::iter::once(program)
+            .chain(args.iter().copied())
+            .map(String::from)
+            .collect();
+        self.expected
+            .lock()
+            .unwrap()
+            .push_back((invocation, output));
+        self
+    }
+
+    /// Returns every invocation so far, each as the program followed by its arguments.
+    pub fn calls(&self) -> Vec<Vec<String>> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    /// Panics if any expected invocation was not run.
+    pub fn assert_done(&self) {
+        let expected = self.expected.lock().unwrap();
+        if !expected.is_empty() {
+            let remaining: Vec<String> = expected.iter().map(|(call, _)| call.join(" ")).collect();
+            panic!("Expected invocations were not run: {:?}", remaining);
+        }
+    }
+}
+
+impl CommandRunner for MockRunner {
+    fn run(&self, program: &str, args: &[&str]) -> io::Result<CommandOutput> {
+        let invocation: Vec<String> = std::iter::once(program)
+            .chain(args.iter().copied())
+            .map(String::from)
+            .collect();
+        self.calls.lock().unwrap().push(invocation.clone());
+        let next = self.expected.lock().unwrap().pop_front();
+        match next {
+            Some((expected, output)) if expected == invocation => Ok(output),
+            Some((expected, _)) => panic!(
+                "Unexpected invocation '{}', expected '{}'",
+                invocation.join(" "),
+                expected.join(" ")
+            ),
+            None => panic!("Unexpected invocation '{}'", invocation.join(" ")),
+        }
+    }
+}
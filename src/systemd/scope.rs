@@ -0,0 +1,94 @@
+use super::kill::Kill;
+use super::resource_control::ResourceControl;
+use super::service::OomPolicy;
+use std::fmt;
+
+/// Scope section options.
+/// Scope units are transient: they group processes that were forked by someone else
+/// and can only be created at runtime, e.g. with `systemd-run --scope`.
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.scope.html#Options
+#[derive(Debug, Clone, Default)]
+pub struct Scope {
+    /// Out-of-memory killing policy.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.scope.html#OOMPolicy=
+    /// One of: continue, stop, or kill
+    pub oom_policy: Option<OomPolicy>,
+
+    /// Maximum time for the scope to run.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.scope.html#RuntimeMaxSec=
+    pub runtime_max_sec: Option<String>,
+
+    /// Randomized extra time added to RuntimeMaxSec=.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.scope.html#RuntimeRandomizedExtraSec=
+    pub runtime_randomized_extra_sec: Option<String>,
+
+    pub resource_control: Option<ResourceControl>,
+
+    pub kill: Option<Kill>,
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, buf: &mut fmt::Formatter<'_>) -> fmt::Result {
+        buf.write_str("[Scope]\n")?;
+        write_option!(buf, self.oom_policy, "OOMPolicy");
+        write_option!(buf, self.runtime_max_sec, "RuntimeMaxSec");
+        write_option!(
+            buf,
+            self.runtime_randomized_extra_sec,
+            "RuntimeRandomizedExtraSec"
+        );
+
+        if let Some(ref kill) = self.kill {
+            kill.fmt(buf)?;
+        }
+        if let Some(ref resource_control) = self.resource_control {
+            resource_control.fmt(buf)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Scope {
+    /// Creates a new empty `Scope` section.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Validate the scope configuration
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(ref kill) = self.kill {
+            kill.validate()?;
+        }
+        if let Some(ref resource_control) = self.resource_control {
+            resource_control.validate()?;
+        }
+        Ok(())
+    }
+
+    // Builder pattern setters
+    pub fn oom_policy(mut self, value: OomPolicy) -> Self {
+        self.oom_policy = Some(value);
+        self
+    }
+
+    pub fn runtime_max_sec(mut self, value: impl Into<String>) -> Self {
+        self.runtime_max_sec = Some(value.into());
+        self
+    }
+
+    pub fn runtime_randomized_extra_sec(mut self, value: impl Into<String>) -> Self {
+        self.runtime_randomized_extra_sec = Some(value.into());
+        self
+    }
+
+    pub fn resource_control(mut self, value: ResourceControl) -> Self {
+        self.resource_control = Some(value);
+        self
+    }
+
+    pub fn kill(mut self, value: Kill) -> Self {
+        self.kill = Some(value);
+        self
+    }
+}
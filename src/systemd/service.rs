@@ -1,4 +1,6 @@
 use super::exec::Exec;
+use super::kill::{self, Kill};
+use super::resource_control::ResourceControl;
 use std::fmt;
 use std::path::PathBuf;
 
@@ -352,6 +354,10 @@ pub struct Service {
     pub reload_signal: Option<String>,
 
     pub exec: Option<Exec>,
+
+    pub resource_control: Option<ResourceControl>,
+
+    pub kill: Option<Kill>,
 }
 
 impl fmt::Display for Service {
@@ -463,6 +469,14 @@ impl fmt::Display for Service {
         // Signals
         write_option!(buf, self.reload_signal, "ReloadSignal");
 
+        if let Some(ref kill) = self.kill {
+            kill.fmt(buf)?;
+        }
+
+        if let Some(ref resource_control) = self.resource_control {
+            resource_control.fmt(buf)?;
+        }
+
         if let Some(ref exec) = self.exec {
             writeln!(buf, "{}", exec)?;
         }
@@ -474,10 +488,27 @@ impl fmt::Display for Service {
 impl Service {
     /// Validate the service configuration according to systemd specifications
     pub fn validate(&self) -> Result<(), String> {
-        // All validations are now compile-time enforced via enum types
+        // Service options are compile-time enforced via enum types, embedded sections validate themselves
+        if let Some(ref signal) = self.reload_signal {
+            kill::validate_signal(signal, "ReloadSignal")?;
+        }
+        if let Some(ref kill) = self.kill {
+            kill.validate()?;
+        }
+        if let Some(ref exec) = self.exec {
+            exec.validate()?;
+        }
+        if let Some(ref resource_control) = self.resource_control {
+            resource_control.validate()?;
+        }
         Ok(())
     }
 
+    /// Returns warnings about settings that are accepted but discouraged by systemd
+    pub fn warnings(&self) -> Vec<String> {
+        self.kill.as_ref().map(Kill::warnings).unwrap_or_default()
+    }
+
     // Builder pattern setters
     pub fn service_type(mut self, value: ServiceType) -> Self {
         self.service_type = Some(value);
@@ -688,4 +719,14 @@ impl Service {
         self.exec = Some(value);
         self
     }
+
+    pub fn resource_control(mut self, value: ResourceControl) -> Self {
+        self.resource_control = Some(value);
+        self
+    }
+
+    pub fn kill(mut self, value: Kill) -> Self {
+        self.kill = Some(value);
+        self
+    }
 }
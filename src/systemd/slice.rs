@@ -0,0 +1,89 @@
+use super::install::Install;
+use super::resource_control::ResourceControl;
+use super::unit::Unit;
+use crate::Result;
+use crate::error::Error;
+use std::fmt;
+
+/// Configuration for a systemd slice unit.
+/// The `[Slice]` section only carries resource control options, applied to the cgroup of every unit placed in the slice.
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.slice.html
+#[derive(Debug, Clone)]
+pub struct SliceConfig {
+    /// Name of the slice without the `.slice` suffix, e.g. `tenants-acme` for `tenants-acme.slice`.
+    /// Dashes encode the hierarchy, so `tenants-acme.slice` is placed in `tenants.slice`.
+    pub name: String,
+    pub unit: Unit,
+    pub resource_control: ResourceControl,
+    pub install: Install,
+}
+
+impl SliceConfig {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            unit: Unit::default(),
+            resource_control: ResourceControl::default(),
+            install: Install::default(),
+        }
+    }
+
+    pub fn name(mut self, s: impl Into<String>) -> Self {
+        self.name = s.into();
+        self
+    }
+
+    pub fn unit(mut self, unit: Unit) -> Self {
+        self.unit = unit;
+        self
+    }
+
+    pub fn resource_control(mut self, resource_control: ResourceControl) -> Self {
+        self.resource_control = resource_control;
+        self
+    }
+
+    pub fn install(mut self, install: Install) -> Self {
+        self.install = install;
+        self
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.name.is_empty()
+            || self.name.starts_with('-')
+            || self.name.ends_with('-')
+            || self.name.contains("--")
+        {
+            return Err(Error::ValidationError(format!(
+                "Invalid slice name '{}': dashes separate non-empty hierarchy levels",
+                self.name
+            )));
+        }
+        if self.resource_control.slice.is_some() {
+            return Err(Error::ValidationError(
+                "Slice= cannot be set on a slice unit, its parent is derived from the name"
+                    .to_string(),
+            ));
+        }
+        self.unit.validate().map_err(Error::ValidationError)?;
+        self.resource_control
+            .validate()
+            .map_err(Error::ValidationError)?;
+        self.install.validate().map_err(Error::ValidationError)?;
+        Ok(())
+    }
+
+    pub(crate) fn unit_name(&self) -> String {
+        format!("{}.slice", self.name)
+    }
+}
+
+impl fmt::Display for SliceConfig {
+    fn fmt(&self, buf: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.unit.fmt(buf)?;
+        buf.write_str("[Slice]\n")?;
+        self.resource_control.fmt(buf)?;
+        self.install.fmt(buf)?;
+        Ok(())
+    }
+}
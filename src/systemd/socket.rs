@@ -0,0 +1,682 @@
+use super::exec::Exec;
+use super::install::Install;
+use super::kill::Kill;
+use super::resource_control::{ByteSize, ResourceControl};
+use super::unit::Unit;
+use crate::error::Error;
+use std::fmt;
+use std::path::PathBuf;
+
+/// IPv6 binding mode for sockets
+#[derive(Debug, Clone, Copy)]
+pub enum BindIpv6Only {
+    /// Use the system default
+    Default,
+    /// Bind to both IPv6 and IPv4
+    Both,
+    /// Bind to IPv6 only
+    Ipv6Only,
+}
+
+impl fmt::Display for BindIpv6Only {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BindIpv6Only::Default => write!(f, "default"),
+            BindIpv6Only::Both => write!(f, "both"),
+            BindIpv6Only::Ipv6Only => write!(f, "ipv6-only"),
+        }
+    }
+}
+
+/// Socket section options
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.socket.html#Options
+#[derive(Debug, Clone, Default)]
+pub struct Socket {
+    /// Addresses to listen on for stream sockets.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.socket.html#ListenStream=
+    pub listen_stream: Option<Vec<String>>,
+
+    /// Addresses to listen on for datagram sockets.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.socket.html#ListenDatagram=
+    pub listen_datagram: Option<Vec<String>>,
+
+    /// Addresses to listen on for sequential packet sockets.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.socket.html#ListenSequentialPacket=
+    pub listen_sequential_packet: Option<Vec<String>>,
+
+    /// FIFOs to listen on.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.socket.html#ListenFIFO=
+    pub listen_fifo: Option<Vec<PathBuf>>,
+
+    /// Special files to listen on.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.socket.html#ListenSpecial=
+    pub listen_special: Option<Vec<PathBuf>>,
+
+    /// Netlink families to listen on.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.socket.html#ListenNetlink=
+    pub listen_netlink: Option<Vec<String>>,
+
+    /// POSIX message queues to listen on.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.socket.html#ListenMessageQueue=
+    pub listen_message_queue: Option<Vec<String>>,
+
+    /// Whether IPv6 sockets are bound to IPv6 only.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.socket.html#BindIPv6Only=
+    /// One of: default, both, or ipv6-only
+    pub bind_ipv6_only: Option<BindIpv6Only>,
+
+    /// Maximum number of pending connections.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.socket.html#Backlog=
+    pub backlog: Option<u32>,
+
+    /// Network interface to bind the socket to.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.socket.html#BindToDevice=
+    pub bind_to_device: Option<String>,
+
+    /// Owner of file system socket nodes.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.socket.html#SocketUser=
+    pub socket_user: Option<String>,
+
+    /// Group of file system socket nodes.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.socket.html#SocketUser=
+    pub socket_group: Option<String>,
+
+    /// Access mode of file system socket nodes.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.socket.html#SocketMode=
+    pub socket_mode: Option<u32>,
+
+    /// Access mode of directories created for file system socket nodes.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.socket.html#DirectoryMode=
+    pub directory_mode: Option<u32>,
+
+    /// Whether a service instance is spawned for each incoming connection.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.socket.html#Accept=
+    pub accept: Option<bool>,
+
+    /// Whether the special file or FIFO is opened for writing.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.socket.html#Writable=
+    pub writable: Option<bool>,
+
+    /// Whether to flush the socket before activating the service.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.socket.html#FlushPending=
+    pub flush_pending: Option<bool>,
+
+    /// Maximum number of concurrent connections for Accept=yes sockets.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.socket.html#MaxConnections=
+    pub max_connections: Option<u32>,
+
+    /// Maximum number of concurrent connections per source address.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.socket.html#MaxConnectionsPerSource=
+    pub max_connections_per_source: Option<u32>,
+
+    /// Enable TCP keep-alive.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.socket.html#KeepAlive=
+    pub keep_alive: Option<bool>,
+
+    /// Disable Nagle's algorithm.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.socket.html#NoDelay=
+    pub no_delay: Option<bool>,
+
+    /// Priority of traffic sent on the socket.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.socket.html#Priority=
+    pub priority: Option<i32>,
+
+    /// Receive buffer size.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.socket.html#ReceiveBuffer=
+    pub receive_buffer: Option<ByteSize>,
+
+    /// Send buffer size.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.socket.html#ReceiveBuffer=
+    pub send_buffer: Option<ByteSize>,
+
+    /// Enable SO_PASSCRED.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.socket.html#PassCredentials=
+    pub pass_credentials: Option<bool>,
+
+    /// Enable SO_PASSSEC.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.socket.html#PassSecurity=
+    pub pass_security: Option<bool>,
+
+    /// Enable SO_REUSEPORT.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.socket.html#ReusePort=
+    pub reuse_port: Option<bool>,
+
+    /// Allow binding to addresses not configured locally.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.socket.html#FreeBind=
+    pub free_bind: Option<bool>,
+
+    /// Enable IP_TRANSPARENT.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.socket.html#Transparent=
+    pub transparent: Option<bool>,
+
+    /// Enable SO_BROADCAST.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.socket.html#Broadcast=
+    pub broadcast: Option<bool>,
+
+    /// Remove file system nodes when the socket is stopped.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.socket.html#RemoveOnStop=
+    pub remove_on_stop: Option<bool>,
+
+    /// Symlinks to create pointing to the file system socket.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.socket.html#Symlinks=
+    pub symlinks: Option<Vec<PathBuf>>,
+
+    /// Name of the file descriptors passed to the service.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.socket.html#FileDescriptorName=
+    pub file_descriptor_name: Option<String>,
+
+    /// Service unit to activate on incoming traffic.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.socket.html#Service=
+    pub service: Option<String>,
+
+    /// Interval used by the activation rate limit.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.socket.html#TriggerLimitIntervalSec=
+    pub trigger_limit_interval_sec: Option<String>,
+
+    /// Number of activations allowed within the rate limit interval.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.socket.html#TriggerLimitIntervalSec=
+    pub trigger_limit_burst: Option<u32>,
+
+    /// Commands executed before the sockets are created and bound.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.socket.html#ExecStartPre=
+    pub exec_start_pre: Option<Vec<String>>,
+
+    /// Commands executed after the sockets are created and bound.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.socket.html#ExecStartPre=
+    pub exec_start_post: Option<Vec<String>>,
+
+    /// Commands executed before the sockets are closed.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.socket.html#ExecStopPre=
+    pub exec_stop_pre: Option<Vec<String>>,
+
+    /// Commands executed after the sockets are closed.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.socket.html#ExecStopPre=
+    pub exec_stop_post: Option<Vec<String>>,
+
+    /// Time to wait for the commands to finish.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.socket.html#TimeoutSec=
+    pub timeout_sec: Option<String>,
+
+    pub exec: Option<Exec>,
+
+    pub resource_control: Option<ResourceControl>,
+
+    pub kill: Option<Kill>,
+}
+
+impl fmt::Display for Socket {
+    fn fmt(&self, buf: &mut fmt::Formatter<'_>) -> fmt::Result {
+        buf.write_str("[Socket]\n")?;
+        // Listeners
+        write_vec_multi!(buf, self.listen_stream, "ListenStream");
+        write_vec_multi!(buf, self.listen_datagram, "ListenDatagram");
+        write_vec_multi!(buf, self.listen_sequential_packet, "ListenSequentialPacket");
+        write_path_multi!(buf, self.listen_fifo, "ListenFIFO");
+        write_path_multi!(buf, self.listen_special, "ListenSpecial");
+        write_vec_multi!(buf, self.listen_netlink, "ListenNetlink");
+        write_vec_multi!(buf, self.listen_message_queue, "ListenMessageQueue");
+
+        // Socket options
+        write_option!(buf, self.bind_ipv6_only, "BindIPv6Only");
+        write_option!(buf, self.backlog, "Backlog");
+        write_option!(buf, self.bind_to_device, "BindToDevice");
+        write_option!(buf, self.socket_user, "SocketUser");
+        write_option!(buf, self.socket_group, "SocketGroup");
+        write_mode!(buf, self.socket_mode, "SocketMode");
+        write_mode!(buf, self.directory_mode, "DirectoryMode");
+        write_bool!(buf, self.accept, "Accept");
+        write_bool!(buf, self.writable, "Writable");
+        write_bool!(buf, self.flush_pending, "FlushPending");
+        write_option!(buf, self.max_connections, "MaxConnections");
+        write_option!(
+            buf,
+            self.max_connections_per_source,
+            "MaxConnectionsPerSource"
+        );
+        write_bool!(buf, self.keep_alive, "KeepAlive");
+        write_bool!(buf, self.no_delay, "NoDelay");
+        write_option!(buf, self.priority, "Priority");
+        write_option!(buf, self.receive_buffer, "ReceiveBuffer");
+        write_option!(buf, self.send_buffer, "SendBuffer");
+        write_bool!(buf, self.pass_credentials, "PassCredentials");
+        write_bool!(buf, self.pass_security, "PassSecurity");
+        write_bool!(buf, self.reuse_port, "ReusePort");
+        write_bool!(buf, self.free_bind, "FreeBind");
+        write_bool!(buf, self.transparent, "Transparent");
+        write_bool!(buf, self.broadcast, "Broadcast");
+        write_bool!(buf, self.remove_on_stop, "RemoveOnStop");
+        if let Some(ref symlinks) = self.symlinks {
+            let symlinks = symlinks
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect::<Vec<_>>();
+            writeln!(buf, "Symlinks={}", symlinks.join(" "))?;
+        }
+        write_option!(buf, self.file_descriptor_name, "FileDescriptorName");
+
+        // Activation
+        write_option!(buf, self.service, "Service");
+        write_option!(
+            buf,
+            self.trigger_limit_interval_sec,
+            "TriggerLimitIntervalSec"
+        );
+        write_option!(buf, self.trigger_limit_burst, "TriggerLimitBurst");
+
+        // Commands
+        write_vec_multi!(buf, self.exec_start_pre, "ExecStartPre");
+        write_vec_multi!(buf, self.exec_start_post, "ExecStartPost");
+        write_vec_multi!(buf, self.exec_stop_pre, "ExecStopPre");
+        write_vec_multi!(buf, self.exec_stop_post, "ExecStopPost");
+        write_option!(buf, self.timeout_sec, "TimeoutSec");
+
+        if let Some(ref kill) = self.kill {
+            kill.fmt(buf)?;
+        }
+        if let Some(ref resource_control) = self.resource_control {
+            resource_control.fmt(buf)?;
+        }
+        if let Some(ref exec) = self.exec {
+            exec.fmt(buf)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Socket {
+    /// Creates a new empty `Socket` section.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Validate the socket configuration according to systemd specifications
+    pub fn validate(&self) -> Result<(), String> {
+        let listeners = [
+            self.listen_stream.as_ref().map(Vec::len),
+            self.listen_datagram.as_ref().map(Vec::len),
+            self.listen_sequential_packet.as_ref().map(Vec::len),
+            self.listen_fifo.as_ref().map(Vec::len),
+            self.listen_special.as_ref().map(Vec::len),
+            self.listen_netlink.as_ref().map(Vec::len),
+            self.listen_message_queue.as_ref().map(Vec::len),
+        ];
+        if listeners.iter().flatten().sum::<usize>() == 0 {
+            return Err("Socket must define at least one Listen*= setting".to_string());
+        }
+
+        for (paths, key) in [
+            (&self.listen_fifo, "ListenFIFO"),
+            (&self.listen_special, "ListenSpecial"),
+            (&self.symlinks, "Symlinks"),
+        ] {
+            for path in paths.iter().flatten() {
+                if !path.is_absolute() {
+                    return Err(format!(
+                        "{} path '{}' must be absolute",
+                        key,
+                        path.display()
+                    ));
+                }
+            }
+        }
+
+        for (mode, key) in [
+            (self.socket_mode, "SocketMode"),
+            (self.directory_mode, "DirectoryMode"),
+        ] {
+            if let Some(mode) = mode
+                && mode > 0o7777
+            {
+                return Err(format!("{} {:o} is not a valid file mode", key, mode));
+            }
+        }
+
+        if let Some(ref service) = self.service
+            && !service.ends_with(".service")
+        {
+            return Err(format!("Service '{}' must end with '.service'", service));
+        }
+
+        if self.accept == Some(true) && self.service.is_some() {
+            return Err("Service= cannot be used with Accept=yes".to_string());
+        }
+
+        if let Some(ref kill) = self.kill {
+            kill.validate()?;
+        }
+        if let Some(ref resource_control) = self.resource_control {
+            resource_control.validate()?;
+        }
+        if let Some(ref exec) = self.exec {
+            exec.validate()?;
+        }
+
+        Ok(())
+    }
+
+    // Builder pattern setters
+    pub fn listen_stream(mut self, value: Vec<impl Into<String>>) -> Self {
+        self.listen_stream = Some(value.into_iter().map(|s| s.into()).collect());
+        self
+    }
+
+    pub fn listen_datagram(mut self, value: Vec<impl Into<String>>) -> Self {
+        self.listen_datagram = Some(value.into_iter().map(|s| s.into()).collect());
+        self
+    }
+
+    pub fn listen_sequential_packet(mut self, value: Vec<impl Into<String>>) -> Self {
+        self.listen_sequential_packet = Some(value.into_iter().map(|s| s.into()).collect());
+        self
+    }
+
+    pub fn listen_fifo(mut self, value: Vec<PathBuf>) -> Self {
+        self.listen_fifo = Some(value);
+        self
+    }
+
+    pub fn listen_special(mut self, value: Vec<PathBuf>) -> Self {
+        self.listen_special = Some(value);
+        self
+    }
+
+    pub fn listen_netlink(mut self, value: Vec<impl Into<String>>) -> Self {
+        self.listen_netlink = Some(value.into_iter().map(|s| s.into()).collect());
+        self
+    }
+
+    pub fn listen_message_queue(mut self, value: Vec<impl Into<String>>) -> Self {
+        self.listen_message_queue = Some(value.into_iter().map(|s| s.into()).collect());
+        self
+    }
+
+    pub fn bind_ipv6_only(mut self, value: BindIpv6Only) -> Self {
+        self.bind_ipv6_only = Some(value);
+        self
+    }
+
+    pub fn backlog(mut self, value: u32) -> Self {
+        self.backlog = Some(value);
+        self
+    }
+
+    pub fn bind_to_device(mut self, value: impl Into<String>) -> Self {
+        self.bind_to_device = Some(value.into());
+        self
+    }
+
+    pub fn socket_user(mut self, value: impl Into<String>) -> Self {
+        self.socket_user = Some(value.into());
+        self
+    }
+
+    pub fn socket_group(mut self, value: impl Into<String>) -> Self {
+        self.socket_group = Some(value.into());
+        self
+    }
+
+    pub fn socket_mode(mut self, value: u32) -> Self {
+        self.socket_mode = Some(value);
+        self
+    }
+
+    pub fn directory_mode(mut self, value: u32) -> Self {
+        self.directory_mode = Some(value);
+        self
+    }
+
+    pub fn accept(mut self, value: bool) -> Self {
+        self.accept = Some(value);
+        self
+    }
+
+    pub fn writable(mut self, value: bool) -> Self {
+        self.writable = Some(value);
+        self
+    }
+
+    pub fn flush_pending(mut self, value: bool) -> Self {
+        self.flush_pending = Some(value);
+        self
+    }
+
+    pub fn max_connections(mut self, value: u32) -> Self {
+        self.max_connections = Some(value);
+        self
+    }
+
+    pub fn max_connections_per_source(mut self, value: u32) -> Self {
+        self.max_connections_per_source = Some(value);
+        self
+    }
+
+    pub fn keep_alive(mut self, value: bool) -> Self {
+        self.keep_alive = Some(value);
+        self
+    }
+
+    pub fn no_delay(mut self, value: bool) -> Self {
+        self.no_delay = Some(value);
+        self
+    }
+
+    pub fn priority(mut self, value: i32) -> Self {
+        self.priority = Some(value);
+        self
+    }
+
+    pub fn receive_buffer(mut self, value: ByteSize) -> Self {
+        self.receive_buffer = Some(value);
+        self
+    }
+
+    pub fn send_buffer(mut self, value: ByteSize) -> Self {
+        self.send_buffer = Some(value);
+        self
+    }
+
+    pub fn pass_credentials(mut self, value: bool) -> Self {
+        self.pass_credentials = Some(value);
+        self
+    }
+
+    pub fn pass_security(mut self, value: bool) -> Self {
+        self.pass_security = Some(value);
+        self
+    }
+
+    pub fn reuse_port(mut self, value: bool) -> Self {
+        self.reuse_port = Some(value);
+        self
+    }
+
+    pub fn free_bind(mut self, value: bool) -> Self {
+        self.free_bind = Some(value);
+        self
+    }
+
+    pub fn transparent(mut self, value: bool) -> Self {
+        self.transparent = Some(value);
+        self
+    }
+
+    pub fn broadcast(mut self, value: bool) -> Self {
+        self.broadcast = Some(value);
+        self
+    }
+
+    pub fn remove_on_stop(mut self, value: bool) -> Self {
+        self.remove_on_stop = Some(value);
+        self
+    }
+
+    pub fn symlinks(mut self, value: Vec<PathBuf>) -> Self {
+        self.symlinks = Some(value);
+        self
+    }
+
+    pub fn file_descriptor_name(mut self, value: impl Into<String>) -> Self {
+        self.file_descriptor_name = Some(value.into());
+        self
+    }
+
+    pub fn service(mut self, value: impl Into<String>) -> Self {
+        self.service = Some(value.into());
+        self
+    }
+
+    pub fn trigger_limit_interval_sec(mut self, value: impl Into<String>) -> Self {
+        self.trigger_limit_interval_sec = Some(value.into());
+        self
+    }
+
+    pub fn trigger_limit_burst(mut self, value: u32) -> Self {
+        self.trigger_limit_burst = Some(value);
+        self
+    }
+
+    pub fn exec_start_pre(mut self, value: Vec<impl Into<String>>) -> Self {
+        self.exec_start_pre = Some(value.into_iter().map(|s| s.into()).collect());
+        self
+    }
+
+    pub fn exec_start_post(mut self, value: Vec<impl Into<String>>) -> Self {
+        self.exec_start_post = Some(value.into_iter().map(|s| s.into()).collect());
+        self
+    }
+
+    pub fn exec_stop_pre(mut self, value: Vec<impl Into<String>>) -> Self {
+        self.exec_stop_pre = Some(value.into_iter().map(|s| s.into()).collect());
+        self
+    }
+
+    pub fn exec_stop_post(mut self, value: Vec<impl Into<String>>) -> Self {
+        self.exec_stop_post = Some(value.into_iter().map(|s| s.into()).collect());
+        self
+    }
+
+    pub fn timeout_sec(mut self, value: impl Into<String>) -> Self {
+        self.timeout_sec = Some(value.into());
+        self
+    }
+
+    pub fn exec(mut self, value: Exec) -> Self {
+        self.exec = Some(value);
+        self
+    }
+
+    pub fn resource_control(mut self, value: ResourceControl) -> Self {
+        self.resource_control = Some(value);
+        self
+    }
+
+    pub fn kill(mut self, value: Kill) -> Self {
+        self.kill = Some(value);
+        self
+    }
+}
+
+/// Configuration for a systemd socket unit.
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.socket.html
+#[derive(Debug, Clone)]
+pub struct SocketConfig {
+    pub name: String,
+    pub unit: Unit,
+    pub socket: Socket,
+    pub install: Install,
+}
+
+impl SocketConfig {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            unit: Unit::default(),
+            socket: Socket::default(),
+            install: Install::default(),
+        }
+    }
+
+    pub fn name(mut self, s: impl Into<String>) -> Self {
+        self.name = s.into();
+        self
+    }
+
+    pub fn unit(mut self, unit: Unit) -> Self {
+        self.unit = unit;
+        self
+    }
+
+    pub fn socket(mut self, socket: Socket) -> Self {
+        self.socket = socket;
+        self
+    }
+
+    pub fn install(mut self, install: Install) -> Self {
+        self.install = install;
+        self
+    }
+
+    pub fn validate(&self) -> crate::Result<()> {
+        self.unit.validate().map_err(Error::ValidationError)?;
+        self.socket.validate().map_err(Error::ValidationError)?;
+        self.install.validate().map_err(Error::ValidationError)?;
+        Ok(())
+    }
+
+    /// Returns warnings about settings that pass validation but are discouraged by systemd.
+    pub fn warnings(&self) -> Vec<String> {
+        self.socket
+            .kill
+            .as_ref()
+            .map(Kill::warnings)
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn unit_name(&self) -> String {
+        format!("{}.socket", self.name)
+    }
+}
+
+impl fmt::Display for SocketConfig {
+    fn fmt(&self, buf: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.unit.fmt(buf)?;
+        self.socket.fmt(buf)?;
+        self.install.fmt(buf)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_socket_unit() {
+        let config = SocketConfig::new("demo")
+            .socket(
+                Socket::new()
+                    .listen_stream(vec!["127.0.0.1:8080", "/run/demo.sock"])
+                    .socket_mode(0o660)
+                    .service("demo.service"),
+            )
+            .install(Install::default().wanted_by(vec!["sockets.target"]));
+
+        assert!(config.validate().is_ok());
+        assert_eq!(config.unit_name(), "demo.socket");
+        assert_eq!(
+            config.to_string(),
+            "[Unit]\n[Socket]\nListenStream=127.0.0.1:8080\nListenStream=/run/demo.sock\n\
+             SocketMode=0660\nService=demo.service\n\
+             [Install]\nWantedBy=sockets.target\n"
+        );
+    }
+
+    #[test]
+    fn socket_needs_a_listener() {
+        assert_eq!(
+            Socket::new().validate(),
+            Err("Socket must define at least one Listen*= setting".to_string())
+        );
+    }
+}
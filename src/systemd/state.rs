@@ -0,0 +1,577 @@
+#[cfg(feature = "dbus")]
+use super::DbusManager;
+use super::Status;
+use super::UnitFileState;
+#[cfg(feature = "dbus")]
+use super::UnitType;
+use crate::Result;
+use crate::error::Error;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
+
+/// Properties read by [`Systemd::state`](super::Systemd::state).
+pub(crate) const PROPERTIES: &[&str] = &[
+    "LoadState",
+    "ActiveState",
+    "SubState",
+    "UnitFileState",
+    "MainPID",
+    "ExecMainStatus",
+    "ExecMainCode",
+    "Result",
+    "NRestarts",
+    // Microseconds on CLOCK_MONOTONIC, which unlike ActiveEnterTimestamp is printed
+    // the same by every systemd release, without a locale dependent format
+    "ActiveEnterTimestampMonotonic",
+    "MemoryCurrent",
+    "CPUUsageNSec",
+    "InvocationID",
+];
+
+/// Value `systemctl show` prints for unset numeric properties.
+const NOT_SET: &str = "[not set]";
+
+/// Whether the unit configuration was loaded by the service manager.
+/// https://www.freedesktop.org/software/systemd/man/latest/org.freedesktop.systemd1.html#Properties2
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoadState {
+    Loaded,
+    /// There is no unit file for the unit.
+    NotFound,
+    /// The unit file has a setting that could not be parsed.
+    BadSetting,
+    Error,
+    Masked,
+    Stub,
+    Merged,
+    Other(String),
+}
+
+impl fmt::Display for LoadState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadState::Loaded => write!(f, "loaded"),
+            LoadState::NotFound => write!(f, "not-found"),
+            LoadState::BadSetting => write!(f, "bad-setting"),
+            LoadState::Error => write!(f, "error"),
+            LoadState::Masked => write!(f, "masked"),
+            LoadState::Stub => write!(f, "stub"),
+            LoadState::Merged => write!(f, "merged"),
+            LoadState::Other(state) => write!(f, "{}", state),
+        }
+    }
+}
+
+impl From<&str> for LoadState {
+    fn from(state: &str) -> Self {
+        match state {
+            "loaded" => LoadState::Loaded,
+            "not-found" => LoadState::NotFound,
+            "bad-setting" => LoadState::BadSetting,
+            "error" => LoadState::Error,
+            "masked" => LoadState::Masked,
+            "stub" => LoadState::Stub,
+            "merged" => LoadState::Merged,
+            other => LoadState::Other(other.to_string()),
+        }
+    }
+}
+
+/// High-level activation state of a unit. The unit type specific state is in [`UnitState::sub_state`].
+/// https://www.freedesktop.org/software/systemd/man/latest/org.freedesktop.systemd1.html#Properties2
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ActiveState {
+    Active,
+    Reloading,
+    Inactive,
+    Failed,
+    Activating,
+    Deactivating,
+    /// The unit is inactive while the service manager cleans up its resources.
+    Maintenance,
+    /// New mounts are being propagated into the unit's namespace.
+    Refreshing,
+    Other(String),
+}
+
+impl fmt::Display for ActiveState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ActiveState::Active => write!(f, "active"),
+            ActiveState::Reloading => write!(f, "reloading"),
+            ActiveState::Inactive => write!(f, "inactive"),
+            ActiveState::Failed => write!(f, "failed"),
+            ActiveState::Activating => write!(f, "activating"),
+            ActiveState::Deactivating => write!(f, "deactivating"),
+            ActiveState::Maintenance => write!(f, "maintenance"),
+            ActiveState::Refreshing => write!(f, "refreshing"),
+            ActiveState::Other(state) => write!(f, "{}", state),
+        }
+    }
+}
+
+impl From<&str> for ActiveState {
+    fn from(state: &str) -> Self {
+        match state {
+            "active" => ActiveState::Active,
+            "reloading" => ActiveState::Reloading,
+            "inactive" => ActiveState::Inactive,
+            "failed" => ActiveState::Failed,
+            "activating" => ActiveState::Activating,
+            "deactivating" => ActiveState::Deactivating,
+            "maintenance" => ActiveState::Maintenance,
+            "refreshing" => ActiveState::Refreshing,
+            other => ActiveState::Other(other.to_string()),
+        }
+    }
+}
+
+/// Result of the last run of a unit, e.g. why a service failed.
+/// https://www.freedesktop.org/software/systemd/man/latest/org.freedesktop.systemd1.html#Properties3
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UnitResult {
+    Success,
+    /// The service manager ran out of resources, e.g. it could not fork.
+    Resources,
+    /// The service violated its protocol, e.g. it did not write its PID file.
+    Protocol,
+    Timeout,
+    /// The main process exited with a non-zero exit status.
+    ExitCode,
+    /// The main process was killed by a signal.
+    Signal,
+    /// The main process dumped core.
+    CoreDump,
+    Watchdog,
+    StartLimitHit,
+    /// The unit was killed by the out-of-memory killer.
+    OomKill,
+    Other(String),
+}
+
+impl fmt::Display for UnitResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnitResult::Success => write!(f, "success"),
+            UnitResult::Resources => write!(f, "resources"),
+            UnitResult::Protocol => write!(f, "protocol"),
+            UnitResult::Timeout => write!(f, "timeout"),
+            UnitResult::ExitCode => write!(f, "exit-code"),
+            UnitResult::Signal => write!(f, "signal"),
+            UnitResult::CoreDump => write!(f, "core-dump"),
+            UnitResult::Watchdog => write!(f, "watchdog"),
+            UnitResult::StartLimitHit => write!(f, "start-limit-hit"),
+            UnitResult::OomKill => write!(f, "oom-kill"),
+            UnitResult::Other(result) => write!(f, "{}", result),
+        }
+    }
+}
+
+impl From<&str> for UnitResult {
+    fn from(result: &str) -> Self {
+        match result {
+            "success" => UnitResult::Success,
+            "resources" => UnitResult::Resources,
+            "protocol" => UnitResult::Protocol,
+            "timeout" => UnitResult::Timeout,
+            "exit-code" => UnitResult::ExitCode,
+            "signal" => UnitResult::Signal,
+            "core-dump" => UnitResult::CoreDump,
+            "watchdog" => UnitResult::Watchdog,
+            "start-limit-hit" => UnitResult::StartLimitHit,
+            "oom-kill" => UnitResult::OomKill,
+            other => UnitResult::Other(other.to_string()),
+        }
+    }
+}
+
+/// How the main process of a service ended, the `ExecMainCode` property.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitReason {
+    /// The process exited, [`UnitState::exec_main_status`] is its exit status.
+    Exited,
+    /// The process was killed, [`UnitState::exec_main_status`] is the signal number.
+    Killed,
+    /// The process was killed and dumped core, [`UnitState::exec_main_status`] is the signal number.
+    Dumped,
+}
+
+impl fmt::Display for ExitReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExitReason::Exited => write!(f, "exited"),
+            ExitReason::Killed => write!(f, "killed"),
+            ExitReason::Dumped => write!(f, "dumped"),
+        }
+    }
+}
+
+impl ExitReason {
+    /// Maps the `si_code` of the `SIGCHLD` the service manager received, `None` if the
+    /// process has not ended.
+    fn from_code(code: i32) -> Option<Self> {
+        match code {
+            1 => Some(ExitReason::Exited),
+            2 => Some(ExitReason::Killed),
+            3 => Some(ExitReason::Dumped),
+            _ => None,
+        }
+    }
+}
+
+/// Runtime state of a unit, as reported by the service manager.
+/// Properties that do not apply to the unit type, or are not set, are `None` or zero.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnitState {
+    pub load_state: LoadState,
+    pub active_state: ActiveState,
+    /// Unit type specific state, e.g. `running`, `exited` or `auto-restart` for services.
+    pub sub_state: String,
+    /// Enablement state of the unit file, `None` for units without one, e.g. transient units.
+    pub unit_file_state: Option<UnitFileState>,
+    /// PID of the main process of a service, `None` if it is not running.
+    pub main_pid: Option<u32>,
+    /// Exit status or signal number of the last main process, depending on [`UnitState::exec_main_code`].
+    pub exec_main_status: i32,
+    pub exec_main_code: Option<ExitReason>,
+    pub result: Option<UnitResult>,
+    /// Number of automatic restarts since the service was started.
+    pub restarts: u32,
+    /// When the unit last entered the active state.
+    ///
+    /// `systemctl show` reports it on CLOCK_MONOTONIC, which stops while the system is
+    /// suspended, so it is converted relative to the current time and is early by the time
+    /// spent suspended since the unit became active. The D-Bus backend reads the wall clock
+    /// timestamp and does not drift.
+    pub active_enter_timestamp: Option<SystemTime>,
+    /// Memory used by the unit's control group in bytes, `None` without memory accounting.
+    pub memory_current: Option<u64>,
+    /// CPU time used by the unit's control group, `None` without CPU accounting.
+    pub cpu_usage: Option<Duration>,
+    /// ID of the current or last run of the unit, as 32 hexadecimal digits.
+    /// Journal entries of the run carry it in the `INVOCATION_ID` field.
+    pub invocation_id: Option<String>,
+}
+
+impl UnitState {
+    /// Summarizes the state as a [`Status`].
+    pub fn status(&self) -> Status {
+        if self.load_state == LoadState::NotFound {
+            return Status::NotInstalled;
+        }
+        match self.active_state {
+            ActiveState::Active
+            | ActiveState::Reloading
+            | ActiveState::Activating
+            | ActiveState::Refreshing => Status::Running,
+            ActiveState::Failed => Status::Failed,
+            ActiveState::Inactive | ActiveState::Deactivating | ActiveState::Maintenance => {
+                Status::Stopped
+            }
+            ActiveState::Other(_) => Status::Unknown,
+        }
+    }
+
+    /// Parses the output of `systemctl show --property=...`, with one `Name=value` line
+    /// per property. Properties the unit type does not have are missing from the output.
+    pub(crate) fn parse(output: &str) -> Result<Self> {
+        let properties = Properties(
+            output
+                .lines()
+                .filter_map(|line| line.split_once('='))
+                .collect(),
+        );
+
+        Ok(Self {
+            load_state: LoadState::from(properties.required("LoadState")?),
+            active_state: ActiveState::from(properties.required("ActiveState")?),
+            sub_state: properties.required("SubState")?.to_string(),
+            unit_file_state: properties
+                .get("UnitFileState")
+                .and_then(UnitFileState::from_name),
+            main_pid: properties.number::<u32>("MainPID")?.filter(|pid| *pid != 0),
+            exec_main_status: properties.number("ExecMainStatus")?.unwrap_or_default(),
+            exec_main_code: properties
+                .number("ExecMainCode")?
+                .and_then(ExitReason::from_code),
+            result: properties.get("Result").map(UnitResult::from),
+            restarts: properties.number("NRestarts")?.unwrap_or_default(),
+            active_enter_timestamp: properties
+                .number::<u64>("ActiveEnterTimestampMonotonic")?
+                .filter(|micros| *micros != 0)
+                .and_then(|micros| monotonic_to_system_time(Duration::from_micros(micros))),
+            memory_current: properties
+                .number("MemoryCurrent")?
+                .filter(|bytes| *bytes != u64::MAX),
+            cpu_usage: properties
+                .number("CPUUsageNSec")?
+                .filter(|nanos| *nanos != u64::MAX)
+                .map(Duration::from_nanos),
+            invocation_id: properties.get("InvocationID").map(str::to_string),
+        })
+    }
+
+    /// Reads the state of `unit` from the properties of its D-Bus object.
+    #[cfg(feature = "dbus")]
+    pub(crate) fn from_dbus(manager: &DbusManager, unit: &str) -> Result<Self> {
+        let mut properties = manager.unit_properties(unit, "org.freedesktop.systemd1.Unit")?;
+        // The service, process and resource properties are on the interface of the unit type
+        if let Some(unit_type) = UnitType::from_unit_name(unit) {
+            properties.extend(manager.unit_properties(unit, type_interface(unit_type))?);
+        }
+        let mut properties = DbusProperties(properties);
+
+        Ok(Self {
+            load_state: LoadState::from(properties.string("LoadState")?.as_str()),
+            active_state: ActiveState::from(properties.string("ActiveState")?.as_str()),
+            sub_state: properties.string("SubState")?,
+            unit_file_state: UnitFileState::from_name(&properties.string("UnitFileState")?),
+            main_pid: properties.value::<u32>("MainPID")?.filter(|pid| *pid != 0),
+            exec_main_status: properties.value("ExecMainStatus")?.unwrap_or_default(),
+            exec_main_code: properties
+                .value("ExecMainCode")?
+                .and_then(ExitReason::from_code),
+            result: Some(properties.string("Result")?)
+                .filter(|result| !result.is_empty())
+                .map(|result| UnitResult::from(result.as_str())),
+            restarts: properties.value("NRestarts")?.unwrap_or_default(),
+            active_enter_timestamp: properties
+                .value::<u64>("ActiveEnterTimestamp")?
+                .filter(|micros| *micros != 0)
+                .map(|micros| SystemTime::UNIX_EPOCH + Duration::from_micros(micros)),
+            memory_current: properties
+                .value::<u64>("MemoryCurrent")?
+                .filter(|bytes| *bytes != u64::MAX),
+            cpu_usage: properties
+                .value::<u64>("CPUUsageNSec")?
+                .filter(|nanos| *nanos != u64::MAX)
+                .map(Duration::from_nanos),
+            invocation_id: properties
+                .value::<Vec<u8>>("InvocationID")?
+                .filter(|id| !id.is_empty())
+                .map(|id| id.iter().map(|byte| format!("{:02x}", byte)).collect()),
+        })
+    }
+}
+
+impl fmt::Display for UnitState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.load_state != LoadState::Loaded {
+            write!(f, "{}, ", self.load_state)?;
+        }
+        write!(f, "{} ({})", self.active_state, self.sub_state)?;
+        if let Some(pid) = self.main_pid {
+            write!(f, ", main PID {}", pid)?;
+        }
+        if let Some(ref result) = self.result
+            && *result != UnitResult::Success
+        {
+            write!(f, ", result {}", result)?;
+        }
+        if let Some(code) = self.exec_main_code
+            && (code != ExitReason::Exited || self.exec_main_status != 0)
+        {
+            write!(f, ", main process {} with {}", code, self.exec_main_status)?;
+        }
+        if self.restarts > 0 {
+            write!(f, ", {} restart(s)", self.restarts)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the D-Bus interface with the properties specific to a unit type.
+#[cfg(feature = "dbus")]
+fn type_interface(unit_type: UnitType) -> &'static str {
+    match unit_type {
+        UnitType::Service => "org.freedesktop.systemd1.Service",
+        UnitType::Socket => "org.freedesktop.systemd1.Socket",
+        UnitType::Timer => "org.freedesktop.systemd1.Timer",
+        UnitType::Path => "org.freedesktop.systemd1.Path",
+        UnitType::Mount => "org.freedesktop.systemd1.Mount",
+        UnitType::Automount => "org.freedesktop.systemd1.Automount",
+        UnitType::Swap => "org.freedesktop.systemd1.Swap",
+        UnitType::Target => "org.freedesktop.systemd1.Target",
+        UnitType::Slice => "org.freedesktop.systemd1.Slice",
+    }
+}
+
+/// Converts a point in time on CLOCK_MONOTONIC to wall clock time, relative to now.
+/// Time spent suspended after `monotonic` is not counted, so the result is that much too early.
+fn monotonic_to_system_time(monotonic: Duration) -> Option<SystemTime> {
+    let mut now = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    // SAFETY: `now` is a valid timespec for clock_gettime to write to
+    if unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut now) } != 0 {
+        return None;
+    }
+    let now = Duration::new(u64::try_from(now.tv_sec).ok()?, now.tv_nsec as u32);
+    SystemTime::now().checked_sub(now.checked_sub(monotonic)?)
+}
+
+/// Properties printed by `systemctl show`, by name.
+struct Properties<'a>(HashMap<&'a str, &'a str>);
+
+impl<'a> Properties<'a> {
+    /// Returns the value of a property, `None` if it is missing or empty.
+    fn get(&self, name: &str) -> Option<&'a str> {
+        self.0.get(name).copied().filter(|value| !value.is_empty())
+    }
+
+    fn required(&self, name: &str) -> Result<&'a str> {
+        self.get(name).ok_or_else(|| {
+            Error::CommandError(format!("Property {} is missing from the unit state", name))
+        })
+    }
+
+    /// Parses a numeric property, `None` if it is missing, empty or not set.
+    fn number<T: FromStr>(&self, name: &str) -> Result<Option<T>> {
+        match self.get(name) {
+            None | Some(NOT_SET) => Ok(None),
+            Some(value) => value
+                .parse()
+                .map(Some)
+                .map_err(|_| invalid_value(name, value)),
+        }
+    }
+}
+
+/// Properties of a D-Bus object, by name.
+#[cfg(feature = "dbus")]
+struct DbusProperties(HashMap<String, zbus::zvariant::OwnedValue>);
+
+#[cfg(feature = "dbus")]
+impl DbusProperties {
+    /// Takes a property, `None` if the object does not have it.
+    fn value<T: TryFrom<zbus::zvariant::OwnedValue>>(&mut self, name: &str) -> Result<Option<T>> {
+        self.0
+            .remove(name)
+            .map(|value| {
+                T::try_from(value).map_err(|_| {
+                    Error::CommandError(format!("Property {} has an unexpected type", name))
+                })
+            })
+            .transpose()
+    }
+
+    /// Takes a string property, empty if the object does not have it.
+    fn string(&mut self, name: &str) -> Result<String> {
+        Ok(self.value(name)?.unwrap_or_default())
+    }
+}
+
+fn invalid_value(name: &str, value: &str) -> Error {
+    Error::CommandError(format!(
+        "Property {} has an invalid value '{}'",
+        name, value
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_active_service() {
+        let state = UnitState::parse(
+            "MainPID=812\nExecMainCode=0\nExecMainStatus=0\nNRestarts=0\n\
+             MemoryCurrent=7340032\nCPUUsageNSec=153104000\nResult=success\n\
+             ActiveEnterTimestampMonotonic=5120033\nLoadState=loaded\nActiveState=active\n\
+             SubState=running\nUnitFileState=enabled\n\
+             InvocationID=4b3b1c1e0d6f4e8f9a4b2c1d0e9f8a7b\n",
+        )
+        .unwrap();
+
+        assert_eq!(state.status(), Status::Running);
+        assert_eq!(state.load_state, LoadState::Loaded);
+        assert_eq!(state.sub_state, "running");
+        assert_eq!(state.unit_file_state, Some(UnitFileState::Enabled));
+        assert_eq!(state.main_pid, Some(812));
+        assert_eq!(state.exec_main_code, None);
+        assert_eq!(state.result, Some(UnitResult::Success));
+        assert_eq!(state.memory_current, Some(7_340_032));
+        assert_eq!(state.cpu_usage, Some(Duration::from_nanos(153_104_000)));
+        assert!(
+            state
+                .active_enter_timestamp
+                .is_some_and(|time| time <= SystemTime::now())
+        );
+        assert_eq!(
+            state.invocation_id.as_deref(),
+            Some("4b3b1c1e0d6f4e8f9a4b2c1d0e9f8a7b")
+        );
+        assert_eq!(state.to_string(), "active (running), main PID 812");
+    }
+
+    #[test]
+    fn parse_failed_service() {
+        let state = UnitState::parse(
+            "MainPID=0\nExecMainCode=1\nExecMainStatus=3\nNRestarts=5\n\
+             MemoryCurrent=[not set]\nCPUUsageNSec=[not set]\nResult=exit-code\n\
+             ActiveEnterTimestampMonotonic=0\nLoadState=loaded\nActiveState=failed\n\
+             SubState=failed\nUnitFileState=disabled\nInvocationID=\n",
+        )
+        .unwrap();
+
+        assert_eq!(state.status(), Status::Failed);
+        assert_eq!(state.main_pid, None);
+        assert_eq!(state.exec_main_code, Some(ExitReason::Exited));
+        assert_eq!(state.exec_main_status, 3);
+        assert_eq!(state.result, Some(UnitResult::ExitCode));
+        assert_eq!(state.restarts, 5);
+        assert_eq!(state.active_enter_timestamp, None);
+        assert_eq!(state.memory_current, None);
+        assert_eq!(state.cpu_usage, None);
+        assert_eq!(state.invocation_id, None);
+        assert_eq!(
+            state.to_string(),
+            "failed (failed), result exit-code, main process exited with 3, 5 restart(s)"
+        );
+    }
+
+    #[test]
+    fn parse_not_found_unit() {
+        let state = UnitState::parse(
+            "MainPID=0\nExecMainCode=0\nExecMainStatus=0\nNRestarts=0\n\
+             MemoryCurrent=[not set]\nCPUUsageNSec=[not set]\nResult=success\n\
+             ActiveEnterTimestampMonotonic=0\nLoadState=not-found\nActiveState=inactive\n\
+             SubState=dead\nUnitFileState=\nInvocationID=\n",
+        )
+        .unwrap();
+
+        assert_eq!(state.status(), Status::NotInstalled);
+        assert_eq!(state.load_state, LoadState::NotFound);
+        assert_eq!(state.unit_file_state, None);
+        assert_eq!(state.to_string(), "not-found, inactive (dead)");
+    }
+
+    #[test]
+    fn parse_target_without_service_properties() {
+        let state = UnitState::parse(
+            "ActiveEnterTimestampMonotonic=4981223\nLoadState=loaded\nActiveState=active\n\
+             SubState=active\nUnitFileState=static\n\
+             InvocationID=0f2d9c4a6b8e4c1d9e7f3a5b2c4d6e8f\n",
+        )
+        .unwrap();
+
+        assert_eq!(state.status(), Status::Running);
+        assert_eq!(state.unit_file_state, Some(UnitFileState::Static));
+        assert_eq!(state.main_pid, None);
+        assert_eq!(state.result, None);
+        assert_eq!(state.restarts, 0);
+        assert!(state.active_enter_timestamp.is_some());
+    }
+
+    #[test]
+    fn parse_rejects_invalid_output() {
+        assert!(UnitState::parse("ActiveState=active\nSubState=running\n").is_err());
+        assert!(
+            UnitState::parse("LoadState=loaded\nActiveState=active\nSubState=running\nMainPID=x\n")
+                .is_err()
+        );
+    }
+}
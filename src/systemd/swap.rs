@@ -0,0 +1,217 @@
+use super::exec::Exec;
+use super::install::Install;
+use super::kill::Kill;
+use super::resource_control::ResourceControl;
+use super::unit::Unit;
+use super::unit_file::escape_path;
+use crate::error::Error;
+use std::fmt;
+use std::path::PathBuf;
+
+/// Swap section options
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.swap.html#Options
+#[derive(Debug, Clone, Default)]
+pub struct Swap {
+    /// Absolute path of the device node or file to use for paging. The unit name is derived from it.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.swap.html#What=
+    pub what: PathBuf,
+
+    /// Swap priority to use when activating the swap device or file.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.swap.html#Priority=
+    pub priority: Option<i32>,
+
+    /// Options passed to swapon.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.swap.html#Options=
+    pub options: Option<Vec<String>>,
+
+    /// Time to wait for the swapon command to finish.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.swap.html#TimeoutSec=
+    pub timeout_sec: Option<String>,
+
+    pub exec: Option<Exec>,
+
+    pub resource_control: Option<ResourceControl>,
+
+    pub kill: Option<Kill>,
+}
+
+impl fmt::Display for Swap {
+    fn fmt(&self, buf: &mut fmt::Formatter<'_>) -> fmt::Result {
+        buf.write_str("[Swap]\n")?;
+        writeln!(buf, "What={}", self.what.display())?;
+        write_option!(buf, self.priority, "Priority");
+        if let Some(ref options) = self.options {
+            writeln!(buf, "Options={}", options.join(","))?;
+        }
+        write_option!(buf, self.timeout_sec, "TimeoutSec");
+
+        if let Some(ref kill) = self.kill {
+            kill.fmt(buf)?;
+        }
+        if let Some(ref resource_control) = self.resource_control {
+            resource_control.fmt(buf)?;
+        }
+        if let Some(ref exec) = self.exec {
+            exec.fmt(buf)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Swap {
+    /// Creates a `Swap` section paging to `what`.
+    pub fn new(what: impl Into<PathBuf>) -> Self {
+        Self {
+            what: what.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Validate the swap configuration according to systemd specifications
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.what.is_absolute() {
+            return Err(format!(
+                "Swap What= '{}' must be an absolute path",
+                self.what.display()
+            ));
+        }
+        if let Some(priority) = self.priority
+            && !(-1..=32767).contains(&priority)
+        {
+            return Err(format!(
+                "Swap priority {} must be between -1 and 32767",
+                priority
+            ));
+        }
+
+        if let Some(ref kill) = self.kill {
+            kill.validate()?;
+        }
+        if let Some(ref resource_control) = self.resource_control {
+            resource_control.validate()?;
+        }
+        if let Some(ref exec) = self.exec {
+            exec.validate()?;
+        }
+
+        Ok(())
+    }
+
+    // Builder pattern setters
+    pub fn what(mut self, value: impl Into<PathBuf>) -> Self {
+        self.what = value.into();
+        self
+    }
+
+    pub fn priority(mut self, value: i32) -> Self {
+        self.priority = Some(value);
+        self
+    }
+
+    pub fn options(mut self, value: Vec<impl Into<String>>) -> Self {
+        self.options = Some(value.into_iter().map(|s| s.into()).collect());
+        self
+    }
+
+    pub fn timeout_sec(mut self, value: impl Into<String>) -> Self {
+        self.timeout_sec = Some(value.into());
+        self
+    }
+
+    pub fn exec(mut self, value: Exec) -> Self {
+        self.exec = Some(value);
+        self
+    }
+
+    pub fn resource_control(mut self, value: ResourceControl) -> Self {
+        self.resource_control = Some(value);
+        self
+    }
+
+    pub fn kill(mut self, value: Kill) -> Self {
+        self.kill = Some(value);
+        self
+    }
+}
+
+/// Configuration for a systemd swap unit.
+/// The unit name is derived from the swap device, e.g. `/dev/sda5` becomes `dev-sda5.swap`.
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.swap.html
+#[derive(Debug, Clone)]
+pub struct SwapConfig {
+    pub unit: Unit,
+    pub swap: Swap,
+    pub install: Install,
+}
+
+impl SwapConfig {
+    pub fn new(swap: Swap) -> Self {
+        Self {
+            unit: Unit::default(),
+            swap,
+            install: Install::default(),
+        }
+    }
+
+    pub fn unit(mut self, unit: Unit) -> Self {
+        self.unit = unit;
+        self
+    }
+
+    pub fn swap(mut self, swap: Swap) -> Self {
+        self.swap = swap;
+        self
+    }
+
+    pub fn install(mut self, install: Install) -> Self {
+        self.install = install;
+        self
+    }
+
+    pub fn validate(&self) -> crate::Result<()> {
+        self.unit.validate().map_err(Error::ValidationError)?;
+        self.swap.validate().map_err(Error::ValidationError)?;
+        self.install.validate().map_err(Error::ValidationError)?;
+        Ok(())
+    }
+
+    /// Returns warnings about settings that pass validation but are discouraged by systemd.
+    pub fn warnings(&self) -> Vec<String> {
+        self.swap
+            .kill
+            .as_ref()
+            .map(Kill::warnings)
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn unit_name(&self) -> String {
+        format!("{}.swap", escape_path(&self.swap.what))
+    }
+}
+
+impl fmt::Display for SwapConfig {
+    fn fmt(&self, buf: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.unit.fmt(buf)?;
+        self.swap.fmt(buf)?;
+        self.install.fmt(buf)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_swap_unit() {
+        let config = SwapConfig::new(Swap::new("/swapfile").priority(10).options(vec!["discard"]));
+
+        assert!(config.validate().is_ok());
+        assert_eq!(config.unit_name(), "swapfile.swap");
+        assert_eq!(
+            config.to_string(),
+            "[Unit]\n[Swap]\nWhat=/swapfile\nPriority=10\nOptions=discard\n[Install]\n"
+        );
+    }
+}
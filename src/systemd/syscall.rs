@@ -0,0 +1,803 @@
+use std::fmt;
+
+/// System call groups predefined by systemd, usable in place of system call names.
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#SystemCallFilter=
+pub const SYSCALL_GROUPS: &[&str] = &[
+    "@aio",
+    "@basic-io",
+    "@chown",
+    "@clock",
+    "@cpu-emulation",
+    "@debug",
+    "@default",
+    "@file-system",
+    "@io-event",
+    "@ipc",
+    "@keyring",
+    "@known",
+    "@memlock",
+    "@module",
+    "@mount",
+    "@network-io",
+    "@obsolete",
+    "@pkey",
+    "@privileged",
+    "@process",
+    "@raw-io",
+    "@reboot",
+    "@resources",
+    "@sandbox",
+    "@setuid",
+    "@signal",
+    "@swap",
+    "@sync",
+    "@system-service",
+    "@timer",
+];
+
+/// Architecture identifiers accepted by SystemCallArchitectures=.
+const ARCHITECTURES: &[&str] = &[
+    "native",
+    "x86",
+    "x86-64",
+    "x32",
+    "arm",
+    "arm64",
+    "loongarch64",
+    "mips",
+    "mips-le",
+    "mips64",
+    "mips64-le",
+    "mips64-n32",
+    "mips64-le-n32",
+    "parisc",
+    "parisc64",
+    "ppc",
+    "ppc64",
+    "ppc64-le",
+    "riscv64",
+    "s390",
+    "s390x",
+];
+
+/// Names of Linux system calls across the x86, x86-64 and generic syscall tables.
+const SYSCALLS: &[&str] = &[
+    "_llseek",
+    "_newselect",
+    "_sysctl",
+    "accept",
+    "accept4",
+    "access",
+    "acct",
+    "add_key",
+    "adjtimex",
+    "afs_syscall",
+    "alarm",
+    "arch_prctl",
+    "bdflush",
+    "bind",
+    "bpf",
+    "break",
+    "brk",
+    "cacheflush",
+    "cachestat",
+    "capget",
+    "capset",
+    "chdir",
+    "chmod",
+    "chown",
+    "chown32",
+    "chroot",
+    "clock_adjtime",
+    "clock_adjtime64",
+    "clock_getres",
+    "clock_getres_time64",
+    "clock_gettime",
+    "clock_gettime64",
+    "clock_nanosleep",
+    "clock_nanosleep_time64",
+    "clock_settime",
+    "clock_settime64",
+    "clone",
+    "clone3",
+    "close",
+    "close_range",
+    "connect",
+    "copy_file_range",
+    "creat",
+    "create_module",
+    "delete_module",
+    "dup",
+    "dup2",
+    "dup3",
+    "epoll_create",
+    "epoll_create1",
+    "epoll_ctl",
+    "epoll_ctl_old",
+    "epoll_pwait",
+    "epoll_pwait2",
+    "epoll_wait",
+    "epoll_wait_old",
+    "eventfd",
+    "eventfd2",
+    "execve",
+    "execveat",
+    "exit",
+    "exit_group",
+    "faccessat",
+    "faccessat2",
+    "fadvise64",
+    "fadvise64_64",
+    "fallocate",
+    "fanotify_init",
+    "fanotify_mark",
+    "fchdir",
+    "fchmod",
+    "fchmodat",
+    "fchmodat2",
+    "fchown",
+    "fchown32",
+    "fchownat",
+    "fcntl",
+    "fcntl64",
+    "fdatasync",
+    "fgetxattr",
+    "finit_module",
+    "flistxattr",
+    "flock",
+    "fork",
+    "fremovexattr",
+    "fsconfig",
+    "fsetxattr",
+    "fsmount",
+    "fsopen",
+    "fspick",
+    "fstat",
+    "fstat64",
+    "fstatat64",
+    "fstatfs",
+    "fstatfs64",
+    "fsync",
+    "ftime",
+    "ftruncate",
+    "ftruncate64",
+    "futex",
+    "futex_requeue",
+    "futex_time64",
+    "futex_wait",
+    "futex_waitv",
+    "futex_wake",
+    "futimesat",
+    "get_kernel_syms",
+    "get_mempolicy",
+    "get_robust_list",
+    "get_thread_area",
+    "getcpu",
+    "getcwd",
+    "getdents",
+    "getdents64",
+    "getegid",
+    "getegid32",
+    "geteuid",
+    "geteuid32",
+    "getgid",
+    "getgid32",
+    "getgroups",
+    "getgroups32",
+    "getitimer",
+    "getpeername",
+    "getpgid",
+    "getpgrp",
+    "getpid",
+    "getpmsg",
+    "getppid",
+    "getpriority",
+    "getrandom",
+    "getresgid",
+    "getresgid32",
+    "getresuid",
+    "getresuid32",
+    "getrlimit",
+    "getrusage",
+    "getsid",
+    "getsockname",
+    "getsockopt",
+    "gettid",
+    "gettimeofday",
+    "getuid",
+    "getuid32",
+    "getxattr",
+    "getxattrat",
+    "gtty",
+    "idle",
+    "init_module",
+    "inotify_add_watch",
+    "inotify_init",
+    "inotify_init1",
+    "inotify_rm_watch",
+    "io_cancel",
+    "io_destroy",
+    "io_getevents",
+    "io_pgetevents",
+    "io_pgetevents_time64",
+    "io_setup",
+    "io_submit",
+    "io_uring_enter",
+    "io_uring_register",
+    "io_uring_setup",
+    "ioctl",
+    "ioperm",
+    "iopl",
+    "ioprio_get",
+    "ioprio_set",
+    "ipc",
+    "kcmp",
+    "kexec_file_load",
+    "kexec_load",
+    "keyctl",
+    "kill",
+    "landlock_add_rule",
+    "landlock_create_ruleset",
+    "landlock_restrict_self",
+    "lchown",
+    "lchown32",
+    "lgetxattr",
+    "link",
+    "linkat",
+    "listen",
+    "listmount",
+    "listxattr",
+    "listxattrat",
+    "llistxattr",
+    "llseek",
+    "lock",
+    "lookup_dcookie",
+    "lremovexattr",
+    "lseek",
+    "lsetxattr",
+    "lsm_get_self_attr",
+    "lsm_list_modules",
+    "lsm_set_self_attr",
+    "lstat",
+    "lstat64",
+    "madvise",
+    "map_shadow_stack",
+    "mbind",
+    "membarrier",
+    "memfd_create",
+    "memfd_secret",
+    "migrate_pages",
+    "mincore",
+    "mkdir",
+    "mkdirat",
+    "mknod",
+    "mknodat",
+    "mlock",
+    "mlock2",
+    "mlockall",
+    "mmap",
+    "mmap2",
+    "modify_ldt",
+    "mount",
+    "mount_setattr",
+    "move_mount",
+    "move_pages",
+    "mprotect",
+    "mpx",
+    "mq_getsetattr",
+    "mq_notify",
+    "mq_open",
+    "mq_timedreceive",
+    "mq_timedreceive_time64",
+    "mq_timedsend",
+    "mq_timedsend_time64",
+    "mq_unlink",
+    "mremap",
+    "mseal",
+    "msgctl",
+    "msgget",
+    "msgrcv",
+    "msgsnd",
+    "msync",
+    "munlock",
+    "munlockall",
+    "munmap",
+    "name_to_handle_at",
+    "nanosleep",
+    "newfstatat",
+    "nfsservctl",
+    "nice",
+    "oldfstat",
+    "oldlstat",
+    "oldolduname",
+    "oldstat",
+    "olduname",
+    "open",
+    "open_by_handle_at",
+    "open_tree",
+    "open_tree_attr",
+    "openat",
+    "openat2",
+    "pause",
+    "perf_event_open",
+    "personality",
+    "pidfd_getfd",
+    "pidfd_open",
+    "pidfd_send_signal",
+    "pipe",
+    "pipe2",
+    "pivot_root",
+    "pkey_alloc",
+    "pkey_free",
+    "pkey_mprotect",
+    "poll",
+    "ppoll",
+    "ppoll_time64",
+    "prctl",
+    "pread64",
+    "preadv",
+    "preadv2",
+    "prlimit64",
+    "process_madvise",
+    "process_mrelease",
+    "process_vm_readv",
+    "process_vm_writev",
+    "prof",
+    "profil",
+    "pselect6",
+    "pselect6_time64",
+    "ptrace",
+    "putpmsg",
+    "pwrite64",
+    "pwritev",
+    "pwritev2",
+    "query_module",
+    "quotactl",
+    "quotactl_fd",
+    "read",
+    "readahead",
+    "readdir",
+    "readlink",
+    "readlinkat",
+    "readv",
+    "reboot",
+    "recvfrom",
+    "recvmmsg",
+    "recvmmsg_time64",
+    "recvmsg",
+    "remap_file_pages",
+    "removexattr",
+    "removexattrat",
+    "rename",
+    "renameat",
+    "renameat2",
+    "request_key",
+    "restart_syscall",
+    "rmdir",
+    "rseq",
+    "rt_sigaction",
+    "rt_sigpending",
+    "rt_sigprocmask",
+    "rt_sigqueueinfo",
+    "rt_sigreturn",
+    "rt_sigsuspend",
+    "rt_sigtimedwait",
+    "rt_sigtimedwait_time64",
+    "rt_tgsigqueueinfo",
+    "sched_get_priority_max",
+    "sched_get_priority_min",
+    "sched_getaffinity",
+    "sched_getattr",
+    "sched_getparam",
+    "sched_getscheduler",
+    "sched_rr_get_interval",
+    "sched_rr_get_interval_time64",
+    "sched_setaffinity",
+    "sched_setattr",
+    "sched_setparam",
+    "sched_setscheduler",
+    "sched_yield",
+    "seccomp",
+    "security",
+    "select",
+    "semctl",
+    "semget",
+    "semop",
+    "semtimedop",
+    "semtimedop_time64",
+    "sendfile",
+    "sendfile64",
+    "sendmmsg",
+    "sendmsg",
+    "sendto",
+    "set_mempolicy",
+    "set_mempolicy_home_node",
+    "set_robust_list",
+    "set_thread_area",
+    "set_tid_address",
+    "setdomainname",
+    "setfsgid",
+    "setfsgid32",
+    "setfsuid",
+    "setfsuid32",
+    "setgid",
+    "setgid32",
+    "setgroups",
+    "setgroups32",
+    "sethostname",
+    "setitimer",
+    "setns",
+    "setpgid",
+    "setpriority",
+    "setregid",
+    "setregid32",
+    "setresgid",
+    "setresgid32",
+    "setresuid",
+    "setresuid32",
+    "setreuid",
+    "setreuid32",
+    "setrlimit",
+    "setsid",
+    "setsockopt",
+    "settimeofday",
+    "setuid",
+    "setuid32",
+    "setxattr",
+    "setxattrat",
+    "sgetmask",
+    "shmat",
+    "shmctl",
+    "shmdt",
+    "shmget",
+    "shutdown",
+    "sigaction",
+    "sigaltstack",
+    "signal",
+    "signalfd",
+    "signalfd4",
+    "sigpending",
+    "sigprocmask",
+    "sigreturn",
+    "sigsuspend",
+    "socket",
+    "socketcall",
+    "socketpair",
+    "splice",
+    "ssetmask",
+    "stat",
+    "stat64",
+    "statfs",
+    "statfs64",
+    "statmount",
+    "statx",
+    "stime",
+    "stty",
+    "swapoff",
+    "swapon",
+    "symlink",
+    "symlinkat",
+    "sync",
+    "sync_file_range",
+    "sync_file_range2",
+    "syncfs",
+    "sysfs",
+    "sysinfo",
+    "syslog",
+    "tee",
+    "tgkill",
+    "time",
+    "timer_create",
+    "timer_delete",
+    "timer_getoverrun",
+    "timer_gettime",
+    "timer_gettime64",
+    "timer_settime",
+    "timer_settime64",
+    "timerfd_create",
+    "timerfd_gettime",
+    "timerfd_gettime64",
+    "timerfd_settime",
+    "timerfd_settime64",
+    "times",
+    "tkill",
+    "truncate",
+    "truncate64",
+    "tuxcall",
+    "ugetrlimit",
+    "ulimit",
+    "umask",
+    "umount",
+    "umount2",
+    "uname",
+    "unlink",
+    "unlinkat",
+    "unshare",
+    "uretprobe",
+    "uselib",
+    "userfaultfd",
+    "ustat",
+    "utime",
+    "utimensat",
+    "utimensat_time64",
+    "utimes",
+    "vfork",
+    "vhangup",
+    "vm86",
+    "vm86old",
+    "vmsplice",
+    "vserver",
+    "wait4",
+    "waitid",
+    "waitpid",
+    "write",
+    "writev",
+];
+
+/// Symbolic errno names accepted as filter actions.
+const ERRNOS: &[&str] = &[
+    "EPERM",
+    "ENOENT",
+    "ESRCH",
+    "EINTR",
+    "EIO",
+    "ENXIO",
+    "E2BIG",
+    "ENOEXEC",
+    "EBADF",
+    "ECHILD",
+    "EAGAIN",
+    "ENOMEM",
+    "EACCES",
+    "EFAULT",
+    "ENOTBLK",
+    "EBUSY",
+    "EEXIST",
+    "EXDEV",
+    "ENODEV",
+    "ENOTDIR",
+    "EISDIR",
+    "EINVAL",
+    "ENFILE",
+    "EMFILE",
+    "ENOTTY",
+    "ETXTBSY",
+    "EFBIG",
+    "ENOSPC",
+    "ESPIPE",
+    "EROFS",
+    "EMLINK",
+    "EPIPE",
+    "EDOM",
+    "ERANGE",
+    "EDEADLK",
+    "ENAMETOOLONG",
+    "ENOLCK",
+    "ENOSYS",
+    "ENOTEMPTY",
+    "ELOOP",
+    "EWOULDBLOCK",
+    "ENOMSG",
+    "EIDRM",
+    "ECHRNG",
+    "EL2NSYNC",
+    "EL3HLT",
+    "EL3RST",
+    "ELNRNG",
+    "EUNATCH",
+    "ENOCSI",
+    "EL2HLT",
+    "EBADE",
+    "EBADR",
+    "EXFULL",
+    "ENOANO",
+    "EBADRQC",
+    "EBADSLT",
+    "EDEADLOCK",
+    "EBFONT",
+    "ENOSTR",
+    "ENODATA",
+    "ETIME",
+    "ENOSR",
+    "ENONET",
+    "ENOPKG",
+    "EREMOTE",
+    "ENOLINK",
+    "EADV",
+    "ESRMNT",
+    "ECOMM",
+    "EPROTO",
+    "EMULTIHOP",
+    "EDOTDOT",
+    "EBADMSG",
+    "EOVERFLOW",
+    "ENOTUNIQ",
+    "EBADFD",
+    "EREMCHG",
+    "ELIBACC",
+    "ELIBBAD",
+    "ELIBSCN",
+    "ELIBMAX",
+    "ELIBEXEC",
+    "EILSEQ",
+    "ERESTART",
+    "ESTRPIPE",
+    "EUSERS",
+    "ENOTSOCK",
+    "EDESTADDRREQ",
+    "EMSGSIZE",
+    "EPROTOTYPE",
+    "ENOPROTOOPT",
+    "EPROTONOSUPPORT",
+    "ESOCKTNOSUPPORT",
+    "EOPNOTSUPP",
+    "EPFNOSUPPORT",
+    "EAFNOSUPPORT",
+    "EADDRINUSE",
+    "EADDRNOTAVAIL",
+    "ENETDOWN",
+    "ENETUNREACH",
+    "ENETRESET",
+    "ECONNABORTED",
+    "ECONNRESET",
+    "ENOBUFS",
+    "EISCONN",
+    "ENOTCONN",
+    "ESHUTDOWN",
+    "ETOOMANYREFS",
+    "ETIMEDOUT",
+    "ECONNREFUSED",
+    "EHOSTDOWN",
+    "EHOSTUNREACH",
+    "EALREADY",
+    "EINPROGRESS",
+    "ESTALE",
+    "EUCLEAN",
+    "ENOTNAM",
+    "ENAVAIL",
+    "EISNAM",
+    "EREMOTEIO",
+    "EDQUOT",
+    "ENOMEDIUM",
+    "EMEDIUMTYPE",
+    "ECANCELED",
+    "ENOKEY",
+    "EKEYEXPIRED",
+    "EKEYREVOKED",
+    "EKEYREJECTED",
+    "EOWNERDEAD",
+    "ENOTRECOVERABLE",
+    "ERFKILL",
+    "EHWPOISON",
+];
+
+/// Highest error number the kernel accepts as seccomp return value.
+const MAX_ERRNO: u16 = 4095;
+
+/// A list of system calls and system call groups that is either allowed or denied.
+/// Entries are system call names like `ptrace`, groups like `@privileged`, and for deny lists
+/// optionally an errno suffix to return instead of the default action, e.g. `mount:EPERM`.
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.exec.html#SystemCallFilter=
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SystemCallFilter {
+    /// Only the listed system calls are permitted.
+    Allow(Vec<String>),
+    /// The listed system calls are denied, rendered with the `~` prefix.
+    Deny(Vec<String>),
+}
+
+impl fmt::Display for SystemCallFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SystemCallFilter::Allow(entries) => write!(f, "{}", entries.join(" ")),
+            SystemCallFilter::Deny(entries) => write!(f, "~{}", entries.join(" ")),
+        }
+    }
+}
+
+impl SystemCallFilter {
+    pub fn allow(entries: Vec<impl Into<String>>) -> Self {
+        SystemCallFilter::Allow(entries.into_iter().map(|s| s.into()).collect())
+    }
+
+    pub fn deny(entries: Vec<impl Into<String>>) -> Self {
+        SystemCallFilter::Deny(entries.into_iter().map(|s| s.into()).collect())
+    }
+
+    pub fn entries(&self) -> &[String] {
+        match self {
+            SystemCallFilter::Allow(entries) | SystemCallFilter::Deny(entries) => entries,
+        }
+    }
+
+    /// Validate the filter as a SystemCallFilter= value
+    pub fn validate(&self) -> Result<(), String> {
+        self.validate_as("SystemCallFilter", true)
+    }
+
+    /// Validates every entry, `errno` controls whether `:errno` suffixes are accepted.
+    pub(crate) fn validate_as(&self, key: &str, errno: bool) -> Result<(), String> {
+        if self.entries().is_empty() {
+            return Err(format!("{} must list at least one system call", key));
+        }
+        for entry in self.entries() {
+            let (name, action) = match entry.split_once(':') {
+                Some((name, action)) => (name, Some(action)),
+                None => (entry.as_str(), None),
+            };
+            validate_syscall(name, key)?;
+            if let Some(action) = action {
+                if !errno || matches!(self, SystemCallFilter::Allow(_)) {
+                    return Err(format!(
+                        "{} entry '{}' cannot carry an errno, only deny lists of SystemCallFilter= can",
+                        key, entry
+                    ));
+                }
+                validate_errno(action, key)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Checks a system call name or `@group` against the bundled tables.
+pub(crate) fn validate_syscall(name: &str, key: &str) -> Result<(), String> {
+    if name.starts_with('@') {
+        if !SYSCALL_GROUPS.contains(&name) {
+            return Err(format!("Unknown system call group '{}' in {}", name, key));
+        }
+    } else if !SYSCALLS.contains(&name) {
+        return Err(format!("Unknown system call '{}' in {}", name, key));
+    }
+    Ok(())
+}
+
+/// Checks an errno given by name, e.g. `EPERM`, or by number.
+pub(crate) fn validate_errno(errno: &str, key: &str) -> Result<(), String> {
+    if ERRNOS.contains(&errno) {
+        return Ok(());
+    }
+    match errno.parse::<u16>() {
+        Ok(number) if number <= MAX_ERRNO => Ok(()),
+        _ => Err(format!(
+            "Invalid errno '{}' in {}: expected a name like EPERM or a number up to {}",
+            errno, key, MAX_ERRNO
+        )),
+    }
+}
+
+/// Checks an architecture identifier accepted by SystemCallArchitectures=.
+pub(crate) fn validate_architecture(arch: &str) -> Result<(), String> {
+    if !ARCHITECTURES.contains(&arch) {
+        return Err(format!(
+            "Unknown architecture '{}' in SystemCallArchitectures, expected one of: {}",
+            arch,
+            ARCHITECTURES.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recent_system_calls_are_known() {
+        let filter = SystemCallFilter::deny(vec!["mseal", "cachestat", "map_shadow_stack:EPERM"]);
+        assert_eq!(filter.validate(), Ok(()));
+        assert_eq!(
+            filter.to_string(),
+            "~mseal cachestat map_shadow_stack:EPERM"
+        );
+    }
+
+    #[test]
+    fn unknown_system_call_is_rejected() {
+        let filter = SystemCallFilter::allow(vec!["@system-service", "not_a_syscall"]);
+        assert_eq!(
+            filter.validate(),
+            Err("Unknown system call 'not_a_syscall' in SystemCallFilter".to_string())
+        );
+    }
+}
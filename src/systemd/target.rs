@@ -0,0 +1,161 @@
+use super::Config;
+use super::install::Install;
+use super::unit::Unit;
+use crate::Result;
+use crate::error::Error;
+use std::fmt;
+
+/// Configuration for a systemd target unit.
+/// A target unit carries no type-specific section, only `[Unit]` and `[Install]`.
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.target.html
+#[derive(Debug, Clone)]
+pub struct TargetConfig {
+    pub name: String,
+    pub unit: Unit,
+    pub install: Install,
+}
+
+impl TargetConfig {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            unit: Unit::default(),
+            install: Install::default(),
+        }
+    }
+
+    /// Creates a target grouping the given services.
+    ///
+    /// The target `Wants=` every member and is wanted by `multi-user.target`.
+    /// Each member is wired to the target with `PartOf=` and `WantedBy=`, so
+    /// starting or stopping the target starts or stops the whole group.
+    pub fn group(
+        name: impl Into<String>,
+        members: impl IntoIterator<Item = Config>,
+    ) -> (Self, Vec<Config>) {
+        let mut target =
+            Self::new(name).install(Install::default().wanted_by(vec!["multi-user.target"]));
+        let members = members
+            .into_iter()
+            .map(|member| target.add_member(member))
+            .collect();
+        (target, members)
+    }
+
+    /// Adds `member` to this target and returns it wired with `PartOf=` and `WantedBy=`.
+    pub fn add_member(&mut self, mut member: Config) -> Config {
+        let target_name = self.unit_name();
+        push_unique(&mut self.unit.wants, member.unit_name());
+        push_unique(&mut member.unit.part_of, target_name.clone());
+        push_unique(&mut member.install.wanted_by, target_name);
+        member
+    }
+
+    /// Names of the units this target wants.
+    pub fn members(&self) -> &[String] {
+        self.unit.wants.as_deref().unwrap_or_default()
+    }
+
+    pub fn name(mut self, s: impl Into<String>) -> Self {
+        self.name = s.into();
+        self
+    }
+
+    pub fn unit(mut self, unit: Unit) -> Self {
+        self.unit = unit;
+        self
+    }
+
+    pub fn install(mut self, install: Install) -> Self {
+        self.install = install;
+        self
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        self.unit.validate().map_err(Error::ValidationError)?;
+        self.install.validate().map_err(Error::ValidationError)?;
+        Ok(())
+    }
+
+    pub(crate) fn unit_name(&self) -> String {
+        format!("{}.target", self.name)
+    }
+}
+
+impl fmt::Display for TargetConfig {
+    fn fmt(&self, buf: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.unit.fmt(buf)?;
+        self.install.fmt(buf)?;
+        Ok(())
+    }
+}
+
+fn push_unique(list: &mut Option<Vec<String>>, value: String) {
+    let list = list.get_or_insert_with(Vec::new);
+    if !list.contains(&value) {
+        list.push(value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn group_wires_members_to_the_target() {
+        let (target, members) =
+            TargetConfig::group("app", [Config::new("web"), Config::new("worker")]);
+
+        assert_eq!(target.members(), ["web.service", "worker.service"]);
+        assert_eq!(
+            target.install.wanted_by.as_deref(),
+            Some(&["multi-user.target".to_string()][..])
+        );
+        for member in &members {
+            assert_eq!(
+                member.unit.part_of.as_deref(),
+                Some(&["app.target".to_string()][..])
+            );
+            assert_eq!(
+                member.install.wanted_by.as_deref(),
+                Some(&["app.target".to_string()][..])
+            );
+        }
+        assert_eq!(
+            target.to_string(),
+            "[Unit]\nWants=web.service worker.service\n[Install]\nWantedBy=multi-user.target\n"
+        );
+    }
+
+    #[test]
+    fn add_member_twice_does_not_duplicate() {
+        let mut target = TargetConfig::new("app");
+
+        let member = target.add_member(Config::new("web"));
+        let member = target.add_member(member);
+
+        assert_eq!(target.members(), ["web.service"]);
+        assert_eq!(member.unit.part_of, Some(vec!["app.target".to_string()]));
+        assert_eq!(
+            member.install.wanted_by,
+            Some(vec!["app.target".to_string()])
+        );
+    }
+
+    #[test]
+    fn add_member_keeps_existing_dependencies() {
+        let mut target = TargetConfig::new("app");
+        let member =
+            Config::new("web").install(Install::default().wanted_by(vec!["multi-user.target"]));
+
+        let member = target.add_member(member);
+
+        assert_eq!(
+            member.install.wanted_by,
+            Some(vec![
+                "multi-user.target".to_string(),
+                "app.target".to_string()
+            ])
+        );
+    }
+}
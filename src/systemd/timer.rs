@@ -0,0 +1,309 @@
+use super::install::Install;
+use super::unit::Unit;
+use crate::error::Error;
+use std::fmt;
+
+/// Timer section options
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.timer.html#Options
+#[derive(Debug, Clone, Default)]
+pub struct Timer {
+    /// Timer relative to the moment the timer unit itself is activated.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.timer.html#OnActiveSec=
+    pub on_active_sec: Option<String>,
+
+    /// Timer relative to when the machine was booted up.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.timer.html#OnBootSec=
+    pub on_boot_sec: Option<String>,
+
+    /// Timer relative to when the service manager was first started.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.timer.html#OnStartupSec=
+    pub on_startup_sec: Option<String>,
+
+    /// Timer relative to when the unit the timer activates was last activated.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.timer.html#OnUnitActiveSec=
+    pub on_unit_active_sec: Option<String>,
+
+    /// Timer relative to when the unit the timer activates was last deactivated.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.timer.html#OnUnitInactiveSec=
+    pub on_unit_inactive_sec: Option<String>,
+
+    /// Realtime (wallclock) timers with calendar event expressions.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.timer.html#OnCalendar=
+    pub on_calendar: Option<Vec<String>>,
+
+    /// Accuracy the timer shall elapse with.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.timer.html#AccuracySec=
+    pub accuracy_sec: Option<String>,
+
+    /// Delay the timer by a randomly selected amount of time.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.timer.html#RandomizedDelaySec=
+    pub randomized_delay_sec: Option<String>,
+
+    /// Keep the randomized delay stable between timer elapses.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.timer.html#FixedRandomDelay=
+    pub fixed_random_delay: Option<bool>,
+
+    /// Elapse when the system clock jumps.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.timer.html#OnClockChange=
+    pub on_clock_change: Option<bool>,
+
+    /// Elapse when the local system timezone is modified.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.timer.html#OnClockChange=
+    pub on_timezone_change: Option<bool>,
+
+    /// Unit to activate when this timer elapses.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.timer.html#Unit=
+    pub unit: Option<String>,
+
+    /// Trigger the service immediately if it would have been triggered while the timer was inactive.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.timer.html#Persistent=
+    pub persistent: Option<bool>,
+
+    /// Resume the system from suspend when the timer elapses.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.timer.html#WakeSystem=
+    pub wake_system: Option<bool>,
+
+    /// Keep the timer loaded after it elapsed and cannot be triggered anymore.
+    /// https://www.freedesktop.org/software/systemd/man/latest/systemd.timer.html#RemainAfterElapse=
+    pub remain_after_elapse: Option<bool>,
+}
+
+impl fmt::Display for Timer {
+    fn fmt(&self, buf: &mut fmt::Formatter<'_>) -> fmt::Result {
+        buf.write_str("[Timer]\n")?;
+        // Monotonic timers
+        write_option!(buf, self.on_active_sec, "OnActiveSec");
+        write_option!(buf, self.on_boot_sec, "OnBootSec");
+        write_option!(buf, self.on_startup_sec, "OnStartupSec");
+        write_option!(buf, self.on_unit_active_sec, "OnUnitActiveSec");
+        write_option!(buf, self.on_unit_inactive_sec, "OnUnitInactiveSec");
+
+        // Realtime timers
+        write_vec_multi!(buf, self.on_calendar, "OnCalendar");
+        write_bool!(buf, self.on_clock_change, "OnClockChange");
+        write_bool!(buf, self.on_timezone_change, "OnTimezoneChange");
+
+        // Accuracy
+        write_option!(buf, self.accuracy_sec, "AccuracySec");
+        write_option!(buf, self.randomized_delay_sec, "RandomizedDelaySec");
+        write_bool!(buf, self.fixed_random_delay, "FixedRandomDelay");
+
+        // Activation
+        write_option!(buf, self.unit, "Unit");
+        write_bool!(buf, self.persistent, "Persistent");
+        write_bool!(buf, self.wake_system, "WakeSystem");
+        write_bool!(buf, self.remain_after_elapse, "RemainAfterElapse");
+
+        Ok(())
+    }
+}
+
+impl Timer {
+    /// Creates a new empty `Timer` section.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Validate the timer configuration according to systemd specifications
+    pub fn validate(&self) -> Result<(), String> {
+        let has_trigger = self.on_active_sec.is_some()
+            || self.on_boot_sec.is_some()
+            || self.on_startup_sec.is_some()
+            || self.on_unit_active_sec.is_some()
+            || self.on_unit_inactive_sec.is_some()
+            || self.on_calendar.as_ref().is_some_and(|c| !c.is_empty())
+            || self.on_clock_change == Some(true)
+            || self.on_timezone_change == Some(true);
+        if !has_trigger {
+            return Err("Timer must define at least one On*= trigger".to_string());
+        }
+
+        if let Some(ref calendars) = self.on_calendar {
+            for calendar in calendars {
+                if calendar.trim().is_empty() {
+                    return Err("OnCalendar cannot contain empty expressions".to_string());
+                }
+            }
+        }
+
+        if let Some(ref unit) = self.unit
+            && unit.ends_with(".timer")
+        {
+            return Err(format!("Timer cannot activate another timer '{}'", unit));
+        }
+
+        Ok(())
+    }
+
+    // Builder pattern setters
+    pub fn on_active_sec(mut self, value: impl Into<String>) -> Self {
+        self.on_active_sec = Some(value.into());
+        self
+    }
+
+    pub fn on_boot_sec(mut self, value: impl Into<String>) -> Self {
+        self.on_boot_sec = Some(value.into());
+        self
+    }
+
+    pub fn on_startup_sec(mut self, value: impl Into<String>) -> Self {
+        self.on_startup_sec = Some(value.into());
+        self
+    }
+
+    pub fn on_unit_active_sec(mut self, value: impl Into<String>) -> Self {
+        self.on_unit_active_sec = Some(value.into());
+        self
+    }
+
+    pub fn on_unit_inactive_sec(mut self, value: impl Into<String>) -> Self {
+        self.on_unit_inactive_sec = Some(value.into());
+        self
+    }
+
+    pub fn on_calendar(mut self, value: Vec<impl Into<String>>) -> Self {
+        self.on_calendar = Some(value.into_iter().map(|s| s.into()).collect());
+        self
+    }
+
+    pub fn accuracy_sec(mut self, value: impl Into<String>) -> Self {
+        self.accuracy_sec = Some(value.into());
+        self
+    }
+
+    pub fn randomized_delay_sec(mut self, value: impl Into<String>) -> Self {
+        self.randomized_delay_sec = Some(value.into());
+        self
+    }
+
+    pub fn fixed_random_delay(mut self, value: bool) -> Self {
+        self.fixed_random_delay = Some(value);
+        self
+    }
+
+    pub fn on_clock_change(mut self, value: bool) -> Self {
+        self.on_clock_change = Some(value);
+        self
+    }
+
+    pub fn on_timezone_change(mut self, value: bool) -> Self {
+        self.on_timezone_change = Some(value);
+        self
+    }
+
+    pub fn unit(mut self, value: impl Into<String>) -> Self {
+        self.unit = Some(value.into());
+        self
+    }
+
+    pub fn persistent(mut self, value: bool) -> Self {
+        self.persistent = Some(value);
+        self
+    }
+
+    pub fn wake_system(mut self, value: bool) -> Self {
+        self.wake_system = Some(value);
+        self
+    }
+
+    pub fn remain_after_elapse(mut self, value: bool) -> Self {
+        self.remain_after_elapse = Some(value);
+        self
+    }
+}
+
+/// Configuration for a systemd timer unit.
+/// https://www.freedesktop.org/software/systemd/man/latest/systemd.timer.html
+#[derive(Debug, Clone)]
+pub struct TimerConfig {
+    pub name: String,
+    pub unit: Unit,
+    pub timer: Timer,
+    pub install: Install,
+}
+
+impl TimerConfig {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            unit: Unit::default(),
+            timer: Timer::default(),
+            install: Install::default(),
+        }
+    }
+
+    pub fn name(mut self, s: impl Into<String>) -> Self {
+        self.name = s.into();
+        self
+    }
+
+    pub fn unit(mut self, unit: Unit) -> Self {
+        self.unit = unit;
+        self
+    }
+
+    pub fn timer(mut self, timer: Timer) -> Self {
+        self.timer = timer;
+        self
+    }
+
+    pub fn install(mut self, install: Install) -> Self {
+        self.install = install;
+        self
+    }
+
+    pub fn validate(&self) -> crate::Result<()> {
+        self.unit.validate().map_err(Error::ValidationError)?;
+        self.timer.validate().map_err(Error::ValidationError)?;
+        self.install.validate().map_err(Error::ValidationError)?;
+        Ok(())
+    }
+
+    pub(crate) fn unit_name(&self) -> String {
+        format!("{}.timer", self.name)
+    }
+}
+
+impl fmt::Display for TimerConfig {
+    fn fmt(&self, buf: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.unit.fmt(buf)?;
+        self.timer.fmt(buf)?;
+        self.install.fmt(buf)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_timer_unit() {
+        let config = TimerConfig::new("backup")
+            .timer(
+                Timer::new()
+                    .on_calendar(vec!["daily", "Sat *-*-* 12:00"])
+                    .randomized_delay_sec("15min")
+                    .persistent(true)
+                    .unit("backup.service"),
+            )
+            .install(Install::default().wanted_by(vec!["timers.target"]));
+
+        assert!(config.validate().is_ok());
+        assert_eq!(config.unit_name(), "backup.timer");
+        assert_eq!(
+            config.to_string(),
+            "[Unit]\n[Timer]\nOnCalendar=daily\nOnCalendar=Sat *-*-* 12:00\nRandomizedDelaySec=15min\n\
+             Unit=backup.service\nPersistent=yes\n\
+             [Install]\nWantedBy=timers.target\n"
+        );
+    }
+
+    #[test]
+    fn timer_needs_a_trigger() {
+        assert_eq!(
+            Timer::new().persistent(true).validate(),
+            Err("Timer must define at least one On*= trigger".to_string())
+        );
+    }
+}
@@ -0,0 +1,207 @@
+use super::install::InstallLink;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+
+/// Suffix of the backup kept of a file replaced by a [`Transaction`].
+const BACKUP_SUFFIX: &str = ".bak";
+
+/// Changes made to the file system while installing a unit, undone in reverse order
+/// when a later step fails.
+#[derive(Debug, Default)]
+pub(crate) struct Transaction {
+    undo: Vec<Undo>,
+}
+
+#[derive(Debug)]
+enum Undo {
+    /// A file that did not exist before was created.
+    Remove(PathBuf),
+    /// A file was replaced, its previous contents are in the backup.
+    Restore { path: PathBuf, backup: PathBuf },
+    /// Symlinks were created.
+    Unlink(Vec<InstallLink>),
+}
+
+impl Transaction {
+    /// Atomically writes `contents` to `path` with permissions `mode`.
+    /// A file being replaced is first copied to `<path>.bak`, until the transaction is committed.
+    pub(crate) fn write(&mut self, path: &Path, contents: &[u8], mode: u32) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let undo = match fs::read(path) {
+            Ok(previous) => {
+                let backup = backup_path(path);
+                let previous_mode = fs::metadata(path).map(|m| m.permissions().mode())?;
+                write_atomic(&backup, &previous, previous_mode)?;
+                Undo::Restore {
+                    path: path.to_path_buf(),
+                    backup,
+                }
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Undo::Remove(path.to_path_buf()),
+            Err(e) => return Err(e),
+        };
+        write_atomic(path, contents, mode)?;
+        self.undo.push(undo);
+        Ok(())
+    }
+
+    /// Records symlinks created during the transaction, so they are removed on rollback.
+    pub(crate) fn linked(&mut self, links: Vec<InstallLink>) {
+        if !links.is_empty() {
+            self.undo.push(Undo::Unlink(links));
+        }
+    }
+
+    /// Keeps all recorded changes and removes the backups of replaced files.
+    pub(crate) fn commit(self) -> io::Result<()> {
+        for undo in self.undo {
+            if let Undo::Restore { backup, .. } = undo {
+                remove_backup(&backup)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Undoes all recorded changes, most recent first. Every change is attempted,
+    /// the first error is returned.
+    pub(crate) fn rollback(self) -> io::Result<()> {
+        let mut result = Ok(());
+        for undo in self.undo.into_iter().rev() {
+            let undone = match undo {
+                Undo::Remove(path) => fs::remove_file(path),
+                Undo::Restore { path, backup } => fs::read(&backup)
+                    .and_then(|previous| {
+                        let mode = fs::metadata(&backup)?.permissions().mode();
+                        write_atomic(&path, &previous, mode)
+                    })
+                    .and_then(|_| remove_backup(&backup)),
+                Undo::Unlink(links) => {
+                    links
+                        .iter()
+                        .try_for_each(|link| match fs::remove_file(&link.path) {
+                            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+                            _ => Ok(()),
+                        })
+                }
+            };
+            if result.is_ok() {
+                result = undone;
+            }
+        }
+        result
+    }
+}
+
+/// Writes `contents` to a temporary file next to `path`, syncs it and renames it over `path`,
+/// so readers see either the old or the new file, even after a crash.
+fn write_atomic(path: &Path, contents: &[u8], mode: u32) -> io::Result<()> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Path has no file name"))?;
+    let tmp = dir.join(format!(
+        ".{}.{}.tmp",
+        name.to_string_lossy(),
+        std::process::id()
+    ));
+
+    let written = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(mode)
+        .open(&tmp)
+        .and_then(|mut file| {
+            file.write_all(contents)?;
+            // The mode passed to open is subject to the umask
+            file.set_permissions(fs::Permissions::from_mode(mode))?;
+            file.sync_all()
+        })
+        .and_then(|_| fs::rename(&tmp, path));
+    if let Err(e) = written {
+        let _ = fs::remove_file(&tmp);
+        return Err(e);
+    }
+    // Persist the rename itself
+    File::open(dir)?.sync_all()
+}
+
+fn remove_backup(backup: &Path) -> io::Result<()> {
+    match fs::remove_file(backup) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+fn backup_path(path: &Path) -> PathBuf {
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(BACKUP_SUFFIX);
+    PathBuf::from(backup)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systemd::testing::TempDir;
+
+    fn mode(path: &Path) -> u32 {
+        fs::metadata(path).unwrap().permissions().mode() & 0o777
+    }
+
+    #[test]
+    fn rollback_removes_created_files_and_links() {
+        let dir = TempDir::new();
+        let path = dir.join("units/demo.service");
+        let link = InstallLink {
+            path: dir.join("demo-alias.service"),
+            target: path.clone(),
+        };
+        let mut transaction = Transaction::default();
+
+        transaction.write(&path, b"new", 0o644).unwrap();
+        std::os::unix::fs::symlink(&link.target, &link.path).unwrap();
+        transaction.linked(vec![link.clone()]);
+        assert_eq!(mode(&path), 0o644);
+        transaction.rollback().unwrap();
+
+        assert!(!path.exists());
+        assert!(!link.path.is_symlink());
+    }
+
+    #[test]
+    fn rollback_restores_contents_and_mode() {
+        let dir = TempDir::new();
+        let path = dir.join("demo.service");
+        write_atomic(&path, b"old", 0o600).unwrap();
+        let mut transaction = Transaction::default();
+
+        transaction.write(&path, b"new", 0o644).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"new");
+        assert_eq!(fs::read(backup_path(&path)).unwrap(), b"old");
+        transaction.rollback().unwrap();
+
+        assert_eq!(fs::read(&path).unwrap(), b"old");
+        assert_eq!(mode(&path), 0o600);
+        assert!(!backup_path(&path).exists());
+    }
+
+    #[test]
+    fn commit_keeps_changes_and_removes_backups() {
+        let dir = TempDir::new();
+        let path = dir.join("demo.service");
+        write_atomic(&path, b"old", 0o644).unwrap();
+        let mut transaction = Transaction::default();
+
+        transaction.write(&path, b"new", 0o644).unwrap();
+        transaction.commit().unwrap();
+
+        assert_eq!(fs::read(&path).unwrap(), b"new");
+        assert!(!backup_path(&path).exists());
+    }
+}
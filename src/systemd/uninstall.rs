@@ -0,0 +1,315 @@
+use super::exec::{self, DirectoryKind};
+use super::{ManagerScope, Systemd, UnitFile};
+use crate::Result;
+use crate::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Directory below `/var/lib` and `/var/log` holding the directories of units with `DynamicUser=yes`.
+const PRIVATE_DIRECTORY: &str = "private";
+
+/// Options of [`Systemd::uninstall_with`].
+#[derive(Debug, Clone)]
+pub struct UninstallOptions {
+    /// Stop the unit before removing it. Enabled by default.
+    pub stop: bool,
+    /// Remove the drop-in directory `<unit>.d/` next to the unit file. Enabled by default.
+    pub remove_drop_ins: bool,
+    /// Reset the failed state of the unit, so it does not linger in `systemctl --failed`.
+    /// Enabled by default.
+    pub reset_failed: bool,
+    /// Delete the `StateDirectory=` and `LogsDirectory=` directories of a service,
+    /// and all data in them, including the `private/` directories of `DynamicUser=yes`.
+    /// Disabled by default.
+    pub purge: bool,
+}
+
+impl Default for UninstallOptions {
+    fn default() -> Self {
+        Self {
+            stop: true,
+            remove_drop_ins: true,
+            reset_failed: true,
+            purge: false,
+        }
+    }
+}
+
+impl UninstallOptions {
+    pub fn stop(mut self, value: bool) -> Self {
+        self.stop = value;
+        self
+    }
+
+    pub fn remove_drop_ins(mut self, value: bool) -> Self {
+        self.remove_drop_ins = value;
+        self
+    }
+
+    pub fn reset_failed(mut self, value: bool) -> Self {
+        self.reset_failed = value;
+        self
+    }
+
+    pub fn purge(mut self, value: bool) -> Self {
+        self.purge = value;
+        self
+    }
+}
+
+/// Changes made by [`Systemd::uninstall_with`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UninstallReport {
+    /// Whether the unit was stopped.
+    pub stopped: bool,
+    /// Whether the unit was disabled.
+    pub disabled: bool,
+    /// Files and directories that were removed.
+    pub removed: Vec<PathBuf>,
+    /// Whether the service manager reloaded its unit files.
+    pub reloaded: bool,
+}
+
+impl UninstallReport {
+    /// Returns true if anything was changed.
+    pub fn is_changed(&self) -> bool {
+        self.stopped || self.disabled || !self.removed.is_empty()
+    }
+}
+
+impl fmt::Display for UninstallReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.is_changed() {
+            return write!(f, "not installed");
+        }
+        let mut steps = Vec::new();
+        if self.stopped {
+            steps.push("stopped".to_string());
+        }
+        if self.disabled {
+            steps.push("disabled".to_string());
+        }
+        if !self.removed.is_empty() {
+            steps.push(format!("{} path(s) removed", self.removed.len()));
+        }
+        if self.reloaded {
+            steps.push("reloaded".to_string());
+        }
+        write!(f, "{}", steps.join(", "))
+    }
+}
+
+impl Systemd {
+    /// Removes the unit: stops it, resets its failed state, disables it, deletes the unit file
+    /// and its drop-ins, optionally purges its data, and reloads the service manager.
+    ///
+    /// Parts that are already gone are skipped, so uninstalling twice is not an error.
+    /// Below an alternate root, only files and symlinks are removed.
+    pub fn uninstall_with(&self, options: &UninstallOptions) -> Result<UninstallReport> {
+        let mut report = UninstallReport::default();
+        let unit_file = self.config_path()?;
+        let installed = fs::symlink_metadata(&unit_file).is_ok();
+        // Without a running manager there is nothing to stop, reset or reload
+        let live = self.root.is_none() && self.scope != ManagerScope::Global;
+
+        if installed && live {
+            if options.stop {
+                self.stop()?;
+                report.stopped = true;
+            }
+            if options.reset_failed {
+                self.reset_failed()?;
+            }
+        }
+
+        if installed && self.root.is_none() {
+            self.disable_with_manager()?;
+            report.disabled = true;
+        } else {
+            let removed = self.disable()?;
+            report.disabled = !removed.is_empty();
+        }
+
+        if remove(&unit_file)? {
+            report.removed.push(unit_file.clone());
+        }
+        if options.remove_drop_ins {
+            let mut drop_ins = unit_file.into_os_string();
+            drop_ins.push(".d");
+            let drop_ins = PathBuf::from(drop_ins);
+            if remove(&drop_ins)? {
+                report.removed.push(drop_ins);
+            }
+        }
+        if options.purge {
+            for dir in self.purged_directories()? {
+                if remove(&dir)? {
+                    report.removed.push(dir);
+                }
+            }
+        }
+
+        if live && report.is_changed() {
+            self.daemon_reload()?;
+            report.reloaded = true;
+        }
+        Ok(report)
+    }
+
+    /// Resets the failed state of the unit, and its restart counter.
+    pub fn reset_failed(&self) -> Result<()> {
+        let unit_name = self.config.unit_name();
+        #[cfg(feature = "dbus")]
+        if let Some(ref manager) = self.dbus
+            && self.root.is_none()
+        {
+            return manager.reset_failed_unit(&unit_name);
+        }
+        self.systemctl(&["reset-failed", &unit_name], "reset failed state")?;
+        Ok(())
+    }
+
+    /// Returns the state and logs directories of a service, below the alternate root if one is set.
+    ///
+    /// With `DynamicUser=yes`, the system manager creates the directories below `private/` and
+    /// only symlinks them from their usual place, so both the symlink and its target are returned.
+    fn purged_directories(&self) -> Result<Vec<PathBuf>> {
+        let UnitFile::Service(ref config) = self.config else {
+            return Ok(Vec::new());
+        };
+        let Some(ref exec) = config.service.exec else {
+            return Ok(Vec::new());
+        };
+        let private = exec.dynamic_user == Some(true) && self.scope == ManagerScope::System;
+        let mut dirs = Vec::new();
+        for kind in [DirectoryKind::State, DirectoryKind::Logs] {
+            let base = kind.base(self.scope)?;
+            for name in exec.directories(kind).into_iter().flatten() {
+                // The names end up in remove_dir_all, so they must stay below the base
+                exec::validate_directory_name(name, kind).map_err(Error::ValidationError)?;
+                dirs.push(self.rooted(&base.join(name)));
+                if private {
+                    dirs.push(self.rooted(&base.join(PRIVATE_DIRECTORY).join(name)));
+                }
+            }
+        }
+        Ok(dirs)
+    }
+}
+
+/// Removes a file, symlink or directory tree, returning false if it did not exist.
+fn remove(path: &Path) -> Result<bool> {
+    let removed = match fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.is_dir() => fs::remove_dir_all(path),
+        Ok(_) => fs::remove_file(path),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e.into()),
+    };
+    match removed {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systemd::exec::Exec;
+    use crate::systemd::testing::TempDir;
+    use crate::systemd::{CommandOutput, Config, MockRunner, Service};
+    use std::os::unix::fs::symlink;
+    use std::sync::Arc;
+
+    fn ok() -> CommandOutput {
+        CommandOutput::success("")
+    }
+
+    fn dynamic_user_config() -> Config {
+        let exec = Exec::new()
+            .dynamic_user(true)
+            .state_directory(vec!["demo"])
+            .logs_directory(vec!["demo"]);
+        Config::new("demo").service(Service::default().exec(exec))
+    }
+
+    #[test]
+    fn purge_removes_private_directories_of_dynamic_user() {
+        let root = TempDir::new();
+        let unit_file = root.join("etc/systemd/system/demo.service");
+        fs::create_dir_all(unit_file.parent().unwrap()).unwrap();
+        fs::write(&unit_file, dynamic_user_config().to_string()).unwrap();
+        for base in ["var/lib", "var/log"] {
+            let private = root.join(base).join("private/demo");
+            fs::create_dir_all(&private).unwrap();
+            fs::write(private.join("data"), "data").unwrap();
+            symlink("private/demo", root.join(base).join("demo")).unwrap();
+        }
+        let systemd = Systemd::new(dynamic_user_config()).root(root.path());
+
+        let report = systemd
+            .uninstall_with(&UninstallOptions::default().purge(true))
+            .unwrap();
+
+        assert_eq!(
+            report.removed,
+            [
+                unit_file,
+                root.join("var/lib/demo"),
+                root.join("var/lib/private/demo"),
+                root.join("var/log/demo"),
+                root.join("var/log/private/demo"),
+            ]
+        );
+        for path in &report.removed {
+            assert!(fs::symlink_metadata(path).is_err());
+        }
+        assert!(root.join("var/lib/private").exists());
+        // Nothing is left to remove the second time
+        let again = systemd
+            .uninstall_with(&UninstallOptions::default().purge(true))
+            .unwrap();
+        assert_eq!(again, UninstallReport::default());
+    }
+
+    #[test]
+    fn uninstall_twice_is_unchanged() {
+        let dir = TempDir::new();
+        let config = Config::new("demo");
+        fs::write(dir.join("demo.service"), config.to_string()).unwrap();
+        fs::create_dir(dir.join("demo.service.d")).unwrap();
+        let runner = Arc::new(
+            MockRunner::new()
+                .expect("systemctl", &["stop", "demo.service"], ok())
+                .expect("systemctl", &["reset-failed", "demo.service"], ok())
+                .expect("systemctl", &["disable", "demo.service"], ok())
+                .expect("systemctl", &["daemon-reload"], ok()),
+        );
+        let systemd = Systemd::new(config)
+            .runner(Arc::clone(&runner))
+            .with_unit_dir(&dir);
+
+        let report = systemd
+            .uninstall_with(&UninstallOptions::default())
+            .unwrap();
+        runner.assert_done();
+        assert_eq!(
+            report,
+            UninstallReport {
+                stopped: true,
+                disabled: true,
+                removed: vec![dir.join("demo.service"), dir.join("demo.service.d")],
+                reloaded: true,
+            }
+        );
+
+        let again = systemd
+            .uninstall_with(&UninstallOptions::default())
+            .unwrap();
+        assert_eq!(again, UninstallReport::default());
+        assert_eq!(again.to_string(), "not installed");
+        assert_eq!(runner.calls().len(), 4);
+    }
+}
@@ -0,0 +1,297 @@
+use super::Config;
+use super::automount::AutomountConfig;
+use super::install::Install;
+use super::mount::MountConfig;
+use super::path::PathConfig;
+use super::slice::SliceConfig;
+use super::socket::SocketConfig;
+use super::swap::SwapConfig;
+use super::target::TargetConfig;
+use super::timer::TimerConfig;
+use crate::Result;
+use std::fmt;
+use std::path::Path;
+
+/// Type of a systemd unit, rendered as the unit file suffix
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnitType {
+    Service,
+    Socket,
+    Timer,
+    Path,
+    Mount,
+    Automount,
+    Swap,
+    Target,
+    Slice,
+}
+
+impl fmt::Display for UnitType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnitType::Service => write!(f, "service"),
+            UnitType::Socket => write!(f, "socket"),
+            UnitType::Timer => write!(f, "timer"),
+            UnitType::Path => write!(f, "path"),
+            UnitType::Mount => write!(f, "mount"),
+            UnitType::Automount => write!(f, "automount"),
+            UnitType::Swap => write!(f, "swap"),
+            UnitType::Target => write!(f, "target"),
+            UnitType::Slice => write!(f, "slice"),
+        }
+    }
+}
+
+impl UnitType {
+    /// Returns the unit type of a unit name such as `foo.service`, if the suffix is known.
+    pub fn from_unit_name(name: &str) -> Option<Self> {
+        match name.rsplit_once('.')?.1 {
+            "service" => Some(UnitType::Service),
+            "socket" => Some(UnitType::Socket),
+            "timer" => Some(UnitType::Timer),
+            "path" => Some(UnitType::Path),
+            "mount" => Some(UnitType::Mount),
+            "automount" => Some(UnitType::Automount),
+            "swap" => Some(UnitType::Swap),
+            "target" => Some(UnitType::Target),
+            "slice" => Some(UnitType::Slice),
+            _ => None,
+        }
+    }
+}
+
+/// Any unit file that can be installed and managed by [`Systemd`](super::Systemd).
+/// Every configuration type converts into it with `From`, so it can be passed directly to `Systemd::new`.
+#[derive(Debug, Clone)]
+pub enum UnitFile {
+    Service(Box<Config>),
+    Socket(Box<SocketConfig>),
+    Timer(Box<TimerConfig>),
+    Path(Box<PathConfig>),
+    Mount(Box<MountConfig>),
+    Automount(Box<AutomountConfig>),
+    Swap(Box<SwapConfig>),
+    Target(Box<TargetConfig>),
+    Slice(Box<SliceConfig>),
+}
+
+impl UnitFile {
+    /// Returns the type of this unit.
+    pub fn unit_type(&self) -> UnitType {
+        match self {
+            UnitFile::Service(_) => UnitType::Service,
+            UnitFile::Socket(_) => UnitType::Socket,
+            UnitFile::Timer(_) => UnitType::Timer,
+            UnitFile::Path(_) => UnitType::Path,
+            UnitFile::Mount(_) => UnitType::Mount,
+            UnitFile::Automount(_) => UnitType::Automount,
+            UnitFile::Swap(_) => UnitType::Swap,
+            UnitFile::Target(_) => UnitType::Target,
+            UnitFile::Slice(_) => UnitType::Slice,
+        }
+    }
+
+    /// Returns the full unit name including the type suffix, e.g. `foo.service`.
+    pub fn unit_name(&self) -> String {
+        match self {
+            UnitFile::Service(config) => config.unit_name(),
+            UnitFile::Socket(config) => config.unit_name(),
+            UnitFile::Timer(config) => config.unit_name(),
+            UnitFile::Path(config) => config.unit_name(),
+            UnitFile::Mount(config) => config.unit_name(),
+            UnitFile::Automount(config) => config.unit_name(),
+            UnitFile::Swap(config) => config.unit_name(),
+            UnitFile::Target(config) => config.unit_name(),
+            UnitFile::Slice(config) => config.unit_name(),
+        }
+    }
+
+    /// Returns the `[Install]` section of this unit.
+    pub fn install_section(&self) -> &Install {
+        match self {
+            UnitFile::Service(config) => &config.install,
+            UnitFile::Socket(config) => &config.install,
+            UnitFile::Timer(config) => &config.install,
+            UnitFile::Path(config) => &config.install,
+            UnitFile::Mount(config) => &config.install,
+            UnitFile::Automount(config) => &config.install,
+            UnitFile::Swap(config) => &config.install,
+            UnitFile::Target(config) => &config.install,
+            UnitFile::Slice(config) => &config.install,
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            UnitFile::Service(config) => config.validate(),
+            UnitFile::Socket(config) => config.validate(),
+            UnitFile::Timer(config) => config.validate(),
+            UnitFile::Path(config) => config.validate(),
+            UnitFile::Mount(config) => config.validate(),
+            UnitFile::Automount(config) => config.validate(),
+            UnitFile::Swap(config) => config.validate(),
+            UnitFile::Target(config) => config.validate(),
+            UnitFile::Slice(config) => config.validate(),
+        }
+    }
+
+    /// Returns warnings about settings that pass validation but are discouraged by systemd.
+    pub fn warnings(&self) -> Vec<String> {
+        match self {
+            UnitFile::Service(config) => config.warnings(),
+            UnitFile::Socket(config) => config.warnings(),
+            UnitFile::Mount(config) => config.warnings(),
+            UnitFile::Swap(config) => config.warnings(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl fmt::Display for UnitFile {
+    fn fmt(&self, buf: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnitFile::Service(config) => config.fmt(buf),
+            UnitFile::Socket(config) => config.fmt(buf),
+            UnitFile::Timer(config) => config.fmt(buf),
+            UnitFile::Path(config) => config.fmt(buf),
+            UnitFile::Mount(config) => config.fmt(buf),
+            UnitFile::Automount(config) => config.fmt(buf),
+            UnitFile::Swap(config) => config.fmt(buf),
+            UnitFile::Target(config) => config.fmt(buf),
+            UnitFile::Slice(config) => config.fmt(buf),
+        }
+    }
+}
+
+macro_rules! impl_from_config {
+    ($($config:ty => $variant:ident),* $(,)?) => {
+        $(
+            impl From<$config> for UnitFile {
+                fn from(config: $config) -> Self {
+                    UnitFile::$variant(Box::new(config))
+                }
+            }
+        )*
+    };
+}
+
+impl_from_config!(
+    Config => Service,
+    SocketConfig => Socket,
+    TimerConfig => Timer,
+    PathConfig => Path,
+    MountConfig => Mount,
+    AutomountConfig => Automount,
+    SwapConfig => Swap,
+    TargetConfig => Target,
+    SliceConfig => Slice,
+);
+
+/// Escapes a path into a unit name prefix the way `systemd-escape --path` does,
+/// e.g. `/var/lib/my data` becomes `var-lib-my\x20data`.
+pub fn escape_path(path: &Path) -> String {
+    let path = path.to_string_lossy();
+    let trimmed = path
+        .split('/')
+        .filter(|component| !component.is_empty())
+        .collect::<Vec<_>>()
+        .join("/");
+    if trimmed.is_empty() {
+        return "-".to_string();
+    }
+
+    let mut escaped = String::with_capacity(trimmed.len());
+    for (i, byte) in trimmed.bytes().enumerate() {
+        match byte {
+            b'/' => escaped.push('-'),
+            b'.' if i == 0 => escaped.push_str("\\x2e"),
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b':' | b'_' | b'.' => {
+                escaped.push(byte as char)
+            }
+            _ => escaped.push_str(&format!("\\x{:02x}", byte)),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systemd::{Automount, Mount, Swap};
+
+    /// Pairs of paths and their `systemd-escape --path` output.
+    const ESCAPED_PATHS: &[(&str, &str)] = &[
+        ("/", "-"),
+        ("/dev/sda5", "dev-sda5"),
+        ("/var/lib/my-data", "var-lib-my\\x2ddata"),
+        ("/var/lib/my data", "var-lib-my\\x20data"),
+        ("/srv/café", "srv-caf\\xc3\\xa9"),
+        ("/.hidden/x", "\\x2ehidden-x"),
+        ("/a/.b", "a-.b"),
+        ("//a//b/", "a-b"),
+    ];
+
+    #[test]
+    fn escape_path_matches_systemd_escape() {
+        for (path, escaped) in ESCAPED_PATHS {
+            assert_eq!(escape_path(Path::new(path)), *escaped, "{}", path);
+        }
+    }
+
+    #[test]
+    fn unit_names_derive_from_paths() {
+        let mount = UnitFile::from(MountConfig::new(Mount::new(
+            "/dev/sdb1",
+            "/var/lib/my-data",
+        )));
+        let automount = UnitFile::from(AutomountConfig::new(Automount::new("/var/lib/my-data")));
+        let swap = UnitFile::from(SwapConfig::new(Swap::new("/dev/sda5")));
+
+        assert_eq!(mount.unit_name(), "var-lib-my\\x2ddata.mount");
+        assert_eq!(automount.unit_name(), "var-lib-my\\x2ddata.automount");
+        assert_eq!(swap.unit_name(), "dev-sda5.swap");
+        assert_eq!(swap.unit_type(), UnitType::Swap);
+    }
+
+    #[test]
+    fn unit_type_from_unit_name() {
+        assert_eq!(
+            UnitType::from_unit_name("demo.service"),
+            Some(UnitType::Service)
+        );
+        assert_eq!(
+            UnitType::from_unit_name("var-lib.automount"),
+            Some(UnitType::Automount)
+        );
+        assert_eq!(UnitType::from_unit_name("demo.device"), None);
+        assert_eq!(UnitType::from_unit_name("demo"), None);
+    }
+
+    #[test]
+    fn unit_name_suffix_follows_the_variant() {
+        assert_eq!(
+            UnitFile::from(Config::new("demo")).unit_name(),
+            "demo.service"
+        );
+        assert_eq!(
+            UnitFile::from(SocketConfig::new("demo")).unit_name(),
+            "demo.socket"
+        );
+        assert_eq!(
+            UnitFile::from(TimerConfig::new("demo")).unit_name(),
+            "demo.timer"
+        );
+        assert_eq!(
+            UnitFile::from(PathConfig::new("demo")).unit_name(),
+            "demo.path"
+        );
+        assert_eq!(
+            UnitFile::from(TargetConfig::new("demo")).unit_name(),
+            "demo.target"
+        );
+        assert_eq!(
+            UnitFile::from(SliceConfig::new("demo")).unit_name(),
+            "demo.slice"
+        );
+    }
+}
@@ -0,0 +1,259 @@
+use super::service::ServiceType;
+use super::{ActiveState, LoadState, Systemd, UnitFile, UnitResult, UnitState};
+use crate::Result;
+use crate::error::Error;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Interval between two reads of the unit state while waiting.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Number of log lines included in the error when a unit does not reach the expected state.
+const DIAGNOSTIC_LOG_LINES: usize = 10;
+
+impl Systemd {
+    /// Waits until the unit reaches `state`. If a [`DbusManager`](super::DbusManager) is set,
+    /// the state is read again whenever the properties of the unit change, otherwise it is read
+    /// with `systemctl` every 100 milliseconds.
+    ///
+    /// Fails early if the unit enters the failed state, or is not installed while waiting
+    /// for another state than inactive. The error describes the last state of the unit
+    /// and includes its last log lines.
+    pub fn wait_for(&self, state: ActiveState, timeout: Duration) -> Result<UnitState> {
+        let unit_name = self.config.unit_name();
+        let deadline = Instant::now() + timeout;
+        // Listen before reading the state, so no change in between is missed
+        #[cfg(feature = "dbus")]
+        let mut changes = match self.dbus {
+            Some(ref manager) if self.root.is_none() => Some(manager.unit_changes(&unit_name)?),
+            _ => None,
+        };
+        loop {
+            let current = self.state()?;
+            if current.active_state == state {
+                return Ok(current);
+            }
+            if current.load_state == LoadState::NotFound {
+                return Err(self.diagnostic(
+                    format!("Unit {} is not installed", unit_name),
+                    Some(&current),
+                ));
+            }
+            if current.active_state == ActiveState::Failed {
+                return Err(self.diagnostic(
+                    format!(
+                        "Unit {} failed while waiting for it to be {}",
+                        unit_name, state
+                    ),
+                    Some(&current),
+                ));
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(self.diagnostic(
+                    format!(
+                        "Unit {} did not become {} within {:?}",
+                        unit_name, state, timeout
+                    ),
+                    Some(&current),
+                ));
+            }
+            #[cfg(feature = "dbus")]
+            if let Some(ref mut changes) = changes {
+                changes.wait(deadline - now)?;
+                continue;
+            }
+            thread::sleep(self.poll_interval().min(deadline - now));
+        }
+    }
+
+    /// Starts the unit and waits until it is active, see [`Systemd::wait_for`].
+    ///
+    /// A oneshot service without `RemainAfterExit=` is never active, it is instead expected to be
+    /// inactive with a successful result once it ran. For `Type=simple` services, active only
+    /// means the main process was spawned, a process exiting right away may fail afterwards.
+    pub fn start_and_wait(&self, timeout: Duration) -> Result<UnitState> {
+        if let Err(e) = self.start() {
+            let message = match e {
+                Error::CommandError(message) => message,
+                e => e.to_string(),
+            };
+            let state = self.state().ok();
+            return Err(self.diagnostic(message, state.as_ref()));
+        }
+        if !self.is_transient_oneshot() {
+            return self.wait_for(ActiveState::Active, timeout);
+        }
+
+        let state = self.wait_for(ActiveState::Inactive, timeout)?;
+        match state.result {
+            None | Some(UnitResult::Success) => Ok(state),
+            Some(ref result) => Err(self.diagnostic(
+                format!(
+                    "Unit {} finished with result {}",
+                    self.config.unit_name(),
+                    result
+                ),
+                Some(&state),
+            )),
+        }
+    }
+
+    fn poll_interval(&self) -> Duration {
+        #[cfg(test)]
+        if let Some(interval) = self.poll_interval {
+            return interval;
+        }
+        POLL_INTERVAL
+    }
+
+    /// Returns true for oneshot services that become inactive again once their process exited.
+    fn is_transient_oneshot(&self) -> bool {
+        match self.config {
+            UnitFile::Service(ref config) => {
+                matches!(config.service.service_type, Some(ServiceType::Oneshot))
+                    && config.service.remain_after_exit != Some(true)
+            }
+            _ => false,
+        }
+    }
+
+    /// Builds the error of a unit not reaching the expected state, from `message`, the last
+    /// state of the unit and its last log lines, as far as they can be read.
+    fn diagnostic(&self, message: String, state: Option<&UnitState>) -> Error {
+        let mut message = message;
+        if let Some(state) = state {
+            message.push_str(&format!(" (state: {})", state));
+        }
+        if let Ok(logs) = self.recent_logs(DIAGNOSTIC_LOG_LINES)
+            && !logs.trim().is_empty()
+        {
+            message.push_str("\nLast log lines:\n");
+            message.push_str(logs.trim_end());
+        }
+        Error::CommandError(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systemd::state::PROPERTIES;
+    use crate::systemd::{CommandOutput, Config, MockRunner};
+    use std::sync::Arc;
+
+    /// `systemctl show` output of the demo service in `active_state`.
+    fn show(active_state: &str, sub_state: &str, result: &str) -> CommandOutput {
+        CommandOutput::success(format!(
+            "MainPID=0\nExecMainCode=0\nExecMainStatus=0\nNRestarts=0\nResult={}\n\
+             LoadState=loaded\nActiveState={}\nSubState={}\nUnitFileState=enabled\n",
+            result, active_state, sub_state
+        ))
+    }
+
+    /// Expects `count` reads of the unit state, answered with `output`.
+    fn expect_show(runner: MockRunner, count: usize, output: CommandOutput) -> MockRunner {
+        let properties = format!("--property={}", PROPERTIES.join(","));
+        (0..count).fold(runner, |runner, _| {
+            runner.expect(
+                "systemctl",
+                &["show", &properties, "demo.service"],
+                output.clone(),
+            )
+        })
+    }
+
+    fn logs(runner: MockRunner, output: CommandOutput) -> MockRunner {
+        runner.expect(
+            "journalctl",
+            &["-u", "demo.service", "-n", "10", "--no-pager"],
+            output,
+        )
+    }
+
+    fn systemd(runner: &Arc<MockRunner>) -> Systemd {
+        Systemd::new(Config::new("demo")).runner(Arc::clone(runner))
+    }
+
+    #[test]
+    fn start_and_wait_until_active() {
+        let runner = MockRunner::new().expect(
+            "systemctl",
+            &["start", "demo.service"],
+            CommandOutput::success(""),
+        );
+        let runner = expect_show(runner, 2, show("activating", "start", "success"));
+        let runner = Arc::new(expect_show(runner, 1, show("active", "running", "success")));
+
+        let state = systemd(&runner)
+            .with_poll_interval(Duration::ZERO)
+            .start_and_wait(Duration::from_secs(5))
+            .unwrap();
+
+        runner.assert_done();
+        assert_eq!(state.active_state, ActiveState::Active);
+    }
+
+    #[test]
+    fn wait_fails_early_with_log_lines() {
+        let runner = expect_show(MockRunner::new(), 1, show("activating", "start", ""));
+        let runner = expect_show(runner, 1, show("failed", "failed", "exit-code"));
+        let runner = Arc::new(logs(
+            runner,
+            CommandOutput::success("demo: starting\ndemo: cannot open config\n"),
+        ));
+
+        let error = systemd(&runner)
+            .with_poll_interval(Duration::ZERO)
+            .wait_for(ActiveState::Active, Duration::from_secs(5))
+            .unwrap_err();
+
+        runner.assert_done();
+        assert_eq!(
+            error.to_string(),
+            "Command Error: Unit demo.service failed while waiting for it to be active \
+             (state: failed (failed), result exit-code)\n\
+             Last log lines:\ndemo: starting\ndemo: cannot open config"
+        );
+    }
+
+    #[test]
+    fn wait_times_out() {
+        // The sleep after the first read lasts until the deadline, so the state is read once
+        // more after the deadline passed, however long the reads take
+        let runner = expect_show(MockRunner::new(), 2, show("activating", "auto-restart", ""));
+        let runner = Arc::new(logs(runner, CommandOutput::failure(1, "No journal files")));
+        let started = Instant::now();
+
+        let error = systemd(&runner)
+            .with_poll_interval(Duration::from_secs(3600))
+            .wait_for(ActiveState::Active, Duration::from_millis(250))
+            .unwrap_err();
+
+        runner.assert_done();
+        assert!(started.elapsed() >= Duration::from_millis(250));
+        // Logs that cannot be read are left out
+        assert_eq!(
+            error.to_string(),
+            "Command Error: Unit demo.service did not become active within 250ms \
+             (state: activating (auto-restart))"
+        );
+    }
+
+    #[test]
+    fn wait_with_an_elapsed_deadline_reads_once() {
+        let runner = expect_show(MockRunner::new(), 1, show("activating", "start", ""));
+        let runner = Arc::new(logs(runner, CommandOutput::success("")));
+
+        let error = systemd(&runner)
+            .wait_for(ActiveState::Active, Duration::ZERO)
+            .unwrap_err();
+
+        runner.assert_done();
+        assert_eq!(
+            error.to_string(),
+            "Command Error: Unit demo.service did not become active within 0ns \
+             (state: activating (start))"
+        );
+    }
+}